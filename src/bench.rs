@@ -0,0 +1,374 @@
+//! Benchmark of the solvers over a set of instances.
+//!
+//! Each run is executed in a child process (see [`run_isolated`]) so that a panic or a solver that does not stop
+//! at the time limit does not end the whole benchmark. The child runs [`solve_instance`] and writes its result
+//! on its standard output as a report (see [`BenchRun::to_report`]), which is read back by the parent.
+//! The results are then recorded in the instance database by `mvc bench`.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use petgraph::prelude::UnGraphMap;
+
+use crate::{BnbOptions, branch_and_bound_with, check_vertex_cover, Clock, Limits, naive_search};
+use crate::database::{Problem, RunStatus};
+use crate::errors::MvcError;
+use crate::graph_utils::{complement, is_vertex_cover};
+
+/// Prefix of the lines of a report, to tell them apart from what the solver prints.
+const REPORT_PREFIX: &str = "@bench";
+/// Time given to a child after the time limit before it is killed.
+pub const KILL_GRACE: Duration = Duration::from_secs(10);
+
+/// Solver that can be benchmarked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Solver {
+    /// Branch and bound on the graph (minimum vertex cover)
+    BranchAndBound,
+    /// Branch and bound on the complement of the graph (maximum clique)
+    Clique,
+    /// Naive search on the graph (minimum vertex cover)
+    NaiveSearch,
+}
+
+impl Solver {
+    pub const ALL: [Solver; 3] = [Solver::BranchAndBound, Solver::Clique, Solver::NaiveSearch];
+
+    /// Name of the solver, used on the command line and as the algorithm of the runs in the database.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Solver::BranchAndBound => "bnb",
+            Solver::Clique => "clique",
+            Solver::NaiveSearch => "naive_search",
+        }
+    }
+
+    /// Name of the algorithm of the runs of the solver in the database : the name of the solver, followed by the
+    /// options of the branch and bound when they are not the default ones (ex: `bnb:mirror+degree-two`).
+    pub fn algorithm(&self, bnb: &BnbOptions) -> String {
+        match self {
+            Solver::BranchAndBound | Solver::Clique if *bnb != BnbOptions::default() => format!("{}:{}", self.name(), bnb),
+            _ => self.name().to_string(),
+        }
+    }
+
+    /// Problem solved by the solver.
+    pub fn problem(&self) -> Problem {
+        match self {
+            Solver::BranchAndBound | Solver::NaiveSearch => Problem::Mvc,
+            Solver::Clique => Problem::Clique,
+        }
+    }
+}
+
+impl fmt::Display for Solver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Solver {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Solver::ALL.iter()
+            .find(|solver| solver.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown solver {:?} (expecting one of bnb, clique, naive_search)", s))
+    }
+}
+
+/// Result of a run of a solver on an instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchRun {
+    /// Value found for the problem of the solver (none if it stopped before finding a solution)
+    pub value: Option<u64>,
+    pub time: Duration,
+    /// Whether the solver stopped because it reached the time limit
+    pub is_time_limit: bool,
+    /// Time spent in each subroutine, in seconds, and number of subtrees pruned by each rule (`pruned_<rule>`)
+    pub stats: BTreeMap<String, f64>,
+}
+
+impl BenchRun {
+    /// Writes the run as a report, one `@bench <key> <value>` line per field. A run without solution has the value
+    /// `none`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use std::time::Duration;
+    /// use vertex::bench::BenchRun;
+    ///
+    /// let run = BenchRun {
+    ///     value: Some(3),
+    ///     time: Duration::from_micros(1500),
+    ///     is_time_limit: false,
+    ///     stats: BTreeMap::from([("deg_lb".to_string(), 0.25)]),
+    /// };
+    /// let output = format!("Some output of the solver\n{}", run.to_report());
+    /// assert_eq!(BenchRun::from_report(&output), Some(run));
+    /// ```
+    pub fn to_report(&self) -> String {
+        let value = self.value.map_or("none".to_string(), |v| v.to_string());
+        let mut report = format!("{p} value {}\n{p} time_us {}\n{p} time_limit {}\n",
+                                 value, self.time.as_micros(), self.is_time_limit, p = REPORT_PREFIX);
+        for (name, value) in &self.stats {
+            report.push_str(&format!("{} stat {} {}\n", REPORT_PREFIX, name, value));
+        }
+        report
+    }
+
+    /// Reads a run from the output of a child. Returns None if the report is missing or incomplete.
+    pub fn from_report(output: &str) -> Option<BenchRun> {
+        let (mut value, mut time, mut is_time_limit) = (None, None, None);
+        let mut stats = BTreeMap::new();
+        for line in output.lines() {
+            let mut tokens = line.split_whitespace();
+            if tokens.next() != Some(REPORT_PREFIX) {
+                continue;
+            }
+            match (tokens.next(), tokens.next(), tokens.next()) {
+                (Some("value"), Some("none"), None) => value = Some(None),
+                (Some("value"), Some(v), None) => value = v.parse().ok().map(Some),
+                (Some("time_us"), Some(v), None) => time = v.parse().ok().map(Duration::from_micros),
+                (Some("time_limit"), Some(v), None) => is_time_limit = v.parse().ok(),
+                (Some("stat"), Some(name), Some(v)) => {
+                    stats.insert(name.to_string(), v.parse().ok()?);
+                }
+                _ => return None,
+            }
+        }
+        Some(BenchRun {
+            value: value?,
+            time: time?,
+            is_time_limit: is_time_limit?,
+            stats,
+        })
+    }
+}
+
+/// Runs a solver on a graph in the current process. The branch and bound solvers use the options `bnb`.
+///
+/// The solution is checked when the solver did not reach the time limit, so that a wrong solution makes the run fail.
+/// A solver stopped before finding a solution gives no value.
+///
+/// # Throws
+/// - MvcError::InvalidResult if the solution found by the solver is not valid
+/// - the error returned by the solver
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::{BnbOptions, Branching};
+/// use vertex::bench::{Solver, solve_instance};
+/// use vertex::graph_utils::load_clq_file;
+///
+/// let graph = load_clq_file("src/resources/graphs/test.clq").unwrap();
+/// let bnb = BnbOptions::default();
+/// assert_eq!(solve_instance(Solver::BranchAndBound, &graph, Duration::from_secs(60), &bnb).unwrap().value, Some(3));
+/// assert_eq!(solve_instance(Solver::Clique, &graph, Duration::from_secs(60), &bnb).unwrap().value, Some(3));
+/// let mirror = bnb.with_branching(Branching::Mirror);
+/// assert_eq!(solve_instance(Solver::BranchAndBound, &graph, Duration::from_secs(60), &mirror).unwrap().value, Some(3));
+/// ```
+pub fn solve_instance(solver: Solver, graph: &UnGraphMap<u64, ()>, limit: Duration, bnb: &BnbOptions)
+                      -> Result<BenchRun, MvcError> {
+    let mut clock = Clock::with_limits(Limits::new(limit));
+    let value = match solver {
+        Solver::BranchAndBound | Solver::NaiveSearch => {
+            let res = if solver == Solver::BranchAndBound {
+                branch_and_bound_with(graph, &mut clock, bnb)
+            } else {
+                naive_search(graph, &mut clock)
+            };
+            match res {
+                Err(MvcError::Timeout(_)) => None,
+                res => {
+                    let res = res?;
+                    if !clock.is_time_up() {
+                        check_vertex_cover(graph, &res)?;
+                    }
+                    is_vertex_cover(graph, &res.1).then_some(res.0)
+                }
+            }
+        }
+        Solver::Clique => {
+            let g = complement(graph);
+            match branch_and_bound_with(&g, &mut clock, bnb) {
+                Err(MvcError::Timeout(_)) => None,
+                res => {
+                    let res = res?;
+                    if !clock.is_time_up() {
+                        check_vertex_cover(&g, &res)?;
+                    }
+                    is_vertex_cover(&g, &res.1).then(|| graph.node_count() as u64 - res.0)
+                }
+            }
+        }
+    };
+    clock.stop_timer();
+    let mut stats: BTreeMap<String, f64> = clock.subroutines().into_iter()
+        .map(|(name, d)| (name, d.as_secs_f64()))
+        .collect();
+    stats.extend(clock.pruned().iter().map(|(rule, subtrees)| (format!("pruned_{}", rule), *subtrees as f64)));
+    Ok(BenchRun {
+        value,
+        time: clock.get_time().duration,
+        is_time_limit: clock.is_time_up(),
+        stats,
+    })
+}
+
+/// Outcome of a run executed in a child process.
+#[derive(Debug)]
+pub struct IsolatedRun {
+    pub status: RunStatus,
+    /// The result reported by the child, if any
+    pub run: Option<BenchRun>,
+    /// Wall-clock time of the child process
+    pub wall_time: Duration,
+    /// Standard error of the child (ex: the message of a panic)
+    pub stderr: String,
+}
+
+/// Runs a command expected to print a report (see [`BenchRun::to_report`]) and kills it if it is still running
+/// after the deadline.
+///
+/// The status is [`RunStatus::Killed`] if the child was killed, [`RunStatus::Crashed`] if it failed or did not print
+/// a complete report, and is given by the report otherwise.
+///
+/// # Throws
+/// io::Error if the command cannot be started.
+pub fn run_isolated(command: &mut Command, deadline: Duration) -> io::Result<IsolatedRun> {
+    let start = Instant::now();
+    let mut child = command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+
+    // The pipes are read in threads so that a verbose child never blocks on a full pipe
+    let read = |mut pipe: Box<dyn Read + Send>| std::thread::spawn(move || {
+        let mut content = String::new();
+        let _ = pipe.read_to_string(&mut content);
+        content
+    });
+    let stdout = read(Box::new(child.stdout.take().unwrap()));
+    let stderr = read(Box::new(child.stderr.take().unwrap()));
+
+    let mut killed = false;
+    let exit = loop {
+        if let Some(exit) = child.try_wait()? {
+            break exit;
+        }
+        if start.elapsed() >= deadline {
+            let _ = child.kill();
+            killed = true;
+            break child.wait()?;
+        }
+        sleep(Duration::from_millis(10));
+    };
+    let wall_time = start.elapsed();
+    let run = BenchRun::from_report(&stdout.join().unwrap_or_default());
+    let stderr = stderr.join().unwrap_or_default();
+
+    let status = match &run {
+        _ if killed => RunStatus::Killed,
+        Some(run) if exit.success() => {
+            if run.is_time_limit { RunStatus::TimeLimit } else { RunStatus::Solved }
+        }
+        _ => RunStatus::Crashed,
+    };
+    Ok(IsolatedRun { status, run, wall_time, stderr })
+}
+
+/// Returns the graph files of a benchmark.
+///
+/// The path is either a folder, whose `.clq` and `.col` files are returned sorted by name, or a list file with one
+/// path per line. In a list file, empty lines and lines starting with `#` are ignored and the relative paths are
+/// resolved from `instance_dir`.
+///
+/// # Throws
+/// io::Error if the folder or the list file cannot be read.
+///
+/// # Example
+/// ```rust
+/// use std::path::Path;
+/// use vertex::bench::instance_list;
+///
+/// let instances = instance_list(Path::new("src/resources/graphs"), Path::new("src/resources/graphs")).unwrap();
+/// assert!(instances.iter().any(|p| p.ends_with("test.clq")));
+/// ```
+pub fn instance_list(path: &Path, instance_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    if path.is_dir() {
+        let mut instances = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?.path();
+            if matches!(entry.extension().and_then(|e| e.to_str()), Some("clq") | Some("col")) {
+                instances.push(entry);
+            }
+        }
+        instances.sort();
+        Ok(instances)
+    } else {
+        Ok(std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| instance_dir.join(line))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod bench_tests {
+    use super::*;
+
+    #[test]
+    fn test_incomplete_report() {
+        assert_eq!(BenchRun::from_report("no report"), None);
+        assert_eq!(BenchRun::from_report("@bench value 3\n@bench time_us 12\n"), None);
+        assert_eq!(BenchRun::from_report("@bench value x\n@bench time_us 12\n@bench time_limit false\n"), None);
+        assert!(BenchRun::from_report("@bench value 3\n@bench time_us 12\n@bench time_limit false\n").is_some());
+        let stopped = BenchRun::from_report("@bench value none\n@bench time_us 12\n@bench time_limit true\n");
+        assert_eq!(stopped.map(|run| run.value), Some(None));
+    }
+
+    #[test]
+    fn test_solve_at_time_limit() {
+        let graph = crate::graph_utils::load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+        for solver in Solver::ALL {
+            let run = solve_instance(solver, &graph, Duration::ZERO, &BnbOptions::default()).unwrap();
+            assert!(run.is_time_limit, "{}", solver);
+        }
+    }
+
+    #[test]
+    fn test_instance_list_file() {
+        let path = std::env::temp_dir().join(format!("mvc_bench_list_{}.txt", std::process::id()));
+        std::fs::write(&path, "# small graphs\ntest.clq\n\n  myciel3.clq\n").unwrap();
+        let instances = instance_list(&path, Path::new("graphs")).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(instances, vec![PathBuf::from("graphs/test.clq"), PathBuf::from("graphs/myciel3.clq")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_isolated() {
+        let report = "@bench value 3\n@bench time_us 12\n@bench time_limit false";
+        let solved = run_isolated(Command::new("echo").arg(report), Duration::from_secs(10)).unwrap();
+        assert_eq!(solved.status, RunStatus::Solved);
+        assert_eq!(solved.run.unwrap().value, Some(3));
+
+        let crashed = run_isolated(Command::new("sh").args(["-c", "echo boom >&2; exit 101"]),
+                                   Duration::from_secs(10)).unwrap();
+        assert_eq!(crashed.status, RunStatus::Crashed);
+        assert_eq!(crashed.stderr.trim(), "boom");
+
+        let killed = run_isolated(Command::new("sleep").arg("10"), Duration::from_millis(100)).unwrap();
+        assert_eq!(killed.status, RunStatus::Killed);
+        assert!(killed.wall_time < Duration::from_secs(5));
+    }
+}
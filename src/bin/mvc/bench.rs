@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use vertex::bench::{instance_list, KILL_GRACE, run_isolated, Solver, solve_instance};
+use vertex::cli::{Args, EXIT_FAILURE, EXIT_SUCCESS, graph_id, load_graph};
+use vertex::database::{InstanceDatabase, InstanceRecord, RunRecord, RunStatus};
+use vertex::{BnbOptions, ElapseTime};
+use vertex::errors::CliError;
+use vertex::workspace::Workspace;
+
+pub const BENCH_USAGE: &str = "Usage: mvc bench [<folder | list_file>] [--solver <bnb | clique | naive_search>]... \
+[--time-limit <seconds>] [--comment <text>] [--branching <max-degree | mirror | satellite | overlap>] [--degree-two] \
+[--dominance] [--symmetry]\n\
+Each run is executed in a separate process and recorded in the instance database. Exits with code 1 if a run \
+crashed, was killed or found a value different from the known optimal value. The branch and bound solvers run with the \
+given branching strategy and pruning rules, and their runs are recorded with the options in the algorithm name (ex: bnb:mirror).";
+
+/// Runs a single solver on a single instance and prints the report. Used by the child processes of the benchmark.
+pub fn run_child(args: &[String]) -> Result<i32, CliError> {
+    let usage = "Usage: mvc bench --child <solver> <path> <time_limit> [--branching <strategy>] [--degree-two] \
+[--dominance] [--symmetry]";
+    let options = Args::parse(args, &[("--branching", 1), ("--degree-two", 0), ("--dominance", 0), ("--symmetry", 0)],
+                              usage)?;
+    let positional = options.expect_positional(3, 3)?;
+    let solver: Solver = positional[0].parse().map_err(|e: String| options.usage_error(&e))?;
+    let limit = positional[2].parse::<f64>().ok().and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| options.usage_error(&format!("Invalid time limit {:?}", positional[2])))?;
+    let graph = load_graph(Path::new(&positional[1]))?;
+    print!("{}", solve_instance(solver, &graph, limit, &options.parse_bnb_options()?)?.to_report());
+    Ok(EXIT_SUCCESS)
+}
+
+/// Runs the solvers on every instance of a folder or a list file in child processes and records the runs.
+pub fn bench(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[("--solver", 1), ("--time-limit", 1), ("--comment", 1), ("--branching", 1),
+        ("--degree-two", 0), ("--dominance", 0), ("--symmetry", 0)], BENCH_USAGE)?;
+    let positional = options.expect_positional(0, 1)?;
+    let mut solvers = Vec::new();
+    for values in options.all("--solver") {
+        solvers.push(values[0].parse::<Solver>().map_err(|e| options.usage_error(&e))?);
+    }
+    if solvers.is_empty() {
+        solvers.push(Solver::BranchAndBound);
+    }
+    let time_limit = options.parse_duration("--time-limit", Duration::from_secs(60))?;
+    let comment = options.value("--comment").unwrap_or("");
+    let bnb = options.parse_bnb_options()?;
+
+    let source = positional.first().map_or_else(|| ws.instance_dir.clone(), |p| p.into());
+    let instances = instance_list(&source, &ws.instance_dir).map_err(|e| CliError::IoError(
+        format!("Unable to read the instances of {:?} : {}", source, e), format!("{:?}", e)))?;
+    let exe = env::current_exe()?;
+    let deadline = time_limit + KILL_GRACE;
+
+    let mut summary: BTreeMap<String, usize> = BTreeMap::new();
+    let mut failed = false;
+    for path in instances {
+        for &solver in &solvers {
+            let (status, ok) = bench_instance(ws, &exe, &path, solver, &bnb, time_limit, comment, deadline);
+            *summary.entry(status).or_insert(0) += 1;
+            failed |= !ok;
+        }
+    }
+
+    let summary: Vec<String> = summary.iter().map(|(status, count)| format!("{} {}", count, status)).collect();
+    println!("Benchmark done : {}", summary.join(", "));
+    Ok(if failed { EXIT_FAILURE } else { EXIT_SUCCESS })
+}
+
+/// Runs a solver on an instance in a child process and records the run.
+/// Returns the status of the run (or "error") and whether it succeeded.
+#[allow(clippy::too_many_arguments)]
+fn bench_instance(ws: &Workspace, exe: &Path, path: &Path, solver: Solver, bnb: &BnbOptions, time_limit: Duration,
+                  comment: &str, deadline: Duration) -> (String, bool) {
+    let id = graph_id(path);
+    let algorithm = solver.algorithm(bnb);
+    let format = path.extension().unwrap_or_default().to_string_lossy().to_string();
+    let graph = match load_graph(path) {
+        Ok(graph) => graph,
+        Err(e) => {
+            println!("{:<24} {:<12} {}", id, algorithm, e);
+            return ("error".to_string(), false);
+        }
+    };
+
+    let mut command = Command::new(exe);
+    command.arg("bench").arg("--child").arg(solver.name()).arg(path).arg(time_limit.as_secs_f64().to_string())
+        .arg("--branching").arg(bnb.branching.name());
+    for (flag, enabled) in [("--degree-two", bnb.degree_two), ("--dominance", bnb.dominance), ("--symmetry", bnb.symmetry)] {
+        if enabled {
+            command.arg(flag);
+        }
+    }
+    let outcome = match run_isolated(&mut command, deadline) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            println!("{:<24} {:<12} error while starting the run : {}", id, algorithm, e);
+            return ("error".to_string(), false);
+        }
+    };
+
+    let (value, time, stats) = match &outcome.run {
+        Some(run) => (run.value, run.time, run.stats.clone()),
+        None => (None, outcome.wall_time, BTreeMap::new()),
+    };
+    let mut run = RunRecord::new(solver.problem(), &algorithm, value.unwrap_or(0), time,
+                                 outcome.status != RunStatus::Solved, comment);
+    run.value = value;
+    run.status = outcome.status;
+    run.stats = stats;
+
+    let mut record = InstanceRecord::from_graph(&id, &format, &graph);
+    record.runs.push(run);
+    let recorded = InstanceDatabase::update(&ws.database_path(), |db| {
+        let is_optimal = match db.get(&id) {
+            Some(stored) => {
+                stored.check_fingerprint(record.hash.as_ref().unwrap())?;
+                stored.known(solver.problem()).map(|k| Some(k.value) == value)
+            }
+            None => None,
+        };
+        db.add_record(record);
+        Ok(is_optimal)
+    });
+
+    let mut ok = matches!(outcome.status, RunStatus::Solved | RunStatus::TimeLimit);
+    match recorded {
+        Ok(is_optimal) => {
+            let optimal = match (outcome.status, is_optimal) {
+                (RunStatus::Solved, Some(true)) => "optimal",
+                (RunStatus::Solved, Some(false)) => {
+                    ok = false;
+                    "NOT OPTIMAL"
+                }
+                _ => "",
+            };
+            println!("{:<24} {:<12} {:<10} value {:<6} time {} {}", id, algorithm, outcome.status,
+                     value.map_or("none".to_string(), |v| v.to_string()),
+                     ElapseTime::new(time), optimal);
+        }
+        Err(e) => {
+            println!("{:<24} {:<12} error while recording the run : {}", id, algorithm, e);
+            return ("error".to_string(), false);
+        }
+    }
+    if outcome.status == RunStatus::Crashed {
+        if let Some(line) = outcome.stderr.lines().rev().find(|l| !l.trim().is_empty()) {
+            println!("    {}", line.trim());
+        }
+    }
+    (outcome.status.to_string(), ok)
+}
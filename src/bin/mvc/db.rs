@@ -0,0 +1,195 @@
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+
+use vertex::cli::{Args, EXIT_FAILURE, EXIT_SUCCESS, graph_id, load_graph, write_output};
+use vertex::compare::{compare_runs, CompareOptions, InstanceComparison, RunSelector};
+use vertex::database::{InstanceDatabase, InstanceRecord, RunRecord};
+use vertex::ElapseTime;
+use vertex::errors::CliError;
+use vertex::report::{Report, Series};
+use vertex::workspace::Workspace;
+
+pub const DB_USAGE: &str = "Usage: mvc db <add | migrate | show | compare | report> ...\n\
+\x20 mvc db add [<folder>]                   Adds the graphs of a folder (default : the instances) to the database\n\
+\x20 mvc db migrate                          Merges the legacy graph_data.yml, clique_data.yml and time_result.yml \
+files into the database\n\
+\x20 mvc db show <id>                        Prints the record of an instance\n\
+\x20 mvc db compare <baseline> <candidate> [--max-slowdown <ratio>]\n\
+\x20                                         Compares two sets of runs, exits with code 1 on a regression\n\
+\x20 mvc db report [--format <markdown | html>] [--output <file>] [--series <label> <selector>]... \
+[--cactus <file.svg>] [--profile <file.svg>] [--title <title>]\n\
+\x20                                         Writes a benchmark report, with one series per algorithm by default\n\
+A selector is a comment or a comma separated list of comment=<text>, date=<prefix>, date=<from>..<to>, algorithm=<name>";
+
+/// Runs a subcommand on the instance database.
+pub fn db(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(CliError::Usage("Missing subcommand".to_string(), DB_USAGE.to_string())),
+    };
+    match command {
+        "add" => add(ws, rest),
+        "migrate" => migrate(ws, rest),
+        "show" => show(ws, rest),
+        "compare" => compare(ws, rest),
+        "report" => report(ws, rest),
+        _ => Err(CliError::Usage(format!("Unknown subcommand {:?}", command), DB_USAGE.to_string())),
+    }
+}
+
+/// Adds every DIMACS graph of a folder to the database.
+fn add(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[], DB_USAGE)?;
+    let folder = options.expect_positional(0, 1)?.first().map_or_else(|| ws.instance_dir.clone(), PathBuf::from);
+    let mut paths: Vec<PathBuf> = read_dir(&folder)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "clq" || e == "col"))
+        .collect();
+    paths.sort();
+
+    let mut graphs = Vec::new();
+    for path in paths {
+        let graph = load_graph(&path)?;
+        println!("{:?}: {} vertices, {} edges", path, graph.node_count(), graph.edge_count());
+        let format = path.extension().unwrap_or_default().to_string_lossy().to_string();
+        graphs.push(InstanceRecord::from_graph(&graph_id(&path), &format, &graph));
+    }
+    let count = graphs.len();
+    InstanceDatabase::update(&ws.database_path(), |db| {
+        for record in graphs {
+            db.add_record(record);
+        }
+        Ok(())
+    })?;
+    println!("{} graphs added to {}", count, ws.database_path());
+    Ok(EXIT_SUCCESS)
+}
+
+/// Migrates the legacy graph_data.yml, clique_data.yml and time_result.yml files into the database. An existing
+/// database is not replaced : the legacy records are merged into it (see [`InstanceDatabase::merge`]).
+fn migrate(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    Args::parse(args, &[], DB_USAGE)?.expect_positional(0, 0)?;
+    let mut migrated = InstanceDatabase::migrate(&ws.graph_data.to_string_lossy(), &ws.clique_data.to_string_lossy(),
+                                                 &ws.time_result.to_string_lossy())?;
+    // The graphs are loaded before the database is locked
+    for id in migrated.fill_fingerprints(&ws.instance_dir) {
+        println!("No graph file for {:?} : its fingerprint is not stored", id);
+    }
+    let path = ws.database_path();
+    if !Path::new(&path).exists() {
+        migrated.save(&path)?;
+        println!("{} instances written to {}", migrated.instances().len(), path);
+        return Ok(EXIT_SUCCESS);
+    }
+    let (instances, runs) = InstanceDatabase::update(&path, |db| {
+        let runs = db.merge(migrated);
+        Ok((db.instances().len(), runs))
+    })?;
+    println!("Legacy files merged into {} : {} instances, {} runs added", path, instances, runs);
+    Ok(EXIT_SUCCESS)
+}
+
+/// Prints the record of an instance in the YAML format of the database.
+fn show(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[], DB_USAGE)?;
+    let id = &options.expect_positional(1, 1)?[0];
+    let db = InstanceDatabase::load(&ws.database_path())?;
+    match db.get(id) {
+        Some(record) => {
+            let content = serde_yaml::to_string(record)
+                .map_err(|e| CliError::IoError("Unable to serialize the record".to_string(), e.to_string()))?;
+            write_output(None, &content)?;
+            Ok(EXIT_SUCCESS)
+        }
+        None => {
+            println!("No instance {:?} in the database", id);
+            Ok(EXIT_FAILURE)
+        }
+    }
+}
+
+/// Compares two sets of runs of the database. Exits with code 1 if the candidate has a regression.
+fn compare(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let parsed = Args::parse(args, &[("--max-slowdown", 1)], DB_USAGE)?;
+    let positional = parsed.expect_positional(2, 2)?;
+    let selector = |s: &str| s.parse::<RunSelector>().map_err(|e| parsed.usage_error(&e));
+    let (baseline, candidate) = (selector(&positional[0])?, selector(&positional[1])?);
+    let mut options = CompareOptions::default();
+    if let Some(ratio) = parsed.value("--max-slowdown") {
+        let ratio = ratio.parse::<f64>().ok().filter(|r| *r >= 1.0)
+            .ok_or_else(|| parsed.usage_error("--max-slowdown expects a ratio >= 1"))?;
+        options.max_slowdown = Some(ratio);
+    }
+
+    let db = InstanceDatabase::load(&ws.database_path())?;
+    let comparisons = compare_runs(&db, &baseline, &candidate, options);
+    if comparisons.is_empty() {
+        return Err(parsed.usage_error("No run matches the selectors"));
+    }
+    print_comparisons(&comparisons);
+    Ok(if comparisons.iter().any(InstanceComparison::is_regression) { EXIT_FAILURE } else { EXIT_SUCCESS })
+}
+
+fn describe(run: &Option<RunRecord>) -> String {
+    match run {
+        Some(run) => format!("{} ({}, {})", run.value.map_or("none".to_string(), |v| v.to_string()), run.status,
+                             ElapseTime::new(run.time)),
+        None => "-".to_string(),
+    }
+}
+
+fn print_comparisons(comparisons: &[InstanceComparison]) {
+    println!("{:<24} {:<7} {:<34} {:<34} {:>8}  changes", "instance", "problem", "baseline", "candidate", "speedup");
+    for c in comparisons {
+        let speedup = c.speedup.map_or("-".to_string(), |s| format!("{:.2}x", s));
+        let changes: Vec<String> = c.changes.iter().map(|change| change.to_string()).collect();
+        println!("{:<24} {:<7} {:<34} {:<34} {:>8}  {}", c.id, c.problem,
+                 describe(&c.baseline), describe(&c.candidate), speedup, changes.join(", "));
+    }
+
+    // Geometric mean of the speedups of the instances solved by both runs
+    let speedups: Vec<f64> = comparisons.iter().filter_map(|c| c.speedup).filter(|s| *s > 0.0).collect();
+    if !speedups.is_empty() {
+        let mean = (speedups.iter().map(|s| s.ln()).sum::<f64>() / speedups.len() as f64).exp();
+        println!("\nMean speedup on {} instances solved by both : {:.2}x", speedups.len(), mean);
+    }
+    let regressions: Vec<&str> = comparisons.iter().filter(|c| c.is_regression()).map(|c| c.id.as_str()).collect();
+    if regressions.is_empty() {
+        println!("No regression");
+    } else {
+        println!("{} regression(s) : {}", regressions.len(), regressions.join(", "));
+    }
+}
+
+/// Writes a Markdown or HTML report of the benchmarks, with optional SVG plots.
+fn report(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[("--format", 1), ("--output", 1), ("--series", 2), ("--cactus", 1),
+        ("--profile", 1), ("--title", 1)], DB_USAGE)?;
+    options.expect_positional(0, 0)?;
+    let html = match options.value("--format").unwrap_or("markdown") {
+        "markdown" | "md" => false,
+        "html" => true,
+        format => return Err(options.usage_error(&format!("Unknown format {:?}", format))),
+    };
+    let mut series = Vec::new();
+    for values in options.all("--series") {
+        let selector = values[1].parse::<RunSelector>().map_err(|e| options.usage_error(&e))?;
+        series.push(Series::new(&values[0], selector));
+    }
+
+    let db = InstanceDatabase::load(&ws.database_path())?;
+    if series.is_empty() {
+        series = Report::default_series(&db);
+    }
+    let report = Report::new(&db, &series);
+    let title = options.value("--title").unwrap_or("Benchmark report");
+    let content = if html { report.to_html(title) } else { report.to_markdown() };
+    write_output(options.value("--output"), &content)?;
+    if let Some(path) = options.value("--cactus") {
+        write_output(Some(path), &report.cactus_plot_svg())?;
+    }
+    if let Some(path) = options.value("--profile") {
+        write_output(Some(path), &report.performance_profile_svg())?;
+    }
+    Ok(EXIT_SUCCESS)
+}
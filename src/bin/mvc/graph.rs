@@ -0,0 +1,149 @@
+use petgraph::prelude::UnGraphMap;
+
+use vertex::cli::{Args, EXIT_SUCCESS, graph_path, load_graph, write_output};
+use vertex::database::{InstanceDatabase, InstanceRecord, KnownValue, Problem};
+use vertex::errors::{CliError, MvcError};
+use vertex::families::Family;
+use vertex::generators::{barabasi_albert, gnm, gnp, hidden_clique, planted_cover, random_geometric, random_regular};
+use vertex::graph_utils::{graph_to_dot, graph_to_graphml, graph_to_string, GraphStats};
+use vertex::workspace::Workspace;
+
+pub const CONVERT_USAGE: &str = "Usage: mvc convert <graph> [--format <dimacs | dot | graphml>] [--output <file>]\n\
+The graph can be a DIMACS file (.clq, .col).";
+
+pub const STATS_USAGE: &str = "Usage: mvc stats <graph> [--format <text | yaml>] [--output <file>]";
+
+pub const GENERATE_USAGE: &str = "Usage: mvc generate <family | kind> [<parameters>] [--seed <n>] \
+[--format <dimacs | dot | graphml>] [--output <file>] [--add]\n\
+Families : hamming<bits>-<distance>, johnson<bits>-<weight>-<distance>, keller<n>, myciel<k>, queen<rows>_<cols>, \
+MANN_a<3^k>, p_hat<order>-<1 | 2 | 3>\n\
+Random kinds : gnp <n> <p>, gnm <n> <m>, regular <n> <d>, ba <n> <m>, geometric <n> <radius>, \
+planted-cover <n> <k> <p>, hidden-clique <n> <p> <k> [--hide]\n\
+With --add, the graph is written in the instance folder of the workspace and added to the database with its known \
+optimal values.";
+
+/// Writes a graph in the given format.
+fn format_graph(graph: &UnGraphMap<u64, ()>, format: &str, options: &Args) -> Result<String, CliError> {
+    match format {
+        "dimacs" => Ok(graph_to_string(graph)),
+        "dot" => Ok(graph_to_dot(graph, None)),
+        "graphml" => Ok(graph_to_graphml(graph, None)),
+        _ => Err(options.usage_error(&format!("Unknown format {:?}", format))),
+    }
+}
+
+/// Converts a graph to another format.
+pub fn convert(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[("--format", 1), ("--output", 1)], CONVERT_USAGE)?;
+    let graph = load_graph(&graph_path(ws, &options.expect_positional(1, 1)?[0]))?;
+    let content = format_graph(&graph, options.value("--format").unwrap_or("dimacs"), &options)?;
+    write_output(options.value("--output"), &content)?;
+    Ok(EXIT_SUCCESS)
+}
+
+/// Prints the statistics of a graph.
+pub fn stats(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[("--format", 1), ("--output", 1)], STATS_USAGE)?;
+    let graph = load_graph(&graph_path(ws, &options.expect_positional(1, 1)?[0]))?;
+    let stats = GraphStats::new(&graph);
+    let content = match options.value("--format").unwrap_or("text") {
+        "text" => format!("{}\n", stats),
+        "yaml" => serde_yaml::to_string(&stats)
+            .map_err(|e| CliError::IoError("Unable to serialize the statistics".to_string(), e.to_string()))?,
+        format => return Err(options.usage_error(&format!("Unknown format {:?}", format))),
+    };
+    write_output(options.value("--output"), &content)?;
+    Ok(EXIT_SUCCESS)
+}
+
+/// Generated graph along with its id and its known optimal values.
+struct Generated {
+    id: String,
+    graph: UnGraphMap<u64, ()>,
+    family: Option<Family>,
+    known: Vec<(Problem, KnownValue)>,
+}
+
+/// Generates a graph of a DIMACS family or a random graph.
+pub fn generate(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[("--seed", 1), ("--format", 1), ("--output", 1), ("--add", 0), ("--hide", 0)],
+                              GENERATE_USAGE)?;
+    let generated = build(&options)?;
+
+    if options.flag("--add") {
+        let path = ws.instance_path(&generated.id);
+        write_output(Some(&path.to_string_lossy()), &graph_to_string(&generated.graph))?;
+        let record = match generated.family {
+            Some(family) => family.record(&generated.graph),
+            None => {
+                let mut record = InstanceRecord::from_graph(&generated.id, "clq", &generated.graph);
+                for (problem, value) in &generated.known {
+                    record.set_known(*problem, value.clone());
+                }
+                record
+            }
+        };
+        InstanceDatabase::update(&ws.database_path(), |db| {
+            db.add_record(record);
+            Ok(())
+        })?;
+        println!("Graph written to {:?} and added to the database", path);
+    }
+    if !options.flag("--add") || options.value("--output").is_some() {
+        let content = format_graph(&generated.graph, options.value("--format").unwrap_or("dimacs"), &options)?;
+        write_output(options.value("--output"), &content)?;
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn build(options: &Args) -> Result<Generated, CliError> {
+    let positional = options.expect_positional(1, 4)?;
+    let seed = options.parse_value("--seed", 0u64)?;
+    let (kind, params) = (positional[0].as_str(), &positional[1..]);
+
+    if let Ok(family) = kind.parse::<Family>() {
+        options.expect_positional(1, 1)?;
+        return Ok(Generated {
+            id: family.id(),
+            graph: family.generate(seed),
+            family: Some(family),
+            known: family.known_values(),
+        });
+    }
+
+    let expected = match kind {
+        "gnp" | "gnm" | "regular" | "ba" | "geometric" => 2,
+        "planted-cover" | "hidden-clique" => 3,
+        _ => return Err(options.usage_error(&format!("Unknown family or kind of graph {:?}", kind))),
+    };
+    if params.len() != expected {
+        return Err(options.usage_error(&format!("{} expects {} parameters", kind, expected)));
+    }
+    let int = |i: usize| params[i].parse::<usize>()
+        .map_err(|_| options.usage_error(&format!("Expecting an integer but read {:?}", params[i])));
+    let float = |i: usize| params[i].parse::<f64>()
+        .map_err(|_| options.usage_error(&format!("Expecting a number but read {:?}", params[i])));
+    let invalid = |e: MvcError| options.usage_error(&format!("Invalid parameters for {} : {}", kind, e));
+
+    let mut known = Vec::new();
+    let graph = match kind {
+        "gnp" => gnp(int(0)?, float(1)?, seed),
+        "gnm" => gnm(int(0)?, int(1)?, seed).map_err(invalid)?,
+        "regular" => random_regular(int(0)?, int(1)?, seed).map_err(invalid)?,
+        "ba" => barabasi_albert(int(0)?, int(1)?, seed).map_err(invalid)?,
+        "geometric" => random_geometric(int(0)?, float(1)?, seed).0,
+        "planted-cover" => {
+            let k = int(1)?;
+            known.push((Problem::Mvc, KnownValue::new(k as u64, "planted cover")));
+            planted_cover(int(0)?, k, float(2)?, seed).map_err(invalid)?.0
+        }
+        _ => hidden_clique(int(0)?, float(1)?, int(2)?, options.flag("--hide"), seed).map_err(invalid)?.0,
+    };
+    let hide = if options.flag("--hide") && kind == "hidden-clique" { "-hidden" } else { "" };
+    Ok(Generated {
+        id: format!("{}-{}{}-s{}.clq", kind, params.join("-"), hide, seed),
+        graph,
+        family: None,
+        known,
+    })
+}
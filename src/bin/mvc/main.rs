@@ -0,0 +1,88 @@
+use std::env;
+use std::process::exit;
+
+use vertex::cli::EXIT_SUCCESS;
+use vertex::database::Problem;
+use vertex::errors::CliError;
+use vertex::workspace::Workspace;
+
+mod bench;
+mod db;
+mod graph;
+mod solve;
+
+const USAGE: &str = "Usage: cargo run [-r] --bin mvc <command> [<args>] [--workspace <path>]\n\
+Commands :\n\
+\x20 solve <graph>      Find a minimum vertex cover\n\
+\x20 clique <graph>     Find a maximum clique (minimum vertex cover of the complement)\n\
+\x20 mis <graph>        Find a maximum independent set\n\
+\x20 verify <graph> <solution_file>  Check a solution\n\
+\x20 convert <graph>    Write a graph in the DIMACS, DOT or GraphML format\n\
+\x20 stats <graph>      Print the statistics of a graph\n\
+\x20 generate <family | kind> [<parameters>]  Generate a graph\n\
+\x20 bench [<folder | list_file>]  Run solvers on a set of instances and record the runs\n\
+\x20 db <add | migrate | show | compare | report>  Manage the instance database\n\
+A graph is a path or the id of an instance of the workspace. Run mvc <command> --help for the options of a command.\n\
+Exit codes : 0 success, 1 check failed, 2 invalid command line, 3 input or output error, 4 time limit or node limit reached";
+
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let code = match run(&mut args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error : {}", e);
+            if let CliError::Usage(_, usage) = &e {
+                eprintln!("{}", usage);
+            }
+            e.exit_code()
+        }
+    };
+    exit(code);
+}
+
+fn run(args: &mut Vec<String>) -> Result<i32, CliError> {
+    // The child processes of the benchmarks only solve an instance and print a report
+    if args.len() > 2 && args[1] == "bench" && args[2] == "--child" {
+        return bench::run_child(&args[3..]);
+    }
+    let ws = Workspace::from_args(args)?;
+    let command = match args.get(1) {
+        Some(command) => command.as_str(),
+        None => return Err(CliError::Usage("Missing command".to_string(), USAGE.to_string())),
+    };
+    let rest = &args[2..];
+    if rest.iter().any(|a| a == "--help") {
+        println!("{}", command_usage(command).unwrap_or(USAGE));
+        return Ok(EXIT_SUCCESS);
+    }
+    match command {
+        "solve" => solve::solve(&ws, rest, Problem::Mvc),
+        "clique" => solve::solve(&ws, rest, Problem::Clique),
+        "mis" => solve::solve(&ws, rest, Problem::Mis),
+        "verify" => solve::verify(&ws, rest),
+        "convert" => graph::convert(&ws, rest),
+        "stats" => graph::stats(&ws, rest),
+        "generate" => graph::generate(&ws, rest),
+        "bench" => bench::bench(&ws, rest),
+        "db" => db::db(&ws, rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(EXIT_SUCCESS)
+        }
+        _ => Err(CliError::Usage(format!("Unknown command {:?}", command), USAGE.to_string())),
+    }
+}
+
+/// Returns the usage of a command.
+fn command_usage(command: &str) -> Option<&'static str> {
+    match command {
+        "solve" | "clique" | "mis" => Some(solve::SOLVE_USAGE),
+        "verify" => Some(solve::VERIFY_USAGE),
+        "convert" => Some(graph::CONVERT_USAGE),
+        "stats" => Some(graph::STATS_USAGE),
+        "generate" => Some(graph::GENERATE_USAGE),
+        "bench" => Some(bench::BENCH_USAGE),
+        "db" => Some(db::DB_USAGE),
+        _ => None,
+    }
+}
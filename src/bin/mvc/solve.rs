@@ -0,0 +1,196 @@
+use std::fs::read_to_string;
+use std::io;
+use std::io::IsTerminal;
+use std::sync::Arc;
+use std::time::Duration;
+
+use itertools::Itertools;
+use petgraph::prelude::UnGraphMap;
+
+use vertex::{Algorithm, BnbOptions, branch_and_bound_with, Clock, Limits, MVCResult, naive_search, OutputFormat,
+             solve_problem};
+use vertex::bench::Solver;
+use vertex::checkpoint::{checkpointed_branch_and_bound, CheckpointOptions, DEFAULT_INTERVAL};
+use vertex::cli::{Args, EXIT_FAILURE, EXIT_SUCCESS, EXIT_TIME_LIMIT, graph_id, graph_path, load_graph,
+                  load_graph_with_stats, parse_solution, solution_to_string, write_output};
+use vertex::database::{InstanceDatabase, InstanceRecord, Problem, RunRecord, RunStatus};
+use vertex::errors::CliError;
+use vertex::graph_utils::{graph_to_dot, graph_to_graphml, Highlight, is_clique, is_independent_set, is_vertex_cover};
+use vertex::observer::ProgressLine;
+use vertex::workspace::Workspace;
+
+pub const SOLVE_USAGE: &str = "Usage: mvc <solve | clique | mis> <graph> [--solver <bnb | naive_search>] \
+[--time-limit <seconds>] [--node-limit <nodes>] [--format <text | json | yaml | csv>] [--output <file>] \
+[--solution <file>] [--dot <file>] [--graphml <file>] [--record] [--comment <text>] [--quiet] [--profile] \
+[--folded <file>] [--branching <max-degree | mirror | satellite | overlap>] [--degree-two] [--dominance] [--symmetry] \
+[--checkpoint <file> [--checkpoint-interval <seconds>] [--resume]]\n\
+The time limit can be fractional (ex: 0.5) and the node limit bounds the number of nodes of the search : the solver \
+then returns the best solution found so far. The result is written to the output file (default : the standard output) \
+in the given format (default : text). The solution file lists the vertices of the solution, numbered from 1. With \
+--record, the run is stored in the instance database. With --checkpoint, the branch and bound writes its state in the \
+file every 60 seconds (default) and when it stops, and --resume continues the search from the file on the same graph. \
+The progress of the search is drawn on the standard error when it is a terminal, unless --quiet is given. --profile \
+adds the time spent in each subroutine of the solver to the text result and --folded writes it as folded stacks for \
+the flame graph tools. --branching selects the branching strategy of the branch and bound (default : max-degree), \
+--degree-two solves its subgraphs of maximum degree 2 without branching, and --dominance and --symmetry prune the \
+subtrees of dominated and symmetric vertices.";
+
+pub const VERIFY_USAGE: &str = "Usage: mvc verify <graph> <solution_file> [--problem <mvc | clique | mis>]\n\
+The solution file lists the vertices of the solution, numbered from 1 (default problem : mvc).";
+
+/// Solves a problem on a graph. Exits with code 1 if the value is not the known optimal value and with code 4 if the
+/// solver was stopped by a limit.
+pub fn solve(ws: &Workspace, args: &[String], problem: Problem) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[("--solver", 1), ("--time-limit", 1), ("--node-limit", 1), ("--format", 1),
+        ("--output", 1),
+        ("--solution", 1), ("--dot", 1), ("--graphml", 1), ("--record", 0), ("--comment", 1), ("--checkpoint", 1),
+        ("--checkpoint-interval", 1), ("--resume", 0), ("--quiet", 0), ("--profile", 0), ("--folded", 1),
+        ("--branching", 1), ("--degree-two", 0), ("--dominance", 0), ("--symmetry", 0)], SOLVE_USAGE)?;
+    let path = graph_path(ws, &options.expect_positional(1, 1)?[0]);
+    let solver = options.value("--solver").unwrap_or("bnb");
+    let bnb = options.parse_bnb_options()?;
+    let algorithm: Box<Algorithm> = match solver {
+        "bnb" => Box::new(move |g: &UnGraphMap<u64, ()>, clock: &mut Clock| branch_and_bound_with(g, clock, &bnb)),
+        "naive_search" if bnb != BnbOptions::default() => {
+            return Err(options.usage_error("The options of the branch and bound are only supported by the bnb solver"));
+        }
+        "naive_search" => Box::new(naive_search),
+        _ => return Err(options.usage_error(&format!("Unknown solver {:?}", solver))),
+    };
+    let mut limits = Limits::new(options.parse_duration("--time-limit", Duration::from_secs(3600))?);
+    if options.value("--node-limit").is_some() {
+        limits = limits.with_node_limit(options.parse_value("--node-limit", 0u64)?);
+    }
+    if !options.flag("--quiet") && io::stderr().is_terminal() {
+        limits = limits.with_observer(Arc::new(ProgressLine::stderr()));
+    }
+    let format = options.parse_value("--format", OutputFormat::Text)?;
+
+    let checkpoint = match options.value("--checkpoint") {
+        Some(file) if solver == "bnb" => Some(CheckpointOptions::new(file)
+            .with_interval(options.parse_duration("--checkpoint-interval", DEFAULT_INTERVAL)?)
+            .with_resume(options.flag("--resume"))
+            .with_bnb_options(bnb)),
+        Some(_) => return Err(options.usage_error("--checkpoint is only supported by the bnb solver")),
+        None if options.flag("--resume") => return Err(options.usage_error("--resume expects a --checkpoint file")),
+        None => None,
+    };
+
+    let id = graph_id(&path);
+    let (graph, load_stats) = load_graph_with_stats(&path)?;
+    if let Some(observer) = &limits.observer {
+        observer.on_load(&load_stats);
+    }
+    let res = match checkpoint {
+        Some(checkpoint) => {
+            let f = move |g: &UnGraphMap<u64, ()>, clock: &mut Clock| checkpointed_branch_and_bound(g, clock, &checkpoint);
+            solve_problem(ws, &id, &graph, &f, problem, limits)?
+        }
+        None => solve_problem(ws, &id, &graph, algorithm.as_ref(), problem, limits)?,
+    };
+    let mut content = match (format, &res.graph_stats) {
+        (OutputFormat::Text, Some(stats)) => format!("Graph : {}\nResult : {}\n", stats, res),
+        _ => res.format(format),
+    };
+    if format == OutputFormat::Text && !res.pruned.is_empty() {
+        let pruned = res.pruned.iter().map(|(rule, subtrees)| format!("{} {}", rule, subtrees)).join(", ");
+        content.push_str(&format!("Subtrees pruned : {}\n", pruned));
+    }
+    if format == OutputFormat::Text && options.flag("--profile") {
+        content.push_str(&format!("Profile :\n{}", res.profile.report()));
+    }
+    write_output(options.value("--output"), &content)?;
+
+    if let Some(file) = options.value("--folded") {
+        write_output(Some(file), &res.profile.to_folded(solver))?;
+    }
+
+    if let Some(file) = options.value("--solution") {
+        write_output(Some(file), &solution_to_string(&res.set))?;
+    }
+    let highlight = match problem {
+        Problem::Mvc => Highlight::VertexCover,
+        Problem::Clique => Highlight::Clique,
+        Problem::Mis => Highlight::IndependentSet,
+    };
+    if let Some(file) = options.value("--dot") {
+        write_output(Some(file), &graph_to_dot(&graph, Some((highlight, &res.set))))?;
+    }
+    if let Some(file) = options.value("--graphml") {
+        write_output(Some(file), &graph_to_graphml(&graph, Some((highlight, &res.set))))?;
+    }
+    if options.flag("--record") {
+        record_run(ws, &path, &graph, solver, &bnb, &res, options.value("--comment").unwrap_or(""))?;
+        if format == OutputFormat::Text {
+            println!("Run recorded in {}", ws.database_path());
+        }
+    }
+
+    Ok(match res.is_optimal {
+        _ if res.status != RunStatus::Solved => EXIT_TIME_LIMIT,
+        Some(false) => EXIT_FAILURE,
+        _ => EXIT_SUCCESS,
+    })
+}
+
+/// Stores a run in the instance database. The branch and bound run on the complement is named "clique", like in the
+/// benchmarks, and the options of the branch and bound are added to the name (see [`Solver::algorithm`]).
+fn record_run(ws: &Workspace, path: &std::path::Path, graph: &UnGraphMap<u64, ()>, solver: &str, bnb: &BnbOptions,
+              res: &MVCResult, comment: &str) -> Result<(), CliError> {
+    let algorithm = match (res.problem, solver) {
+        (Problem::Clique, "bnb") => Solver::Clique.algorithm(bnb),
+        (_, "bnb") => Solver::BranchAndBound.algorithm(bnb),
+        (_, solver) => solver.to_string(),
+    };
+    let format = path.extension().unwrap_or_default().to_string_lossy().to_string();
+    let mut run = RunRecord::new(res.problem, &algorithm, res.value.unwrap_or(0), res.time.duration, res.is_time_limit,
+                                 comment);
+    run.value = res.value;
+    run.status = res.status;
+    run.stats = res.subroutines.clone();
+    run.stats.extend(res.pruned.iter().map(|(rule, subtrees)| (format!("pruned_{}", rule), *subtrees as f64)));
+    let mut record = InstanceRecord::from_graph(&res.graph_id, &format, graph);
+    record.runs.push(run);
+    InstanceDatabase::update(&ws.database_path(), |db| {
+        db.add_record(record);
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Checks a solution. Exits with code 1 if the solution is not valid.
+pub fn verify(ws: &Workspace, args: &[String]) -> Result<i32, CliError> {
+    let options = Args::parse(args, &[("--problem", 1)], VERIFY_USAGE)?;
+    let positional = options.expect_positional(2, 2)?;
+    let problem = options.parse_value("--problem", Problem::Mvc)?;
+    let path = graph_path(ws, &positional[0]);
+    let graph = load_graph(&path)?;
+    let content = read_to_string(&positional[1]).map_err(|e| CliError::IoError(
+        format!("Unable to read the solution {:?} : {}", positional[1], e), format!("{:?}", e)))?;
+    let mut solution = parse_solution(&content)?;
+    solution.sort_unstable();
+    solution.dedup();
+
+    if let Some(v) = solution.iter().find(|v| !graph.contains_node(**v)) {
+        println!("Invalid solution : the graph has no vertex {}", v + 1);
+        return Ok(EXIT_FAILURE);
+    }
+    let valid = match problem {
+        Problem::Mvc => is_vertex_cover(&graph, &solution),
+        Problem::Clique => is_clique(&graph, &solution),
+        Problem::Mis => is_independent_set(&graph, &solution),
+    };
+    let size = solution.len() as u64;
+    if !valid {
+        println!("Invalid solution : the {} vertices do not form a {}", size, problem.description().to_lowercase());
+        return Ok(EXIT_FAILURE);
+    }
+
+    let db = InstanceDatabase::load(&ws.database_path())?;
+    match db.get_optimal_value(&graph_id(&path), problem, &graph)? {
+        Some(optimal) if optimal == size => println!("Valid solution of size {} (optimal)", size),
+        Some(optimal) => println!("Valid solution of size {} (the optimal value is {})", size, optimal),
+        None => println!("Valid solution of size {} (the optimal value is unknown)", size),
+    }
+    Ok(EXIT_SUCCESS)
+}
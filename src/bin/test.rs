@@ -1,3 +0,0 @@
-fn main() {
-
-}
\ No newline at end of file
@@ -1,95 +1,274 @@
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use itertools::Itertools;
 use petgraph::prelude::UnGraphMap;
 
-use crate::Clock;
+use crate::{BnbOptions, Branching, Clock};
+use crate::errors::MvcError;
+use crate::checkpoint::Subproblem;
 use crate::graph_utils::{complement, copy_graph, get_vertex_with_max_degree};
+use crate::symmetry::{MAX_DEPTH, orbit};
+
+/// Open subproblem of the search : the subgraph left once the vertices fixed in and out of the vertex cover are
+/// removed from the graph.
+pub(crate) struct OpenNode {
+    pub graph: UnGraphMap<u64, ()>,
+    pub fixed: Subproblem,
+    /// Number of branchings between the root and the subproblem
+    pub depth: usize,
+}
 
-pub fn b_and_b(_graph: &UnGraphMap<u64, ()>,
-               g: &UnGraphMap<u64, ()>,
-               upper_bound: u64,
-               upper_bound_vc: &Vec<u64>,
-               vertex_cover: Vec<u64>,
-               clock: &mut Clock) -> (u64, Vec<u64>) {
-    if clock.is_time_up() {
-        return (upper_bound, upper_bound_vc.clone());
+impl OpenNode {
+    /// Rebuilds an open subproblem of a checkpoint from the graph.
+    ///
+    /// Each branching fixes at least one vertex, so the number of fixed vertices is used as the depth, which is not
+    /// stored in the checkpoints.
+    pub fn from_subproblem(graph: &UnGraphMap<u64, ()>, fixed: Subproblem) -> OpenNode {
+        let mut subgraph = copy_graph(graph);
+        for v in fixed.fixed_in.iter().chain(fixed.fixed_out.iter()) {
+            subgraph.remove_node(*v);
+        }
+        let depth = fixed.fixed_in.len() + fixed.fixed_out.len();
+        OpenNode { graph: subgraph, fixed, depth }
     }
+}
 
-    clock.enter_subroutine("copy");
-    let mut subgraph = copy_graph(g);
-    clock.exit_subroutine("copy").expect("Error while exiting subroutine");
-
-    if subgraph.edge_count() == 0 {
-        // If the subgraph is empty, all edges are covered => vertex cover
-        return (vertex_cover.len() as u64, vertex_cover);
+/// Depth first search of the branch and bound, with an explicit stack of open subproblems.
+///
+/// The incumbent `best` is the smallest vertex cover found so far. The search stops when the stack is empty or when
+/// the clock says so : the open subproblems are then left on the stack. `save` is called before each node with the
+/// stack and the incumbent, so that the search can be checkpointed.
+pub(crate) fn b_and_b<F>(open: &mut Vec<OpenNode>, best: &mut Vec<u64>, clock: &mut Clock, options: &BnbOptions,
+                         mut save: F) -> Result<(), MvcError>
+    where F: FnMut(&[OpenNode], &[u64], &Clock) -> Result<(), MvcError> {
+    clock.improve_incumbent(best.len() as u64);
+    loop {
+        save(open, best, clock)?;
+        let node = match open.pop() {
+            Some(node) => node,
+            None => return Ok(()),
+        };
+        if clock.should_stop() {
+            open.push(node);
+            return Ok(());
+        }
+        clock.count_node();
+        branch(node, open, best, clock, options)?;
     }
+}
+
+/// Vertices added to the vertex cover and left out of it by a branch.
+struct Branch {
+    cover: Vec<u64>,
+    out: Vec<u64>,
+}
 
-    clock.enter_subroutine("max_deg");
-    let (v, _max_deg) = get_vertex_with_max_degree(&subgraph, None);
-    clock.exit_subroutine("max_deg").expect("Error while exiting subroutine");
+/// Explores a subproblem : updates the incumbent if it is a vertex cover, and pushes its branches on the stack
+/// unless it is pruned by the lower bound.
+fn branch(node: OpenNode, open: &mut Vec<OpenNode>, best: &mut Vec<u64>, clock: &mut Clock, options: &BnbOptions)
+          -> Result<(), MvcError> {
+    let OpenNode { graph, fixed: Subproblem { fixed_in: mut vertex_cover, fixed_out }, depth } = node;
 
+    let subgraph = {
+        let _copy = clock.scope("copy");
+        copy_graph(&graph)
+    };
 
-    if vertex_cover.len() as u64 + compute_lb(copy_graph(&subgraph), clock)  >= upper_bound {
-        // We can't find a better solution in this branch, we stop and return the best known solution
-        return (upper_bound, upper_bound_vc.clone());
+    let (max_deg_vertex, max_deg) = {
+        let _max_deg = clock.scope("max_deg");
+        get_vertex_with_max_degree(&subgraph, None)
+    };
+    if options.degree_two && max_deg <= 2 {
+        // The subgraph is made of paths and cycles
+        let _degree_two = clock.scope("degree_two");
+        vertex_cover.extend(cover_paths_and_cycles(&subgraph));
+    }
+    if max_deg == 0 || (options.degree_two && max_deg <= 2) {
+        // All edges are covered => vertex cover
+        if vertex_cover.len() <= best.len() {
+            clock.improve_incumbent(vertex_cover.len() as u64);
+            *best = vertex_cover;
+        }
+        return Ok(());
     }
 
-    let neighbors: Vec<u64> = subgraph.neighbors(v).collect();
-
-    // ====> First case <====
-    // - G \ {v}
-    // - C U v
-    let mut vertex_cover_case1 = vertex_cover.clone();
-
-    // Removes v + edges from v to neighbor
-    subgraph.remove_node(v);
-    vertex_cover_case1.push(v);
-    let res_case1 = b_and_b(_graph,
-                            &subgraph,
-                            upper_bound,
-                            upper_bound_vc,
-                            vertex_cover_case1, clock);
-
-    // ====> Second case <====
-    // - G \ N*(v)
-    // - C U N(v)
-    let mut vertex_cover_case2 = vertex_cover.clone();
-
-    // Remove all neighbors of v + edges from neighbors to their neighbors
-    for neighbor in neighbors {
-        vertex_cover_case2.push(neighbor);
-        subgraph.remove_node(neighbor);
+    let lower_bound = {
+        let mut scope = clock.scope("lower_bound");
+        compute_lb(copy_graph(&subgraph), &mut scope)?
+    };
+    if vertex_cover.is_empty() && fixed_out.is_empty() {
+        // Lower bound of the whole graph
+        clock.improve_lower_bound(lower_bound);
+    }
+    if vertex_cover.len() as u64 + lower_bound >= best.len() as u64 {
+        // We can't find a better solution in this branch
+        return Ok(());
     }
 
-    let res_case2 = {
-        if upper_bound >= res_case1.0 {
-            b_and_b(_graph,
-                    &subgraph,
-                    res_case1.0,
-                    &res_case1.1,
-                    vertex_cover_case2, clock)
-        } else {
-            b_and_b(_graph,
-                    &subgraph,
-                    upper_bound,
-                    upper_bound_vc,
-                    vertex_cover_case2,
-                    clock)
-        }
+    let (v, mut branches) = {
+        let _branching = clock.scope("branching");
+        let v = match options.branching {
+            Branching::Overlap => get_vertex_with_max_overlap(&subgraph),
+            _ => max_deg_vertex,
+        };
+        (v, get_branches(&subgraph, v, options.branching))
     };
+    if options.dominance && {
+        let _dominance = clock.scope("dominance");
+        dominates_a_neighbor(&subgraph, v)
+    } {
+        // A minimum vertex cover contains v
+        clock.count_pruned("dominance", branches.len() as u64 - 1);
+        branches = vec![Branch { cover: vec![v], out: vec![] }];
+    } else if options.symmetry && depth < MAX_DEPTH {
+        let orbit = {
+            let _symmetry = clock.scope("symmetry");
+            orbit(&subgraph, v)
+        };
+        if orbit.len() > 1 {
+            clock.count_pruned("symmetry", orbit.len() as u64 - 1);
+            if options.branching == Branching::Satellite {
+                // The satellites are only left out of the cover with v if v can be in it
+                branches = get_branches(&subgraph, v, Branching::MaxDegree);
+            }
+            let others = orbit.into_iter().filter(|u| *u != v && !branches[0].cover.contains(u)).collect_vec();
+            branches[0].cover.extend(others);
+        }
+    }
+    // The first branch is explored first
+    for Branch { cover, out } in branches.into_iter().rev() {
+        let mut child = copy_graph(&subgraph);
+        for v in cover.iter().chain(out.iter()) {
+            child.remove_node(*v);
+        }
+        let fixed_in = vertex_cover.iter().chain(cover.iter()).copied().collect();
+        let fixed_out = fixed_out.iter().chain(out.iter()).copied().collect();
+        open.push(OpenNode { graph: child, fixed: Subproblem { fixed_in, fixed_out }, depth: depth + 1 });
+    }
+    Ok(())
+}
+
+/// Returns the branches of a subproblem on the vertex v, the one to explore first first. The first branch puts v in
+/// the vertex cover and the second one leaves it out.
+fn get_branches(graph: &UnGraphMap<u64, ()>, v: u64, branching: Branching) -> Vec<Branch> {
+    let neighbors: Vec<u64> = graph.neighbors(v).collect();
+    match branching {
+        Branching::MaxDegree | Branching::Overlap => vec![
+            // - G \ {v}, C U v
+            Branch { cover: vec![v], out: vec![] },
+            // - G \ N*(v), C U N(v)
+            Branch { cover: neighbors, out: vec![v] },
+        ],
+        Branching::Mirror => {
+            // If v is in the vertex cover, a minimum one contains its mirrors too
+            let mut cover = vec![v];
+            cover.extend(mirrors(graph, v));
+            vec![Branch { cover, out: vec![] }, Branch { cover: neighbors, out: vec![v] }]
+        }
+        Branching::Satellite => {
+            // If v is out of the vertex cover, a minimum one leaves its satellites out too, unless 2 of them are
+            // adjacent : the second branch is then empty
+            let mut out = vec![v];
+            out.extend(satellites(graph, v));
+            let independent = out.iter().tuple_combinations().all(|(a, b)| !graph.contains_edge(*a, *b));
+            let mut branches = vec![Branch { cover: vec![v], out: vec![] }];
+            if independent {
+                let cover = out.iter().flat_map(|u| graph.neighbors(*u)).unique().collect();
+                branches.push(Branch { cover, out });
+            }
+            branches
+        }
+    }
+}
+
+/// Returns the vertices at distance 2 of a vertex v.
+fn second_neighbors(graph: &UnGraphMap<u64, ()>, v: u64) -> Vec<u64> {
+    graph.neighbors(v)
+        .flat_map(|w| graph.neighbors(w))
+        .filter(|u| *u != v && !graph.contains_edge(v, *u))
+        .unique()
+        .collect()
+}
+
+/// Returns the mirrors of a vertex v : the vertices u at distance 2 such that N(v) \ N(u) is a clique.
+fn mirrors(graph: &UnGraphMap<u64, ()>, v: u64) -> Vec<u64> {
+    second_neighbors(graph, v).into_iter()
+        .filter(|u| {
+            let rest: Vec<u64> = graph.neighbors(v).filter(|w| !graph.contains_edge(*u, *w)).collect();
+            rest.iter().tuple_combinations().all(|(a, b)| graph.contains_edge(*a, *b))
+        })
+        .collect()
+}
 
-    {
-        if res_case1.0 >= res_case2.0 {
-            res_case2
-        } else {
-            res_case1
+/// Returns the satellites of a vertex v : the vertices u at distance 2 such that N\[w\] \ N\[v\] = {u} for a
+/// neighbour w of v.
+fn satellites(graph: &UnGraphMap<u64, ()>, v: u64) -> Vec<u64> {
+    graph.neighbors(v)
+        .filter_map(|w| graph.neighbors(w)
+            .filter(|u| *u != v && !graph.contains_edge(v, *u))
+            .exactly_one()
+            .ok())
+        .unique()
+        .collect()
+}
+
+/// Returns true if a neighbour u of v has its closed neighbourhood in the one of v (N[u] ⊆ N[v]).
+fn dominates_a_neighbor(graph: &UnGraphMap<u64, ()>, v: u64) -> bool {
+    graph.neighbors(v).any(|u| graph.neighbors(u).all(|w| w == v || graph.contains_edge(v, w)))
+}
+
+/// Returns the vertex with the most edges between its neighbours, the ties being broken by degree.
+fn get_vertex_with_max_overlap(graph: &UnGraphMap<u64, ()>) -> u64 {
+    let mut best = (0, 0);
+    let mut best_vertex = 0;
+    for v in graph.nodes() {
+        let neighbors: HashSet<u64> = graph.neighbors(v).collect();
+        let overlap: usize = neighbors.iter()
+            .map(|w| graph.neighbors(*w).filter(|u| neighbors.contains(u)).count())
+            .sum();
+        if (overlap, neighbors.len()) > best {
+            best = (overlap, neighbors.len());
+            best_vertex = v;
         }
     }
+    best_vertex
 }
 
-fn compute_lb(graph: UnGraphMap<u64, ()>, clock: &mut Clock) -> u64 {
+/// Returns a minimum vertex cover of a graph of maximum degree 2 : every other vertex of each path, from its second
+/// vertex, and of each cycle, from its first vertex.
+fn cover_paths_and_cycles(graph: &UnGraphMap<u64, ()>) -> Vec<u64> {
+    let mut vertices: Vec<u64> = graph.nodes().collect();
+    vertices.sort_unstable();
+    // The paths start at a vertex of degree at most 1, the vertices left are on cycles
+    let starts = vertices.iter().filter(|v| graph.neighbors(**v).count() <= 1).chain(vertices.iter());
+
+    let mut visited = HashSet::new();
+    let mut cover = Vec::new();
+    for &start in starts {
+        if !visited.insert(start) {
+            continue;
+        }
+        let first_covered = if graph.neighbors(start).count() == 2 { 0 } else { 1 };
+        let (mut previous, mut current, mut index) = (start, start, 0);
+        loop {
+            if index % 2 == first_covered {
+                cover.push(current);
+            }
+            match graph.neighbors(current).find(|u| *u != previous && !visited.contains(u)) {
+                Some(next) => {
+                    visited.insert(next);
+                    (previous, current, index) = (current, next, index + 1);
+                }
+                None => break,
+            }
+        }
+    }
+    cover
+}
+
+fn compute_lb(graph: UnGraphMap<u64, ()>, clock: &mut Clock) -> Result<u64, MvcError> {
     let graph = Arc::new(graph);
 
     // First thread : deg_lb
@@ -102,44 +281,35 @@ fn compute_lb(graph: UnGraphMap<u64, ()>, clock: &mut Clock) -> u64 {
     let handle_clq = std::thread::spawn(move || {
         clq_lb(&shared_clq)
     });
-    clock.enter_subroutine("deg_lb");
-    let deg_lb = handle_deg.join().unwrap();
-    clock.exit_subroutine("deg_lb").expect("Error while exiting subroutine");
-
-    clock.enter_subroutine("clq_lb");
-    let clq_lb = handle_clq.join().unwrap();
-    clock.exit_subroutine("clq_lb").expect("Error while exiting subroutine");
-    max(deg_lb, clq_lb)
+    let failure = |name: &str| MvcError::ThreadFailure(format!("The thread computing {} panicked", name));
+    let deg_lb = {
+        let _deg_lb = clock.scope("deg_lb");
+        handle_deg.join().map_err(|_| failure("deg_lb"))?
+    };
+    let clq_lb = {
+        let _clq_lb = clock.scope("clq_lb");
+        handle_clq.join().map_err(|_| failure("clq_lb"))?
+    };
+    Ok(max(deg_lb, clq_lb))
 }
 
 fn deg_lb(graph: &UnGraphMap<u64, ()>) -> u64 {
-
+    // k vertices cover at most the sum of the k largest degrees : the bound is the smallest k whose largest degrees
+    // add up to the number of edges. Selecting the vertices greedily in the subgraph left would overestimate it.
     let size = graph.edge_count();
-    let mut selected_vertexes = Vec::<u64>::new();
-    let mut sum_degrees: usize = 0;
-
-    let mut subgraph = copy_graph(graph);
+    let mut degrees: Vec<usize> = graph.nodes().map(|v| graph.neighbors(v).count()).collect();
+    degrees.sort_unstable_by(|a, b| b.cmp(a));
 
-    let mut working = true;
-    while working {
-        // Get the vertex with the highest degree in the subgraph.
-        let (max_degree_vertex, _vertex_degree) = get_vertex_with_max_degree(&subgraph, None);
-        selected_vertexes.push(max_degree_vertex);
-        sum_degrees += graph.neighbors(max_degree_vertex).count();
-        subgraph.remove_node(max_degree_vertex);
+    let mut sum_degrees: usize = 0;
+    let mut selected: u64 = 0;
+    for degree in degrees {
         if sum_degrees >= size {
-            working = false;
+            break;
         }
+        sum_degrees += degree;
+        selected += 1;
     }
-
-    let edges_left = subgraph.edge_count();
-    let next_vertex = get_vertex_with_max_degree(&subgraph, None).0;
-    if edges_left == 0 {
-        selected_vertexes.len() as u64
-    } else {
-        let estim = (edges_left / graph.neighbors(next_vertex).count()) as f64;
-        (selected_vertexes.len() as f64 + estim).floor() as u64
-    }
+    selected
 }
 
 #[allow(dead_code)]
@@ -249,12 +419,8 @@ fn welch_powell(graph: &UnGraphMap<u64, ()>) -> Vec<usize> {
     loop {
         let mut biggest_index = -1;
         // Find the biggest vertex that is not colored
-        for i in 0..sorted_vertices.len() {
-            // vertex - 1 because vertex are numbered from 1 to n
-            if color[i] == -1 {
-                biggest_index = i as i32;
-                break;
-            }
+        if let Some(i) = color.iter().position(|&c| c == -1) {
+            biggest_index = i as i32;
         }
         if biggest_index == -1 {
             // All vertices are colored
@@ -266,10 +432,10 @@ fn welch_powell(graph: &UnGraphMap<u64, ()>) -> Vec<usize> {
 
         assert_eq!(color.len(), sorted_vertices.len());
         // Color vertices that are not neighbors of vertex colored with current_color
-        for i in 0..sorted_vertices.len() {
+        for (i, &vertex) in sorted_vertices.iter().enumerate() {
             if color[i] == -1 {
                 let mut can_color = true;
-                for neighbor in graph.neighbors(sorted_vertices[i]) {
+                for neighbor in graph.neighbors(vertex) {
                     let index = *vertex_to_index.get(&neighbor).unwrap();
                     if color[index] == current_color {
                         // If a neighbor is already colored with current_color, we don't color the vertex
@@ -291,8 +457,10 @@ fn welch_powell(graph: &UnGraphMap<u64, ()>) -> Vec<usize> {
 
 #[cfg(test)]
 mod branch_and_bound_tests {
-    use crate::branch_and_bound;
-    use crate::graph_utils::load_clq_file;
+    use crate::{branch_and_bound, branch_and_bound_with};
+    use crate::generators::gnp;
+    use crate::graph_utils::{is_vertex_cover, load_clq_file};
+    use itertools::iproduct;
 
     use super::*;
 
@@ -340,6 +508,17 @@ mod branch_and_bound_tests {
         };
         let res = deg_lb(&graph);
         assert_eq!(res, 3);
+
+        // The path 7 - 9 - 5 - 1 - 4 is covered by 2 vertices. Its vertices are added so that the middle vertex is
+        // followed by a leaf : selecting them greedily (5, 7 then 1) used to give 3.
+        let mut path = UnGraphMap::<u64, ()>::new();
+        for v in [5, 7, 9, 1, 4] {
+            path.add_node(v);
+        }
+        for (u, v) in [(7, 9), (9, 5), (5, 1), (1, 4)] {
+            path.add_edge(u, v, ());
+        }
+        assert_eq!(deg_lb(&path), 2);
     }
 
     #[test]
@@ -353,16 +532,103 @@ mod branch_and_bound_tests {
         graph.add_edge(2, 0, ());
         graph.add_edge(2, 3, ());
 
-        assert_eq!(branch_and_bound(&graph, &mut Clock::new(3600)).0, 2);
+        assert_eq!(branch_and_bound(&graph, &mut Clock::new(3600)).unwrap().0, 2);
     }
 
     #[test]
     fn test_with_queen_5() {
         let graph = load_clq_file("src/resources/graphs/queen5_5.clq").unwrap();
-        let res = branch_and_bound(&graph, &mut Clock::new(3600));
+        let res = branch_and_bound(&graph, &mut Clock::new(3600)).unwrap();
         assert_eq!(res.0, 20);
     }
 
+    #[test]
+    fn test_branching_strategies() {
+        for file in ["test.clq", "test_cycle_5.clq", "myciel4.clq", "queen5_5.clq"] {
+            let graph = load_clq_file(&format!("src/resources/graphs/{}", file)).unwrap();
+            let expected = branch_and_bound(&graph, &mut Clock::new(3600)).unwrap().0;
+            for branching in Branching::ALL {
+                for (degree_two, dominance, symmetry) in iproduct!([false, true], [false, true], [false, true]) {
+                    let options = BnbOptions::new().with_branching(branching).with_degree_two(degree_two)
+                        .with_dominance(dominance).with_symmetry(symmetry);
+                    let (value, cover) = branch_and_bound_with(&graph, &mut Clock::new(3600), &options).unwrap();
+                    assert_eq!(value, expected, "{} with {}", file, options);
+                    assert!(is_vertex_cover(&graph, &cover));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mirrors_and_satellites() {
+        // In a cycle of length 5, the vertices at distance 2 of a vertex are its mirrors and its satellites
+        let graph = load_clq_file("src/resources/graphs/test_cycle_5.clq").unwrap();
+        let v = graph.nodes().next().unwrap();
+        let mut second = second_neighbors(&graph, v);
+        second.sort_unstable();
+        assert_eq!(second.len(), 2);
+        assert_eq!(mirrors(&graph, v).into_iter().sorted().collect::<Vec<_>>(), second);
+        assert_eq!(satellites(&graph, v).into_iter().sorted().collect::<Vec<_>>(), second);
+        // The satellites are adjacent, so the branch leaving them out of the vertex cover is dropped
+        assert_eq!(get_branches(&graph, v, Branching::Satellite).len(), 1);
+        assert_eq!(get_branches(&graph, v, Branching::Mirror)[0].cover.len(), 3);
+
+        // 1 has 2 neighbours outside N[0] : they are not satellites of 0, and not mirrors while 4 and 5 are not
+        // adjacent
+        let graph = UnGraphMap::<u64, ()>::from_edges([(0, 1), (1, 2), (1, 3), (0, 4), (0, 5)]);
+        assert!(satellites(&graph, 0).is_empty());
+        assert!(mirrors(&graph, 0).is_empty());
+        // Without 5, N(0) \\ N(2) = N(0) \\ N(3) = {4} is a clique
+        let graph = UnGraphMap::<u64, ()>::from_edges([(0, 1), (1, 2), (1, 3), (0, 4)]);
+        assert!(satellites(&graph, 0).is_empty());
+        assert_eq!(mirrors(&graph, 0).into_iter().sorted().collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_pruning_counters() {
+        // In a triangle, every vertex dominates the others and they are all symmetric
+        let triangle = UnGraphMap::<u64, ()>::from_edges([(0, 1), (1, 2), (2, 0)]);
+        let mut clock = Clock::new(60);
+        let options = BnbOptions::new().with_dominance(true).with_symmetry(true);
+        assert_eq!(branch_and_bound_with(&triangle, &mut clock, &options).unwrap().0, 2);
+        // The triangle, then the edge left once a vertex is in the vertex cover
+        assert_eq!(clock.pruned()["dominance"], 2);
+        assert!(!clock.pruned().contains_key("symmetry"));
+
+        let graph = load_clq_file("src/resources/graphs/test_cycle_5.clq").unwrap();
+        let mut clock = Clock::new(60);
+        assert_eq!(branch_and_bound_with(&graph, &mut clock, &BnbOptions::new().with_symmetry(true)).unwrap().0, 3);
+        assert!(clock.pruned()["symmetry"] >= 4);
+        let mut default_clock = Clock::new(60);
+        branch_and_bound(&graph, &mut default_clock).unwrap();
+        assert!(clock.nodes() < default_clock.nodes());
+    }
+
+    #[test]
+    fn test_symmetry_depth_limit() {
+        // The search goes deeper than the limit : the orbits are only searched at the top of the tree
+        let graph = gnp(60, 0.3, 5);
+        let mut clock = Clock::new(60);
+        let symmetry = branch_and_bound_with(&graph, &mut clock, &BnbOptions::new().with_symmetry(true)).unwrap();
+        assert_eq!(symmetry.0, branch_and_bound(&graph, &mut Clock::new(60)).unwrap().0);
+        let searches = clock.profile().entries.iter()
+            .find(|entry| entry.path.last().is_some_and(|name| name == "symmetry"))
+            .map_or(0, |entry| entry.calls);
+        assert!(0 < searches && searches < clock.nodes(), "{} searches for {} nodes", searches, clock.nodes());
+        assert!(searches < 1 << MAX_DEPTH);
+    }
+
+    #[test]
+    fn test_cover_paths_and_cycles() {
+        // A path of 4 vertices, a cycle of 5 vertices, an edge and an isolated vertex
+        let mut graph = UnGraphMap::<u64, ()>::from_edges([(0, 1), (1, 2), (2, 3), (10, 11), (11, 12), (12, 13),
+                                                           (13, 14), (14, 10), (30, 31)]);
+        graph.add_node(20);
+        let cover = cover_paths_and_cycles(&graph);
+        assert_eq!(cover.len(), 2 + 3 + 1);
+        assert!(is_vertex_cover(&graph, &cover));
+    }
+
     #[test]
     fn test_welsh() {
         let g = load_clq_file("src/resources/graphs/test_welsh.clq").unwrap();
@@ -0,0 +1,273 @@
+//! Binary cache of graphs to avoid parsing the large DIMACS instances on every run.
+//!
+//! The cache of a graph file is stored next to it with the `.bin` extension added (ex: `keller5.clq.bin`).
+//! It contains the CSR representation of the graph (see [`CsrGraph`]) in the following format
+//! (all integers are little-endian) :
+//! * magic number `MVCG` (4 bytes)
+//! * version of the format (u32)
+//! * order (u64)
+//! * size (u64)
+//! * offsets array (order + 1 u64)
+//! * targets array (2 * size u32)
+//! * FNV-1a checksum of all the previous bytes (u64)
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::csr::{CsrGraph, load_clq_csr, LoadStats};
+use crate::errors::{CacheError, InvalidClqFileFormat};
+
+const MAGIC: &[u8; 4] = b"MVCG";
+/// Version of the cache format. It has to be incremented when the format changes.
+pub const CACHE_VERSION: u32 = 1;
+const HEADER_LEN: usize = 4 + 4 + 8 + 8;
+
+/// Returns the path of the cache file of a graph file.
+///
+/// # Example
+/// ```rust
+/// use std::path::PathBuf;
+/// use vertex::cache::cache_path;
+///
+/// assert_eq!(cache_path("src/resources/graphs/keller5.clq"), PathBuf::from("src/resources/graphs/keller5.clq.bin"));
+/// ```
+pub fn cache_path(path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.bin", path))
+}
+
+/// Serializes a graph in the cache format.
+pub fn graph_to_bytes(graph: &CsrGraph) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + 8 * graph.offsets().len() + 4 * graph.targets().len() + 8);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(graph.order() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(graph.size() as u64).to_le_bytes());
+    for &offset in graph.offsets() {
+        bytes.extend_from_slice(&(offset as u64).to_le_bytes());
+    }
+    for &target in graph.targets() {
+        bytes.extend_from_slice(&target.to_le_bytes());
+    }
+    let checksum = fnv1a(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    bytes
+}
+
+/// Deserializes a graph from the cache format.
+///
+/// # Throws
+/// CacheError::InvalidFormat if the header, the version, the length, the checksum or the adjacency is not valid.
+///
+/// # Example
+/// ```rust
+/// use vertex::cache::{graph_from_bytes, graph_to_bytes};
+/// use vertex::csr::CsrGraph;
+///
+/// let graph = CsrGraph::from_edges(3, &[(0, 1), (1, 2)]);
+/// let mut bytes = graph_to_bytes(&graph);
+/// assert_eq!(graph_from_bytes(&bytes).unwrap(), graph);
+///
+/// bytes[30] ^= 1;
+/// assert!(graph_from_bytes(&bytes).is_err());
+/// ```
+pub fn graph_from_bytes(bytes: &[u8]) -> Result<CsrGraph, CacheError> {
+    if bytes.len() < HEADER_LEN + 8 || &bytes[0..4] != MAGIC {
+        return Err(CacheError::InvalidFormat("Not a graph cache file".to_string()));
+    }
+    let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    if version != CACHE_VERSION {
+        return Err(CacheError::InvalidFormat(format!("Unsupported cache version {} (expecting {})",
+                                                     version, CACHE_VERSION)));
+    }
+    let (content, checksum) = bytes.split_at(bytes.len() - 8);
+    if fnv1a(content) != u64::from_le_bytes(checksum.try_into().unwrap()) {
+        return Err(CacheError::InvalidFormat("Checksum mismatch".to_string()));
+    }
+
+    let order = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) as usize;
+    let size = u64::from_le_bytes(bytes[16..24].try_into().unwrap()) as usize;
+    let expected_len = order.checked_add(1).and_then(|n| n.checked_mul(8))
+        .zip(size.checked_mul(8))
+        .and_then(|(a, b)| a.checked_add(b))
+        .and_then(|n| n.checked_add(HEADER_LEN));
+    if expected_len != Some(content.len()) {
+        return Err(CacheError::InvalidFormat(format!("Expecting {:?} bytes for order {} and size {} but read {}",
+                                                     expected_len, order, size, content.len())));
+    }
+
+    let offsets_end = HEADER_LEN + 8 * (order + 1);
+    let offsets = content[HEADER_LEN..offsets_end]
+        .chunks_exact(8)
+        .map(|c| u64::from_le_bytes(c.try_into().unwrap()) as usize)
+        .collect();
+    let targets = content[offsets_end..]
+        .chunks_exact(4)
+        .map(|c| u32::from_le_bytes(c.try_into().unwrap()))
+        .collect();
+    CsrGraph::from_raw_parts(offsets, targets)
+}
+
+/// Saves a graph in a cache file.
+///
+/// # Throws
+/// CacheError::IoError if the file cannot be created or written.
+pub fn save_graph(path: &str, graph: &CsrGraph) -> Result<(), CacheError> {
+    let mut file = match File::create(path) {
+        Ok(file) => file,
+        Err(e) => return Err(CacheError::IoError(format!("unable to create file {:?}", path), e))
+    };
+    file.write_all(&graph_to_bytes(graph))?;
+    Ok(())
+}
+
+/// Loads a graph from a cache file.
+///
+/// # Throws
+/// - CacheError::IoError if the file cannot be opened or read
+/// - CacheError::InvalidFormat if the content of the file is not valid
+pub fn load_graph(path: &str) -> Result<CsrGraph, CacheError> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => return Err(CacheError::IoError(format!("unable to open file {:?}", path), e))
+    };
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    graph_from_bytes(&bytes)
+}
+
+/// Load a graph from a DIMACS file, using its cache when it is fresh.
+///
+/// The cache is used if it exists, is not older than the DIMACS file and is valid. Otherwise, the DIMACS file
+/// is parsed with [`load_clq_csr`] and the cache is (re)written. Failing to write the cache is not an error.
+///
+/// # Throws
+/// InvalidClqFileFormat if the cache cannot be used and the DIMACS file is not in the correct format.
+///
+/// # Example
+/// ```rust
+/// use vertex::cache::load_clq_cached;
+///
+/// let graph = load_clq_cached("src/resources/graphs/test.clq").unwrap();
+/// assert_eq!(graph.order(), 5);
+/// assert_eq!(graph.size(), 6);
+/// ```
+pub fn load_clq_cached(path: &str) -> Result<CsrGraph, InvalidClqFileFormat> {
+    load_clq_cached_with_stats(path).map(|(graph, _)| graph)
+}
+
+/// Same as [`load_clq_cached`], along with the statistics of the loading (of the cache file if it was used).
+///
+/// # Throws
+/// InvalidClqFileFormat if the cache cannot be used and the DIMACS file is not in the correct format.
+pub fn load_clq_cached_with_stats(path: &str) -> Result<(CsrGraph, LoadStats), InvalidClqFileFormat> {
+    let start = Instant::now();
+    let cache = cache_path(path);
+    if is_fresh(path, &cache) {
+        if let Ok(graph) = load_graph(&cache.to_string_lossy()) {
+            let stats = LoadStats {
+                bytes: std::fs::metadata(&cache).map_or(0, |m| m.len()),
+                lines: 0,
+                edges: graph.size(),
+                duration: start.elapsed(),
+                from_cache: true,
+            };
+            return Ok((graph, stats));
+        }
+    }
+    let (graph, stats) = load_clq_csr(path)?;
+    let _ = save_graph(&cache.to_string_lossy(), &graph);
+    Ok((graph, stats))
+}
+
+/// Returns true if the cache exists and was modified after the source file.
+fn is_fresh(path: &str, cache: &PathBuf) -> bool {
+    let source_time = std::fs::metadata(path).and_then(|m| m.modified());
+    let cache_time = std::fs::metadata(cache).and_then(|m| m.modified());
+    match (source_time, cache_time) {
+        (Ok(source), Ok(cache)) => cache >= source,
+        _ => false,
+    }
+}
+
+/// 64-bit FNV-1a hash of a sequence of bytes.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod cache_tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let (graph, _) = load_clq_csr("src/resources/graphs/myciel4.clq").unwrap();
+        let path = std::env::temp_dir().join(format!("mvc_cache_test_{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        save_graph(path, &graph).unwrap();
+        assert_eq!(load_graph(path).unwrap(), graph);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_cache() {
+        let graph = CsrGraph::from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+        let bytes = graph_to_bytes(&graph);
+
+        assert!(matches!(graph_from_bytes(&bytes[..10]), Err(CacheError::InvalidFormat(_))));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(graph_from_bytes(&bad_magic), Err(CacheError::InvalidFormat(_))));
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 2;
+        let err = graph_from_bytes(&bad_version).unwrap_err();
+        assert_eq!(err.to_string(), "Unsupported cache version 2 (expecting 1)");
+
+        let mut truncated = bytes[..bytes.len() - 12].to_vec();
+        let checksum = fnv1a(&truncated);
+        truncated.extend_from_slice(&checksum.to_le_bytes());
+        assert!(matches!(graph_from_bytes(&truncated), Err(CacheError::InvalidFormat(_))));
+
+        // Corrupted offsets with a valid checksum : the offsets of the vertex 1 are decreasing
+        let mut corrupted = bytes[..bytes.len() - 8].to_vec();
+        corrupted[HEADER_LEN + 8..HEADER_LEN + 16].copy_from_slice(&6u64.to_le_bytes());
+        let checksum = fnv1a(&corrupted);
+        corrupted.extend_from_slice(&checksum.to_le_bytes());
+        let err = graph_from_bytes(&corrupted).unwrap_err();
+        assert_eq!(err.to_string(), "The offsets of the vertex 1 are decreasing");
+
+        assert!(matches!(load_graph("unknown_file.bin"), Err(CacheError::IoError(_, _))));
+    }
+
+    #[test]
+    fn test_load_clq_cached_refreshes_cache() {
+        let dir = std::env::temp_dir().join(format!("mvc_cache_dir_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("test.clq");
+        std::fs::copy("src/resources/graphs/test.clq", &source).unwrap();
+        let source = source.to_str().unwrap();
+
+        let (graph, stats) = load_clq_cached_with_stats(source).unwrap();
+        assert!(!stats.from_cache);
+        assert!(cache_path(source).exists());
+        let (cached, stats) = load_clq_cached_with_stats(source).unwrap();
+        assert_eq!(cached, graph);
+        assert!(stats.from_cache);
+        assert_eq!(stats.edges, 6);
+
+        // A corrupted cache is ignored and rewritten
+        std::fs::write(cache_path(source), b"garbage").unwrap();
+        assert_eq!(load_clq_cached(source).unwrap(), graph);
+        assert_eq!(load_graph(&cache_path(source).to_string_lossy()).unwrap(), graph);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,310 @@
+//! Checkpoint and resume of long branch and bound runs.
+//!
+//! The search periodically writes its open subproblems, its incumbent and its statistics in a YAML checkpoint file.
+//! A subproblem is stored as the vertices fixed in and out of the vertex cover : its subgraph is rebuilt from the graph
+//! on resume. The checkpoint holds the fingerprint of the graph, so that a run is only resumed on the same graph.
+//!
+//! The file is replaced atomically (see [`crate::persist`]) : a run killed while writing it leaves the previous
+//! checkpoint intact.
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use petgraph::prelude::UnGraphMap;
+use serde::{Deserialize, Serialize};
+
+use crate::branch_and_bound::{b_and_b, OpenNode};
+use crate::{BnbOptions, check_vertex_cover, Clock};
+use crate::errors::{MvcError, YamlError};
+use crate::fingerprint::GraphFingerprint;
+use crate::persist::write_yaml;
+
+/// Default time between 2 checkpoints.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Subproblem of the branch and bound : the vertices fixed in the vertex cover (in the order they were added) and the
+/// vertices fixed out of it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subproblem {
+    pub fixed_in: Vec<u64>,
+    pub fixed_out: Vec<u64>,
+}
+
+/// State of a branch and bound run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Fingerprint of the graph the search runs on
+    pub graph: GraphFingerprint,
+    /// Smallest vertex cover found so far
+    pub incumbent: Vec<u64>,
+    /// Subproblems left to explore, the next one last
+    pub open: Vec<Subproblem>,
+    /// Number of search nodes explored by all the runs
+    pub nodes: u64,
+    /// Time spent by all the runs (in seconds)
+    pub time: f64,
+    /// Time spent in each subroutine by all the runs (in seconds)
+    pub subroutines: BTreeMap<String, f64>,
+    /// Number of subtrees pruned by each rule in all the runs (absent from the checkpoints of older versions)
+    #[serde(default)]
+    pub pruned: BTreeMap<String, u64>,
+}
+
+impl Checkpoint {
+    /// Creates the checkpoint of a run that has not started yet : the whole graph is open and the incumbent holds
+    /// every vertex.
+    pub fn new(graph: &UnGraphMap<u64, ()>) -> Checkpoint {
+        Checkpoint {
+            graph: GraphFingerprint::new(graph),
+            incumbent: graph.nodes().collect(),
+            open: vec![Subproblem::default()],
+            nodes: 0,
+            time: 0.0,
+            subroutines: BTreeMap::new(),
+            pruned: BTreeMap::new(),
+        }
+    }
+
+    /// Returns true if the search is over : the incumbent is a minimum vertex cover.
+    pub fn is_complete(&self) -> bool {
+        self.open.is_empty()
+    }
+
+    /// Loads a checkpoint from a YAML file.
+    ///
+    /// # Throws
+    /// - YamlError::IoError if the file cannot be opened
+    /// - YamlError::YAMLParsingError if the file is not a checkpoint
+    pub fn load(path: &Path) -> Result<Checkpoint, YamlError> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => return Err(YamlError::IoError(format!("unable to open checkpoint {:?}", path), e))
+        };
+        Ok(serde_yaml::from_reader(file)?)
+    }
+
+    /// Saves the checkpoint in a YAML file, replacing it atomically.
+    ///
+    /// # Throws
+    /// YamlError if the checkpoint cannot be serialized or written.
+    pub fn save(&self, path: &Path) -> Result<(), YamlError> {
+        write_yaml(path, &serde_yaml::to_string(self)?)
+    }
+
+    /// Checks that the checkpoint was written for a graph.
+    ///
+    /// # Throws
+    /// YamlError::FingerprintMismatch if the fingerprint of the graph does not match the stored one.
+    pub fn check_graph(&self, graph: &UnGraphMap<u64, ()>) -> Result<(), YamlError> {
+        let fingerprint = GraphFingerprint::new(graph);
+        if self.graph.matches(&fingerprint) {
+            Ok(())
+        } else {
+            Err(YamlError::FingerprintMismatch(
+                "The checkpoint was written for another graph".to_string(),
+                format!("stored {}, computed {}", self.graph, fingerprint)))
+        }
+    }
+}
+
+/// Where and how often a branch and bound run is checkpointed.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::checkpoint::{CheckpointOptions, DEFAULT_INTERVAL};
+///
+/// let options = CheckpointOptions::new("run.ckpt.yml").with_interval(Duration::from_secs(300)).with_resume(true);
+/// assert_eq!(options.interval, Duration::from_secs(300));
+/// assert_eq!(CheckpointOptions::new("run.ckpt.yml").interval, DEFAULT_INTERVAL);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointOptions {
+    pub path: PathBuf,
+    /// Time between 2 checkpoints
+    pub interval: Duration,
+    /// Resume the run from the checkpoint file instead of starting a new one
+    pub resume: bool,
+    /// Options of the branch and bound. They can change when the run is resumed.
+    pub bnb: BnbOptions,
+}
+
+impl CheckpointOptions {
+    /// Checkpoints a new run in a file every [`DEFAULT_INTERVAL`].
+    pub fn new<P: Into<PathBuf>>(path: P) -> CheckpointOptions {
+        CheckpointOptions { path: path.into(), interval: DEFAULT_INTERVAL, resume: false, bnb: BnbOptions::default() }
+    }
+
+    /// Sets the time between 2 checkpoints.
+    pub fn with_interval(self, interval: Duration) -> CheckpointOptions {
+        CheckpointOptions { interval, ..self }
+    }
+
+    /// Sets whether the run is resumed from the checkpoint file.
+    pub fn with_resume(self, resume: bool) -> CheckpointOptions {
+        CheckpointOptions { resume, ..self }
+    }
+
+    /// Sets the options of the branch and bound.
+    pub fn with_bnb_options(self, bnb: BnbOptions) -> CheckpointOptions {
+        CheckpointOptions { bnb, ..self }
+    }
+}
+
+/// Branch and bound (see [`crate::branch_and_bound_with`], with `options.bnb`) that writes a checkpoint every
+/// `options.interval` and when it ends, stopped by a limit or not. With `options.resume`, the search continues from the checkpoint file.
+///
+/// The limits of the clock apply to this run only, while the statistics of the checkpoint add up the runs. Once the
+/// search is over, the checkpoint has no open subproblem left and resuming it returns the minimum vertex cover at once.
+///
+/// # Throws
+/// - MvcError::Yaml if the checkpoint cannot be read or written, or if it was written for another graph
+///   (YamlError::FingerprintMismatch)
+/// - the errors of [`crate::branch_and_bound`]
+///
+/// # Example
+/// ```rust
+/// use vertex::{Clock, Limits};
+/// use vertex::checkpoint::{Checkpoint, checkpointed_branch_and_bound, CheckpointOptions};
+/// use vertex::graph_utils::load_clq_file;
+///
+/// let graph = load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+/// let path = std::env::temp_dir().join(format!("mvc_checkpoint_example_{}.yml", std::process::id()));
+/// let options = CheckpointOptions::new(path);
+///
+/// // The first run is stopped after 5 nodes...
+/// let mut clock = Clock::with_limits(Limits::seconds(60).with_node_limit(5));
+/// checkpointed_branch_and_bound(&graph, &mut clock, &options).unwrap();
+/// assert!(!Checkpoint::load(&options.path).unwrap().is_complete());
+///
+/// // ...and the second one ends the search
+/// let (value, _) = checkpointed_branch_and_bound(&graph, &mut Clock::new(60), &options.clone().with_resume(true))
+///     .unwrap();
+/// assert_eq!(value, 12);
+/// assert!(Checkpoint::load(&options.path).unwrap().is_complete());
+/// # std::fs::remove_file(&options.path).unwrap();
+/// ```
+pub fn checkpointed_branch_and_bound(graph: &UnGraphMap<u64, ()>, clock: &mut Clock, options: &CheckpointOptions)
+                                     -> Result<(u64, Vec<u64>), MvcError> {
+    let previous = if options.resume {
+        let checkpoint = Checkpoint::load(&options.path)?;
+        checkpoint.check_graph(graph)?;
+        checkpoint
+    } else {
+        Checkpoint::new(graph)
+    };
+
+    let mut best = previous.incumbent.clone();
+    let mut open: Vec<OpenNode> = previous.open.iter()
+        .map(|fixed| OpenNode::from_subproblem(graph, fixed.clone()))
+        .collect();
+    let mut last_save = Instant::now();
+    let save = |open: &[OpenNode], best: &[u64], clock: &Clock| {
+        if last_save.elapsed() < options.interval {
+            return Ok(());
+        }
+        last_save = Instant::now();
+        Ok(to_checkpoint(&previous, open, best, clock).save(&options.path)?)
+    };
+    b_and_b(&mut open, &mut best, clock, &options.bnb, save)?;
+    to_checkpoint(&previous, &open, &best, clock).save(&options.path)?;
+
+    let res = (best.len() as u64, best);
+    check_vertex_cover(graph, &res)?;
+    Ok(res)
+}
+
+/// Adds the state of the current run to the checkpoint of the previous runs.
+fn to_checkpoint(previous: &Checkpoint, open: &[OpenNode], best: &[u64], clock: &Clock) -> Checkpoint {
+    let mut subroutines = previous.subroutines.clone();
+    for (name, duration) in clock.subroutines() {
+        *subroutines.entry(name).or_insert(0.0) += duration.as_secs_f64();
+    }
+    let mut pruned = previous.pruned.clone();
+    for (rule, subtrees) in clock.pruned() {
+        *pruned.entry(rule.clone()).or_insert(0) += subtrees;
+    }
+    Checkpoint {
+        graph: previous.graph,
+        incumbent: best.to_vec(),
+        open: open.iter().map(|node| node.fixed.clone()).collect(),
+        nodes: previous.nodes + clock.nodes(),
+        time: previous.time + clock.get_time().duration.as_secs_f64(),
+        subroutines,
+        pruned,
+    }
+}
+
+#[cfg(test)]
+mod checkpoint_tests {
+    use crate::{branch_and_bound, Limits};
+    use crate::graph_utils::{is_vertex_cover, load_clq_file};
+
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("mvc_checkpoint_{}_{}.yml", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_resume_until_complete() {
+        let graph = load_clq_file("src/resources/graphs/queen5_5.clq").unwrap();
+        let options = CheckpointOptions::new(temp_path("resume")).with_interval(Duration::ZERO);
+        let mut clock = Clock::with_limits(Limits::seconds(3600).with_node_limit(10));
+        checkpointed_branch_and_bound(&graph, &mut clock, &options).unwrap();
+
+        // Resume several times with a small node budget
+        let options = options.with_resume(true);
+        let mut runs = 1;
+        while !Checkpoint::load(&options.path).unwrap().is_complete() {
+            let mut clock = Clock::with_limits(Limits::seconds(3600).with_node_limit(5));
+            checkpointed_branch_and_bound(&graph, &mut clock, &options).unwrap();
+            runs += 1;
+        }
+        let checkpoint = Checkpoint::load(&options.path).unwrap();
+        std::fs::remove_file(&options.path).unwrap();
+
+        assert!(runs > 2);
+        assert_eq!(checkpoint.incumbent.len(), 20);
+        assert!(is_vertex_cover(&graph, &checkpoint.incumbent));
+        // The resumed runs explore the same tree as a single run
+        let mut clock = Clock::new(3600);
+        let res = branch_and_bound(&graph, &mut clock).unwrap();
+        assert_eq!(checkpoint.incumbent, res.1);
+        assert_eq!(checkpoint.nodes, clock.nodes());
+    }
+
+    #[test]
+    fn test_subproblems_of_checkpoint() {
+        let graph = load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+        let options = CheckpointOptions::new(temp_path("subproblems"));
+        let mut clock = Clock::with_limits(Limits::seconds(3600).with_node_limit(4));
+        checkpointed_branch_and_bound(&graph, &mut clock, &options).unwrap();
+        let checkpoint = Checkpoint::load(&options.path).unwrap();
+        std::fs::remove_file(&options.path).unwrap();
+
+        assert_eq!(checkpoint.nodes, 4);
+        assert_eq!(checkpoint.graph, GraphFingerprint::new(&graph));
+        assert!(!checkpoint.open.is_empty());
+        for subproblem in &checkpoint.open {
+            // Each vertex fixed out of the cover has all its neighbours in it
+            for v in &subproblem.fixed_out {
+                assert!(graph.neighbors(*v).all(|n| subproblem.fixed_in.contains(&n)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_resume_on_another_graph() {
+        let graph = load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+        let options = CheckpointOptions::new(temp_path("mismatch"));
+        let mut clock = Clock::with_limits(Limits::seconds(3600).with_node_limit(1));
+        checkpointed_branch_and_bound(&graph, &mut clock, &options).unwrap();
+
+        let other = load_clq_file("src/resources/graphs/test.clq").unwrap();
+        let res = checkpointed_branch_and_bound(&other, &mut Clock::new(60), &options.clone().with_resume(true));
+        std::fs::remove_file(&options.path).unwrap();
+        assert!(matches!(res, Err(MvcError::Yaml(YamlError::FingerprintMismatch(_, _)))));
+    }
+}
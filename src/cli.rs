@@ -0,0 +1,305 @@
+//! Helpers of the `mvc` bin : parsing of the options, exit codes, input graphs, solutions and outputs.
+//!
+//! Every subcommand accepts the same flags for the same things (`--time-limit <seconds>`, `--solver <name>`,
+//! `--format <format>`, `--output <file>` and `--workspace <path>`), and the graphs are given either as a path or as
+//! the id of an instance of the workspace.
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use itertools::Itertools;
+use petgraph::prelude::UnGraphMap;
+
+use crate::{BnbOptions, Branching};
+use crate::cache::load_clq_cached_with_stats;
+use crate::csr::LoadStats;
+use crate::errors::CliError;
+use crate::workspace::Workspace;
+
+/// The command succeeded.
+pub const EXIT_SUCCESS: i32 = 0;
+/// The command ran but its check failed (invalid solution, regression...).
+pub const EXIT_FAILURE: i32 = 1;
+/// The command line is not valid.
+pub const EXIT_USAGE: i32 = 2;
+/// An input cannot be read or an output cannot be written.
+pub const EXIT_ERROR: i32 = 3;
+/// The solver was stopped by its time limit or its node budget, so its value is not proven optimal.
+pub const EXIT_TIME_LIMIT: i32 = 4;
+
+impl CliError {
+    /// Returns the exit code of the bin for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_, _) => EXIT_USAGE,
+            CliError::IoError(_, _) | CliError::Solver(_, _) => EXIT_ERROR,
+        }
+    }
+}
+
+/// Options of a command, parsed from its arguments.
+///
+/// The options are declared with their number of values (0 for a flag) and can be repeated. The other arguments
+/// are the positional arguments of the command.
+///
+/// # Example
+/// ```rust
+/// use vertex::cli::Args;
+///
+/// let args: Vec<String> = ["test.clq", "--time-limit", "10", "--record", "--series", "a", "b"]
+///     .iter().map(|s| s.to_string()).collect();
+/// let options = Args::parse(&args, &[("--time-limit", 1), ("--record", 0), ("--series", 2)], "usage").unwrap();
+///
+/// assert_eq!(options.positional(), ["test.clq"]);
+/// assert_eq!(options.parse_value("--time-limit", 60u64).unwrap(), 10);
+/// assert!(options.flag("--record"));
+/// assert_eq!(options.all("--series"), [vec!["a".to_string(), "b".to_string()]]);
+/// assert!(Args::parse(&args, &[], "usage").is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    positional: Vec<String>,
+    options: BTreeMap<String, Vec<Vec<String>>>,
+    usage: String,
+}
+
+impl Args {
+    /// Parses the arguments of a command.
+    ///
+    /// # Throws
+    /// CliError::Usage if an option is unknown or does not have enough values.
+    pub fn parse(args: &[String], spec: &[(&str, usize)], usage: &str) -> Result<Args, CliError> {
+        let mut parsed = Args { usage: usage.to_string(), ..Args::default() };
+        let mut i = 0;
+        while i < args.len() {
+            let arg = &args[i];
+            if !arg.starts_with("--") {
+                parsed.positional.push(arg.clone());
+                i += 1;
+                continue;
+            }
+            let arity = match spec.iter().find(|(name, _)| name == arg) {
+                Some((_, arity)) => *arity,
+                None => return Err(parsed.usage_error(&format!("Unknown option {:?}", arg))),
+            };
+            if arity > 0 && i + arity >= args.len() {
+                return Err(parsed.usage_error(&format!("Missing value after {}", arg)));
+            }
+            parsed.options.entry(arg.clone()).or_default().push(args[i + 1..i + 1 + arity].to_vec());
+            i += 1 + arity;
+        }
+        Ok(parsed)
+    }
+
+    /// Returns a usage error with the usage of the command.
+    pub fn usage_error(&self, message: &str) -> CliError {
+        CliError::Usage(message.to_string(), self.usage.clone())
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    /// Returns the positional arguments, or an error if there are not between min and max of them.
+    ///
+    /// # Throws
+    /// CliError::Usage if the number of positional arguments is not valid.
+    pub fn expect_positional(&self, min: usize, max: usize) -> Result<&[String], CliError> {
+        match self.positional.len() {
+            n if n < min => Err(self.usage_error("Missing argument")),
+            n if n > max => Err(self.usage_error(&format!("Unexpected argument {:?}", self.positional[max]))),
+            _ => Ok(&self.positional),
+        }
+    }
+
+    /// Returns true if the flag is given.
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    /// Returns the values of every occurrence of an option.
+    pub fn all(&self, name: &str) -> &[Vec<String>] {
+        self.options.get(name).map_or(&[], |v| v.as_slice())
+    }
+
+    /// Returns the first value of the last occurrence of an option.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.all(name).last().and_then(|values| values.first()).map(|v| v.as_str())
+    }
+
+    /// Parses the value of an option, or returns the default value if the option is not given.
+    ///
+    /// # Throws
+    /// CliError::Usage if the value cannot be parsed.
+    pub fn parse_value<T: FromStr>(&self, name: &str, default: T) -> Result<T, CliError> where T::Err: Display {
+        match self.value(name) {
+            Some(value) => value.parse()
+                .map_err(|e| self.usage_error(&format!("Invalid value {:?} for {} : {}", value, name, e))),
+            None => Ok(default),
+        }
+    }
+
+    /// Parses a duration given in seconds, with sub-second precision (ex: `--time-limit 0.25`), or returns the
+    /// default duration if the option is not given.
+    ///
+    /// # Throws
+    /// CliError::Usage if the value is not a non-negative number of seconds.
+    pub fn parse_duration(&self, name: &str, default: Duration) -> Result<Duration, CliError> {
+        match self.value(name) {
+            Some(value) => value.parse::<f64>().ok()
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                .ok_or_else(|| self.usage_error(&format!("Invalid value {:?} for {} : expecting seconds", value, name))),
+            None => Ok(default),
+        }
+    }
+
+    /// Parses the options of the branch and bound : `--branching <strategy>`, `--degree-two`, `--dominance` and
+    /// `--symmetry`.
+    ///
+    /// # Throws
+    /// CliError::Usage if the branching strategy is unknown.
+    ///
+    /// # Example
+    /// ```rust
+    /// use vertex::{BnbOptions, Branching};
+    /// use vertex::cli::Args;
+    ///
+    /// let args: Vec<String> = ["--branching", "mirror"].iter().map(|s| s.to_string()).collect();
+    /// let options = Args::parse(&args, &[("--branching", 1), ("--degree-two", 0)], "usage").unwrap();
+    /// assert_eq!(options.parse_bnb_options().unwrap(), BnbOptions::new().with_branching(Branching::Mirror));
+    /// ```
+    pub fn parse_bnb_options(&self) -> Result<BnbOptions, CliError> {
+        Ok(BnbOptions::new()
+            .with_branching(self.parse_value("--branching", Branching::default())?)
+            .with_degree_two(self.flag("--degree-two"))
+            .with_dominance(self.flag("--dominance"))
+            .with_symmetry(self.flag("--symmetry")))
+    }
+}
+
+/// Returns the path of an input graph : the path itself if the file exists, the instance of the workspace otherwise.
+pub fn graph_path(ws: &Workspace, input: &str) -> PathBuf {
+    let path = Path::new(input);
+    if path.exists() {
+        path.to_path_buf()
+    } else {
+        ws.instance_path(input)
+    }
+}
+
+/// Returns the id of a graph in the database (the name of its file). Example : "test.clq"
+pub fn graph_id(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// Loads a graph in the DIMACS format, with its cache (see [`crate::cache`]).
+///
+/// # Throws
+/// CliError::IoError if the file cannot be loaded.
+pub fn load_graph(path: &Path) -> Result<UnGraphMap<u64, ()>, CliError> {
+    load_graph_with_stats(path).map(|(graph, _)| graph)
+}
+
+/// Same as [`load_graph`], along with the statistics of the loading (size read, throughput...).
+///
+/// # Throws
+/// CliError::IoError if the file cannot be loaded.
+pub fn load_graph_with_stats(path: &Path) -> Result<(UnGraphMap<u64, ()>, LoadStats), CliError> {
+    load_clq_cached_with_stats(&path.to_string_lossy())
+        .map(|(g, stats)| (g.to_graph_map(), stats))
+        .map_err(|e| CliError::IoError(format!("Unable to load the graph {:?} : {}", path, e), format!("{:?}", e)))
+}
+
+/// Writes the output of a command to a file, or to the standard output if there is no file.
+///
+/// # Throws
+/// CliError::IoError if the file cannot be written.
+pub fn write_output(output: Option<&str>, content: &str) -> Result<(), CliError> {
+    match output {
+        Some(path) => File::create(path).and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(|e| CliError::IoError(format!("Unable to write {:?} : {}", path, e), format!("{:?}", e))),
+        None => {
+            print!("{}", content);
+            Ok(())
+        }
+    }
+}
+
+/// Parses a solution : the vertices are numbered from 1 like in the DIMACS files and separated by spaces, commas or
+/// new lines. The lines starting with `c` or `#` are comments. The vertices are returned numbered from 0.
+///
+/// # Throws
+/// CliError::IoError if a vertex is not a positive number.
+///
+/// # Example
+/// ```rust
+/// use vertex::cli::{parse_solution, solution_to_string};
+///
+/// let solution = parse_solution("c Vertex cover of test.clq\n1 3\n5\n").unwrap();
+/// assert_eq!(solution, vec![0, 2, 4]);
+/// assert_eq!(solution_to_string(&solution), "1 3 5\n");
+/// assert!(parse_solution("0 1").is_err());
+/// ```
+pub fn parse_solution(content: &str) -> Result<Vec<u64>, CliError> {
+    content.lines()
+        .filter(|line| !line.starts_with('c') && !line.starts_with('#'))
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|token| !token.is_empty())
+        .map(|token| match token.parse::<u64>() {
+            Ok(v) if v > 0 => Ok(v - 1),
+            _ => Err(CliError::IoError(format!("Invalid vertex {:?} in the solution", token),
+                                       "The vertices are numbered from 1".to_string())),
+        })
+        .collect()
+}
+
+/// Writes a solution in the format read by [`parse_solution`].
+pub fn solution_to_string(set: &[u64]) -> String {
+    format!("{}\n", set.iter().sorted().map(|v| v + 1).join(" "))
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let spec = [("--solver", 1), ("--hide", 0)];
+        let err = Args::parse(&args(&["--solver"]), &spec, "usage").unwrap_err();
+        assert_eq!(err.exit_code(), EXIT_USAGE);
+        assert!(Args::parse(&args(&["--solver", "bnb", "--hide"]), &spec, "usage").is_ok());
+
+        let options = Args::parse(&args(&["a", "b", "--solver", "x", "--solver", "y"]), &spec, "usage").unwrap();
+        assert_eq!(options.value("--solver"), Some("y"));
+        assert_eq!(options.all("--solver").len(), 2);
+        assert!(options.expect_positional(1, 1).is_err());
+        assert_eq!(options.expect_positional(2, 3).unwrap().len(), 2);
+        assert!(options.parse_value::<u64>("--solver", 0).is_err());
+        assert_eq!(options.parse_value::<u64>("--time-limit", 7).unwrap(), 7);
+
+        let options = Args::parse(&args(&["--solver", "0.25"]), &spec, "usage").unwrap();
+        assert_eq!(options.parse_duration("--solver", Duration::ZERO).unwrap(), Duration::from_millis(250));
+        assert_eq!(options.parse_duration("--hide", Duration::from_secs(3)).unwrap(), Duration::from_secs(3));
+        let options = Args::parse(&args(&["--solver", "-1"]), &spec, "usage").unwrap();
+        assert!(options.parse_duration("--solver", Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_load_graph() {
+        let ws = Workspace::default();
+        let path = graph_path(&ws, "test.clq");
+        assert_eq!(path, ws.instance_path("test.clq"));
+        assert_eq!(graph_id(&path), "test.clq");
+        assert_eq!(load_graph(&path).unwrap().node_count(), 5);
+
+        assert_eq!(load_graph(Path::new("unknown.clq")).unwrap_err().exit_code(), EXIT_ERROR);
+    }
+}
@@ -0,0 +1,359 @@
+//! Comparison of two sets of runs stored in the instance database, to detect regressions between two versions
+//! of an algorithm.
+//!
+//! The runs of each side are selected with a [`RunSelector`] (by comment, date or algorithm). For every instance
+//! and problem having a run on either side, the last selected run of each side is compared (see [`compare_runs`]).
+use std::fmt;
+use std::str::FromStr;
+
+use crate::database::{InstanceDatabase, Problem, RunRecord, RunStatus};
+
+/// Criteria selecting runs of the database. A run is selected if it matches every criterion that is set.
+///
+/// A selector is written as a comma separated list of `key=value` criteria :
+/// * `comment=<text>` : the comment of the run is exactly the text
+/// * `date=<prefix>` : the date of the run starts with the prefix (ex: `date=2023-12-27`)
+/// * `date=<from>..<to>` : the date of the run is between the two prefixes (inclusive)
+/// * `algorithm=<name>` : the run was made by the algorithm
+///
+/// A text without `=` selects the runs by comment.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::compare::RunSelector;
+/// use vertex::database::{Problem, RunRecord};
+///
+/// let mut run = RunRecord::new(Problem::Mvc, "bnb", 3, Duration::from_millis(5), false, "Multithreaded lower bound");
+/// run.date = "2023-12-27 10:12:00".to_string();
+///
+/// assert!("Multithreaded lower bound".parse::<RunSelector>().unwrap().matches(&run));
+/// assert!("date=2023-12-20..2023-12-27,algorithm=bnb".parse::<RunSelector>().unwrap().matches(&run));
+/// assert!(!"date=2023-12-28..".parse::<RunSelector>().unwrap().matches(&run));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunSelector {
+    pub comment: Option<String>,
+    /// First date selected (prefix of the date of the runs)
+    pub date_from: Option<String>,
+    /// Last date selected (prefix of the date of the runs)
+    pub date_to: Option<String>,
+    pub algorithm: Option<String>,
+}
+
+impl RunSelector {
+    /// Returns true if the run matches every criterion of the selector.
+    pub fn matches(&self, run: &RunRecord) -> bool {
+        let date_prefix = |len: usize| &run.date[..len.min(run.date.len())];
+        self.comment.as_ref().is_none_or(|c| *c == run.comment)
+            && self.algorithm.as_ref().is_none_or(|a| *a == run.algorithm)
+            && self.date_from.as_ref().is_none_or(|from| date_prefix(from.len()) >= from.as_str())
+            && self.date_to.as_ref().is_none_or(|to| date_prefix(to.len()) <= to.as_str())
+    }
+}
+
+impl FromStr for RunSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut selector = RunSelector::default();
+        if !s.contains('=') {
+            selector.comment = Some(s.to_string());
+            return Ok(selector);
+        }
+        for criterion in s.split(',') {
+            let (key, value) = criterion.split_once('=')
+                .ok_or_else(|| format!("Invalid criterion {:?} (expecting key=value)", criterion))?;
+            let value = value.to_string();
+            match key.trim() {
+                "comment" => selector.comment = Some(value),
+                "algorithm" => selector.algorithm = Some(value),
+                "date" => match value.split_once("..") {
+                    Some((from, to)) => {
+                        selector.date_from = Some(from.to_string()).filter(|d| !d.is_empty());
+                        selector.date_to = Some(to.to_string()).filter(|d| !d.is_empty());
+                    }
+                    None => {
+                        selector.date_from = Some(value.clone());
+                        selector.date_to = Some(value);
+                    }
+                },
+                _ => return Err(format!("Unknown criterion {:?} (expecting comment, date or algorithm)", key)),
+            }
+        }
+        Ok(selector)
+    }
+}
+
+/// Difference between the baseline run and the candidate run of an instance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    /// The candidate found a value different from the known optimal value
+    Wrong { expected: u64 },
+    /// Both runs finished but found different values (and the optimal value is unknown)
+    ValueChanged,
+    /// The baseline finished but not the candidate
+    NewlyTimedOut,
+    /// The candidate finished but not the baseline
+    NewlySolved,
+    /// None of the runs finished and the candidate found a worse value (or no solution)
+    WorseValue,
+    /// None of the runs finished and the candidate found a better value (or the baseline found no solution)
+    BetterValue,
+    /// Both runs finished and the candidate is slower than the allowed slowdown
+    Slower,
+}
+
+impl Change {
+    /// Returns true if the change is a regression of the candidate.
+    pub fn is_regression(&self) -> bool {
+        !matches!(self, Change::NewlySolved | Change::BetterValue)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Wrong { expected } => write!(f, "wrong value (expecting {})", expected),
+            Change::ValueChanged => write!(f, "value changed"),
+            Change::NewlyTimedOut => write!(f, "newly timed out"),
+            Change::NewlySolved => write!(f, "newly solved"),
+            Change::WorseValue => write!(f, "worse value"),
+            Change::BetterValue => write!(f, "better value"),
+            Change::Slower => write!(f, "slower"),
+        }
+    }
+}
+
+/// Comparison of the runs of an instance for a problem.
+#[derive(Debug, Clone)]
+pub struct InstanceComparison {
+    pub id: String,
+    pub problem: Problem,
+    pub baseline: Option<RunRecord>,
+    pub candidate: Option<RunRecord>,
+    /// Time of the baseline divided by the time of the candidate, when both runs finished
+    pub speedup: Option<f64>,
+    pub changes: Vec<Change>,
+}
+
+impl InstanceComparison {
+    /// Returns true if one of the changes is a regression.
+    pub fn is_regression(&self) -> bool {
+        self.changes.iter().any(Change::is_regression)
+    }
+}
+
+/// Options of a comparison.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CompareOptions {
+    /// Maximum slowdown (baseline time / candidate time >= 1 / max_slowdown) before a finished run is reported as
+    /// slower. Timings are not checked if None.
+    pub max_slowdown: Option<f64>,
+}
+
+/// Compares the runs selected by two selectors, instance by instance.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::compare::{Change, compare_runs, CompareOptions};
+/// use vertex::database::{InstanceDatabase, InstanceRecord, KnownValue, Problem, RunRecord};
+///
+/// let mut db = InstanceDatabase::new();
+/// let mut record = InstanceRecord::new("a.clq", "clq", 10, 20);
+/// record.set_known(Problem::Mvc, KnownValue::new(6, "paper"));
+/// record.runs.push(RunRecord::new(Problem::Mvc, "bnb", 6, Duration::from_secs(4), false, "v1"));
+/// record.runs.push(RunRecord::new(Problem::Mvc, "bnb", 6, Duration::from_secs(1), false, "v2"));
+/// record.runs.push(RunRecord::new(Problem::Mvc, "bnb", 5, Duration::from_secs(1), false, "v3"));
+/// db.insert(record);
+///
+/// let v2 = compare_runs(&db, &"v1".parse().unwrap(), &"v2".parse().unwrap(), CompareOptions::default());
+/// assert_eq!(v2[0].speedup, Some(4.0));
+/// assert!(!v2[0].is_regression());
+///
+/// let v3 = compare_runs(&db, &"v2".parse().unwrap(), &"v3".parse().unwrap(), CompareOptions::default());
+/// assert_eq!(v3[0].changes, vec![Change::Wrong { expected: 6 }]);
+/// ```
+pub fn compare_runs(db: &InstanceDatabase, baseline: &RunSelector, candidate: &RunSelector,
+                    options: CompareOptions) -> Vec<InstanceComparison> {
+    let mut comparisons = Vec::new();
+    for record in db.instances() {
+        for problem in [Problem::Mvc, Problem::Clique, Problem::Mis] {
+            let last = |selector: &RunSelector| record.runs.iter()
+                .filter(|r| r.problem == problem && selector.matches(r))
+                .max_by(|a, b| a.date.cmp(&b.date))
+                .cloned();
+            let (b, c) = (last(baseline), last(candidate));
+            if b.is_none() && c.is_none() {
+                continue;
+            }
+            let known = record.known(problem).map(|k| k.value);
+            let (speedup, changes) = compare_pair(problem, b.as_ref(), c.as_ref(), known, options);
+            comparisons.push(InstanceComparison {
+                id: record.id.clone(),
+                problem,
+                baseline: b,
+                candidate: c,
+                speedup,
+                changes,
+            });
+        }
+    }
+    comparisons
+}
+
+fn compare_pair(problem: Problem, baseline: Option<&RunRecord>, candidate: Option<&RunRecord>, known: Option<u64>,
+                options: CompareOptions) -> (Option<f64>, Vec<Change>) {
+    let mut changes = Vec::new();
+    let solved = |r: &RunRecord| r.status == RunStatus::Solved;
+
+    if let (Some(c), Some(expected)) = (candidate, known) {
+        if solved(c) && c.value != Some(expected) {
+            changes.push(Change::Wrong { expected });
+        }
+    }
+    let (b, c) = match (baseline, candidate) {
+        (Some(b), Some(c)) => (b, c),
+        _ => return (None, changes),
+    };
+
+    let mut speedup = None;
+    match (solved(b), solved(c)) {
+        (true, true) => {
+            if b.value != c.value && known.is_none() {
+                changes.push(Change::ValueChanged);
+            }
+            if c.time.as_secs_f64() > 0.0 {
+                speedup = Some(b.time.as_secs_f64() / c.time.as_secs_f64());
+            }
+            if let (Some(ratio), Some(max)) = (speedup, options.max_slowdown) {
+                if ratio * max < 1.0 {
+                    changes.push(Change::Slower);
+                }
+            }
+        }
+        (true, false) => changes.push(Change::NewlyTimedOut),
+        (false, true) => changes.push(Change::NewlySolved),
+        (false, false) => {
+            // Any solution is better than no solution. The vertex cover is minimized, the clique and the independent
+            // set are maximized.
+            let better = match (b.value, c.value) {
+                (Some(b), Some(c)) if b != c => Some((c < b) == (problem == Problem::Mvc)),
+                (Some(_), None) => Some(false),
+                (None, Some(_)) => Some(true),
+                _ => None,
+            };
+            if let Some(better) = better {
+                changes.push(if better { Change::BetterValue } else { Change::WorseValue });
+            }
+        }
+    }
+    (speedup, changes)
+}
+
+#[cfg(test)]
+mod compare_tests {
+    use std::time::Duration;
+
+    use crate::database::InstanceRecord;
+
+    use super::*;
+
+    fn run(problem: Problem, value: u64, secs: u64, status: RunStatus, comment: &str) -> RunRecord {
+        let mut run = RunRecord::new(problem, "bnb", value, Duration::from_secs(secs), status != RunStatus::Solved, comment);
+        run.status = status;
+        run
+    }
+
+    #[test]
+    fn test_changes() {
+        let mut db = InstanceDatabase::new();
+        let mut timed_out = InstanceRecord::new("timed_out.clq", "clq", 10, 20);
+        timed_out.runs.push(run(Problem::Clique, 4, 10, RunStatus::Solved, "old"));
+        timed_out.runs.push(run(Problem::Clique, 3, 60, RunStatus::Killed, "new"));
+        let mut solved = InstanceRecord::new("solved.clq", "clq", 10, 20);
+        solved.runs.push(run(Problem::Mvc, 7, 60, RunStatus::TimeLimit, "old"));
+        solved.runs.push(run(Problem::Mvc, 6, 30, RunStatus::Solved, "new"));
+        let mut worse = InstanceRecord::new("worse.clq", "clq", 10, 20);
+        worse.runs.push(run(Problem::Clique, 5, 60, RunStatus::TimeLimit, "old"));
+        worse.runs.push(run(Problem::Clique, 4, 60, RunStatus::TimeLimit, "new"));
+        let mut slower = InstanceRecord::new("slower.clq", "clq", 10, 20);
+        slower.runs.push(run(Problem::Mvc, 6, 1, RunStatus::Solved, "old"));
+        slower.runs.push(run(Problem::Mvc, 6, 3, RunStatus::Solved, "new"));
+        slower.runs.push(run(Problem::Mvc, 6, 3, RunStatus::Solved, "other"));
+        let mut only_new = InstanceRecord::new("only_new.clq", "clq", 10, 20);
+        only_new.runs.push(run(Problem::Mvc, 6, 3, RunStatus::Solved, "new"));
+        for record in [timed_out, solved, worse, slower, only_new] {
+            db.insert(record);
+        }
+
+        let options = CompareOptions { max_slowdown: Some(2.0) };
+        let comparisons = compare_runs(&db, &"old".parse().unwrap(), &"new".parse().unwrap(), options);
+        let changes: Vec<(&str, Vec<Change>)> = comparisons.iter().map(|c| (c.id.as_str(), c.changes.clone())).collect();
+        assert_eq!(changes, vec![
+            ("timed_out.clq", vec![Change::NewlyTimedOut]),
+            ("solved.clq", vec![Change::NewlySolved]),
+            ("worse.clq", vec![Change::WorseValue]),
+            ("slower.clq", vec![Change::Slower]),
+            ("only_new.clq", vec![]),
+        ]);
+        assert_eq!(comparisons[3].speedup, Some(1.0 / 3.0));
+        assert!(comparisons.iter().filter(|c| c.is_regression()).count() == 3);
+
+        // Timings are not checked by default
+        let comparisons = compare_runs(&db, &"old".parse().unwrap(), &"new".parse().unwrap(), CompareOptions::default());
+        assert!(!comparisons[3].is_regression());
+    }
+
+    #[test]
+    fn test_no_solution() {
+        let no_solution = |problem: Problem, status: RunStatus, comment: &str| {
+            let mut run = run(problem, 0, 60, status, comment);
+            run.value = None;
+            run
+        };
+        let mut db = InstanceDatabase::new();
+        let mut solved = InstanceRecord::new("solved.clq", "clq", 10, 20);
+        solved.runs.push(run(Problem::Mvc, 6, 30, RunStatus::Solved, "old"));
+        solved.runs.push(no_solution(Problem::Mvc, RunStatus::TimeLimit, "new"));
+        let mut stopped = InstanceRecord::new("stopped.clq", "clq", 10, 20);
+        stopped.runs.push(run(Problem::Mvc, 7, 60, RunStatus::TimeLimit, "old"));
+        stopped.runs.push(no_solution(Problem::Mvc, RunStatus::NodeLimit, "new"));
+        stopped.runs.push(no_solution(Problem::Clique, RunStatus::TimeLimit, "old"));
+        stopped.runs.push(run(Problem::Clique, 3, 60, RunStatus::TimeLimit, "new"));
+        for record in [solved, stopped] {
+            db.insert(record);
+        }
+
+        let comparisons = compare_runs(&db, &"old".parse().unwrap(), &"new".parse().unwrap(), CompareOptions::default());
+        let changes: Vec<(&str, Problem, Vec<Change>)> = comparisons.iter()
+            .map(|c| (c.id.as_str(), c.problem, c.changes.clone()))
+            .collect();
+        assert_eq!(changes, vec![
+            ("solved.clq", Problem::Mvc, vec![Change::NewlyTimedOut]),
+            ("stopped.clq", Problem::Mvc, vec![Change::WorseValue]),
+            ("stopped.clq", Problem::Clique, vec![Change::BetterValue]),
+        ]);
+        assert!(comparisons[0].is_regression() && comparisons[1].is_regression());
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        let selector: RunSelector = "comment=a=b,date=2023-12..".parse().unwrap();
+        assert_eq!(selector.comment.as_deref(), Some("a=b"));
+        assert_eq!(selector.date_from.as_deref(), Some("2023-12"));
+        assert_eq!(selector.date_to, None);
+        assert!("unknown=1".parse::<RunSelector>().is_err());
+        assert!("comment=a,b".parse::<RunSelector>().is_err());
+    }
+
+    #[test]
+    fn test_compare_legacy_runs() {
+        let db = InstanceDatabase::load("src/resources/instances.yml").unwrap();
+        let comparisons = compare_runs(&db, &"sketch degLB + clqLB".parse().unwrap(),
+                                       &"clqLB with welsh powell".parse().unwrap(), CompareOptions::default());
+        assert!(!comparisons.is_empty());
+        assert!(comparisons.iter().all(|c| c.problem == Problem::Clique));
+    }
+}
@@ -0,0 +1,521 @@
+//! Compact representation of a graph (Compressed Sparse Row) and a streaming loader for large DIMACS files.
+//!
+//! The petgraph `UnGraphMap` is convenient for the solvers, but building it edge by edge is slow for
+//! instances with millions of edges. The loader of this module reads the file with a single reusable
+//! buffer, pre-sizes the edge list from the problem line and builds the CSR adjacency directly.
+//! The CSR graph can then be converted into the graph types used by the solvers.
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::time::{Duration, Instant};
+
+use petgraph::prelude::UnGraphMap;
+
+use crate::errors::{CacheError, InvalidClqFileFormat};
+use crate::mvcgraph::MVCGraph;
+
+/// Undirected graph stored in the Compressed Sparse Row format.
+///
+/// The vertices are numbered from 0 to order - 1. The neighbors of the vertex `v` are stored in
+/// `targets[offsets[v]..offsets[v + 1]]`, sorted in increasing order. Each edge is stored twice (once per endpoint).
+///
+/// # Example
+/// ```rust
+/// use vertex::csr::CsrGraph;
+///
+/// let graph = CsrGraph::from_edges(4, &[(0, 1), (1, 2), (2, 0), (1, 0)]);
+/// assert_eq!(graph.order(), 4);
+/// assert_eq!(graph.size(), 3);
+/// assert_eq!(graph.neighbors(1), &[0, 2]);
+/// assert!(graph.has_edge(2, 1));
+/// assert_eq!(graph.degree(3), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CsrGraph {
+    offsets: Vec<usize>,
+    targets: Vec<u32>,
+}
+
+impl CsrGraph {
+    /// Builds a graph from a list of edges. Duplicate edges are merged and self-loops are ignored.
+    ///
+    /// # Panics
+    /// If a vertex of an edge is greater or equal to the order. The order is a precondition like the bounds of an
+    /// index : the callers derive it from the edges (see the `From<&UnGraphMap>` implementation) or check every vertex
+    /// against it while reading them (see [`load_clq_csr`]). An edge out of range is a bug of the caller, not an
+    /// invalid input, and the counting sort cannot go on without a slot for its vertices.
+    pub fn from_edges(order: usize, edges: &[(u32, u32)]) -> CsrGraph {
+        // Counting sort of the edges on their source
+        let mut degrees = vec![0usize; order + 1];
+        for &(i, j) in edges {
+            assert!((i as usize) < order && (j as usize) < order, "Edge ({}, {}) out of range", i, j);
+            if i != j {
+                degrees[i as usize + 1] += 1;
+                degrees[j as usize + 1] += 1;
+            }
+        }
+        for v in 0..order {
+            degrees[v + 1] += degrees[v];
+        }
+        let mut next = degrees.clone();
+        let mut targets = vec![0u32; degrees[order]];
+        for &(i, j) in edges {
+            if i != j {
+                targets[next[i as usize]] = j;
+                next[i as usize] += 1;
+                targets[next[j as usize]] = i;
+                next[j as usize] += 1;
+            }
+        }
+
+        // Sort and deduplicate the neighbors of each vertex in place
+        let mut offsets = Vec::with_capacity(order + 1);
+        offsets.push(0);
+        let mut write = 0;
+        for v in 0..order {
+            let neighbors = &mut targets[degrees[v]..degrees[v + 1]];
+            neighbors.sort_unstable();
+            let mut last = None;
+            for k in degrees[v]..degrees[v + 1] {
+                let t = targets[k];
+                if last != Some(t) {
+                    targets[write] = t;
+                    write += 1;
+                    last = Some(t);
+                }
+            }
+            offsets.push(write);
+        }
+        targets.truncate(write);
+        targets.shrink_to_fit();
+        CsrGraph { offsets, targets }
+    }
+
+    /// Builds a graph from its raw CSR arrays.
+    ///
+    /// The arrays are checked before any lookup, so that corrupted arrays are rejected instead of panicking.
+    ///
+    /// # Throws
+    /// CacheError::InvalidFormat if the arrays are not a valid symmetric CSR adjacency with sorted neighbors.
+    pub fn from_raw_parts(offsets: Vec<usize>, targets: Vec<u32>) -> Result<CsrGraph, CacheError> {
+        let invalid = |message: String| Err(CacheError::InvalidFormat(message));
+        if offsets.first() != Some(&0) || offsets.last() != Some(&targets.len()) {
+            return invalid(format!("The offsets must go from 0 to the number of targets ({})", targets.len()));
+        }
+        if let Some(v) = offsets.windows(2).position(|w| w[0] > w[1]) {
+            return invalid(format!("The offsets of the vertex {} are decreasing", v));
+        }
+        let order = offsets.len() - 1;
+        if let Some(u) = targets.iter().find(|u| **u as usize >= order) {
+            return invalid(format!("The target {} is not a vertex of a graph of order {}", u, order));
+        }
+
+        // The neighbors can now be read safely
+        let graph = CsrGraph { offsets, targets };
+        for v in 0..order as u32 {
+            let neighbors = graph.neighbors(v);
+            if neighbors.windows(2).any(|w| w[0] >= w[1]) {
+                return invalid(format!("The neighbors of the vertex {} are not sorted", v));
+            }
+            if let Some(u) = neighbors.iter().find(|u| **u == v || !graph.has_edge(**u, v)) {
+                return invalid(format!("The edge ({}, {}) is a self-loop or is not stored twice", v, u));
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Returns the offsets array of the CSR representation (of length order + 1).
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Returns the targets array of the CSR representation (of length 2 * size).
+    pub fn targets(&self) -> &[u32] {
+        &self.targets
+    }
+
+    /// Returns the order (number of vertices) of the graph.
+    pub fn order(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// Returns the size (number of edges) of the graph.
+    pub fn size(&self) -> usize {
+        self.targets.len() / 2
+    }
+
+    /// Returns the sorted neighbors of a vertex.
+    pub fn neighbors(&self, v: u32) -> &[u32] {
+        &self.targets[self.offsets[v as usize]..self.offsets[v as usize + 1]]
+    }
+
+    /// Returns the degree of a vertex.
+    pub fn degree(&self, v: u32) -> usize {
+        self.offsets[v as usize + 1] - self.offsets[v as usize]
+    }
+
+    /// Test if the graph contains the edge (i, j).
+    pub fn has_edge(&self, i: u32, j: u32) -> bool {
+        (i as usize) < self.order() && self.neighbors(i).binary_search(&j).is_ok()
+    }
+
+    /// Returns an iterator over the edges (i, j) of the graph with i < j.
+    pub fn edges(&self) -> impl Iterator<Item=(u32, u32)> + '_ {
+        (0..self.order() as u32).flat_map(move |i| {
+            self.neighbors(i).iter().filter(move |&&j| i < j).map(move |&j| (i, j))
+        })
+    }
+
+    /// Converts the graph into the petgraph graph used by the solvers.
+    pub fn to_graph_map(&self) -> UnGraphMap<u64, ()> {
+        let mut g = UnGraphMap::<u64, ()>::with_capacity(self.order(), self.size());
+        for i in 0..self.order() as u64 {
+            g.add_node(i);
+        }
+        for (i, j) in self.edges() {
+            g.add_edge(i as u64, j as u64, ());
+        }
+        g
+    }
+
+    /// Converts the graph into a [`MVCGraph`].
+    pub fn to_mvc_graph(&self) -> MVCGraph {
+        let mut g = MVCGraph::new();
+        for i in 0..self.order() as u64 {
+            g.add_node(i);
+        }
+        for (i, j) in self.edges() {
+            g.add_edge(i as u64, j as u64);
+        }
+        g
+    }
+}
+
+impl From<&UnGraphMap<u64, ()>> for CsrGraph {
+    /// Builds the CSR representation of a petgraph graph.
+    ///
+    /// The vertices are expected to be numbered from 0 : the order of the CSR graph is the greatest vertex + 1.
+    fn from(graph: &UnGraphMap<u64, ()>) -> Self {
+        let order = graph.nodes().max().map_or(0, |v| v as usize + 1);
+        let edges: Vec<(u32, u32)> = graph.all_edges().map(|(i, j, _)| (i as u32, j as u32)).collect();
+        CsrGraph::from_edges(order, &edges)
+    }
+}
+
+/// Statistics about the loading of a graph file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadStats {
+    /// Number of bytes read
+    pub bytes: u64,
+    /// Number of lines read
+    pub lines: usize,
+    /// Number of edge lines read
+    pub edges: usize,
+    /// Time taken to read the file and build the graph
+    pub duration: Duration,
+    /// True if the graph was read from its binary cache (see [`crate::cache`]) instead of the DIMACS file
+    pub from_cache: bool,
+}
+
+impl LoadStats {
+    /// Returns the throughput of the loader in megabytes per second.
+    pub fn megabytes_per_sec(&self) -> f64 {
+        self.bytes as f64 / 1_000_000.0 / self.duration.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Returns the throughput of the loader in edges per second.
+    pub fn edges_per_sec(&self) -> f64 {
+        self.edges as f64 / self.duration.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+impl Display for LoadStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.from_cache {
+            write!(f, "{} bytes and {} edges read from the cache", self.bytes, self.edges)?;
+        } else {
+            write!(f, "{} bytes, {} lines and {} edges read", self.bytes, self.lines, self.edges)?;
+        }
+        write!(f, " in {}ms ({:.2} MB/s, {:.0} edges/s)",
+               self.duration.as_millis(),
+               self.megabytes_per_sec(),
+               self.edges_per_sec())
+    }
+}
+
+/// Load a graph from a DIMACS file into a [`CsrGraph`].
+///
+/// The file is streamed line by line in a single reusable buffer and the numbers are parsed directly
+/// from the bytes. The checks are the same as [`crate::graph_utils::load_clq_file`] (strict mode), except that
+/// duplicate edges are merged without being located : they are only reported through the number of edges.
+///
+/// # Throws
+/// InvalidClqFileFormat if the file is not in the correct format.
+///
+/// # Example
+/// ```rust
+/// use vertex::csr::load_clq_csr;
+///
+/// let (graph, stats) = load_clq_csr("src/resources/graphs/test.clq").unwrap();
+/// assert_eq!(graph.order(), 5);
+/// assert_eq!(graph.size(), 6);
+/// assert_eq!(graph.neighbors(0), &[1, 2, 3, 4]);
+/// assert_eq!(stats.edges, 6);
+/// ```
+pub fn load_clq_csr(path: &str) -> Result<(CsrGraph, LoadStats), InvalidClqFileFormat> {
+    let start = Instant::now();
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => return Err(
+            InvalidClqFileFormat::new(&format!("File {:?} not found \n {:?}", path, e))
+        ),
+    };
+    let mut reader = BufReader::with_capacity(1 << 16, file);
+
+    let mut buffer: Vec<u8> = Vec::with_capacity(128);
+    let mut order: Option<usize> = None;
+    let mut exp_edges = 0;
+    let mut edges: Vec<(u32, u32)> = Vec::new();
+    let mut bytes = 0;
+    let mut line_number = 0;
+
+    loop {
+        buffer.clear();
+        let read = match reader.read_until(b'\n', &mut buffer) {
+            Ok(read) => read,
+            Err(e) => return Err(InvalidClqFileFormat::at(&e.to_string(), line_number + 1, 1)),
+        };
+        if read == 0 {
+            break;
+        }
+        bytes += read as u64;
+        line_number += 1;
+
+        let mut tokens = ByteTokens { line: &buffer, pos: 0 };
+        let (_, symbol) = match tokens.next() {
+            Some(token) => token,
+            None => continue, // Empty line
+        };
+        match symbol {
+            b"c" => continue,
+            b"p" => {
+                if order.is_some() {
+                    return Err(InvalidClqFileFormat::at("Problem line defined twice", line_number, 1));
+                }
+                match tokens.next() {
+                    Some((_, b"edge")) | Some((_, b"col")) => {}
+                    Some((column, _)) => {
+                        return Err(InvalidClqFileFormat::at("Expecting edge/col format", line_number, column));
+                    }
+                    None => {
+                        return Err(InvalidClqFileFormat::at("Expecting edge/col format", line_number, tokens.end()));
+                    }
+                }
+                let n = tokens.parse_number("order", line_number)?;
+                if n > u32::MAX as u64 {
+                    return Err(InvalidClqFileFormat::at("The order of the graph is too large", line_number, 1));
+                }
+                exp_edges = tokens.parse_number("number of edges", line_number)? as usize;
+                if let Some((column, _)) = tokens.next() {
+                    return Err(InvalidClqFileFormat::at("Unexpected token after the number of edges",
+                                                        line_number, column));
+                }
+                // Pre-size the edge list from the problem line (capped in case the header lies)
+                edges.reserve(exp_edges.min(1 << 26));
+                order = Some(n as usize);
+            }
+            b"e" => {
+                let n = match order {
+                    Some(n) if n > 0 => n,
+                    _ => return Err(InvalidClqFileFormat::at("Expecting graph order", line_number, 1)),
+                };
+                let (col_i, i) = tokens.parse_vertex(n, line_number)?;
+                let (col_j, j) = tokens.parse_vertex(n, line_number)?;
+                if let Some((column, _)) = tokens.next() {
+                    return Err(InvalidClqFileFormat::at("Unexpected token after the edge", line_number, column));
+                }
+                if i == j {
+                    return Err(InvalidClqFileFormat::at(&format!("Self-loop on vertex {}", i + 1),
+                                                        line_number, col_j.max(col_i)));
+                }
+                edges.push((i, j));
+            }
+            _ => {
+                let line = String::from_utf8_lossy(&buffer);
+                return Err(InvalidClqFileFormat::at(&format!("Invalid file format for line {:?}", line.trim_end()),
+                                                    line_number, 1));
+            }
+        }
+    }
+
+    let order = match order {
+        Some(n) if n > 0 => n,
+        _ => return Err(InvalidClqFileFormat::new("Expecting graph order")),
+    };
+    let graph = CsrGraph::from_edges(order, &edges);
+    if graph.size() != exp_edges {
+        return Err(InvalidClqFileFormat::new(&format!("Expecting {} edges but read {} edges", exp_edges, graph.size())));
+    }
+
+    let stats = LoadStats {
+        bytes,
+        lines: line_number,
+        edges: edges.len(),
+        duration: start.elapsed(),
+        from_cache: false,
+    };
+    Ok((graph, stats))
+}
+
+/// Iterator over the whitespace separated tokens of a line, along with their column (starting at 1).
+struct ByteTokens<'a> {
+    line: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteTokens<'a> {
+    /// Column just after the end of the line, used when a token is missing.
+    fn end(&self) -> usize {
+        self.line.iter().rposition(|c| !c.is_ascii_whitespace()).map_or(1, |p| p + 2)
+    }
+
+    fn parse_number(&mut self, name: &str, line: usize) -> Result<u64, InvalidClqFileFormat> {
+        let end = self.end();
+        match self.next() {
+            Some((column, token)) => parse_u64(token).ok_or_else(|| {
+                InvalidClqFileFormat::at(&format!("Invalid {} {:?}", name, String::from_utf8_lossy(token)),
+                                         line, column)
+            }),
+            None => Err(InvalidClqFileFormat::at(&format!("Missing {}", name), line, end)),
+        }
+    }
+
+    /// Parses a vertex and converts it to the 0-based numbering.
+    fn parse_vertex(&mut self, order: usize, line: usize) -> Result<(usize, u32), InvalidClqFileFormat> {
+        let column = self.pos + 1 + self.line[self.pos..].iter().take_while(|c| c.is_ascii_whitespace()).count();
+        let vertex = self.parse_number("vertex", line)?;
+        if vertex == 0 || vertex > order as u64 {
+            return Err(InvalidClqFileFormat::at(&format!("Vertex {} out of range 1..={}", vertex, order),
+                                                line, column));
+        }
+        Ok((column, (vertex - 1) as u32))
+    }
+}
+
+impl<'a> Iterator for ByteTokens<'a> {
+    type Item = (usize, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.line.len() && self.line[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        if self.pos == self.line.len() {
+            return None;
+        }
+        let start = self.pos;
+        while self.pos < self.line.len() && !self.line[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        Some((start + 1, &self.line[start..self.pos]))
+    }
+}
+
+fn parse_u64(token: &[u8]) -> Option<u64> {
+    if token.is_empty() {
+        return None;
+    }
+    let mut value: u64 = 0;
+    for &c in token {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        value = value.checked_mul(10)?.checked_add((c - b'0') as u64)?;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod csr_tests {
+    use crate::graph_utils::load_clq_file;
+
+    use super::*;
+
+    #[test]
+    fn test_from_edges() {
+        let graph = CsrGraph::from_edges(5, &[(0, 1), (4, 0), (1, 0), (2, 2), (3, 1)]);
+        assert_eq!(graph.order(), 5);
+        assert_eq!(graph.size(), 3);
+        assert_eq!(graph.neighbors(0), &[1, 4]);
+        assert_eq!(graph.neighbors(1), &[0, 3]);
+        assert_eq!(graph.neighbors(2), &[] as &[u32]);
+        assert_eq!(graph.edges().collect::<Vec<_>>(), vec![(0, 1), (0, 4), (1, 3)]);
+    }
+
+    #[test]
+    fn test_from_raw_parts() {
+        let graph = CsrGraph::from_edges(3, &[(0, 1), (1, 2)]);
+        let copy = CsrGraph::from_raw_parts(graph.offsets().to_vec(), graph.targets().to_vec());
+        assert_eq!(copy.unwrap(), graph);
+
+        let invalid = |offsets: Vec<usize>, targets: Vec<u32>| {
+            matches!(CsrGraph::from_raw_parts(offsets, targets), Err(CacheError::InvalidFormat(_)))
+        };
+        // Edge (0, 1) without (1, 0)
+        assert!(invalid(vec![0, 1, 1], vec![1]));
+        // Offsets not matching the targets
+        assert!(invalid(vec![0, 1, 3], vec![1, 0]));
+        // Decreasing offsets, whose neighbors cannot be sliced
+        assert!(invalid(vec![0, 2, 1, 2], vec![1, 0]));
+        // Target out of the graph, looked up before the symmetry is checked
+        assert!(invalid(vec![0, 1, 2], vec![7, 0]));
+    }
+
+    #[test]
+    fn test_load_clq_csr_same_as_load_clq_file() {
+        for name in ["test.clq", "myciel4.clq", "queen5_5.clq", "C125.9.clq"] {
+            let path = format!("src/resources/graphs/{}", name);
+            let expected = load_clq_file(&path).unwrap();
+            let (graph, stats) = load_clq_csr(&path).unwrap();
+
+            assert_eq!(graph.order(), expected.node_count());
+            assert_eq!(graph.size(), expected.edge_count());
+            for (i, j, _) in expected.all_edges() {
+                assert!(graph.has_edge(i as u32, j as u32));
+                assert!(graph.has_edge(j as u32, i as u32));
+            }
+            assert!(stats.bytes > 0);
+
+            let converted = graph.to_graph_map();
+            assert_eq!(converted.node_count(), expected.node_count());
+            assert_eq!(converted.edge_count(), expected.edge_count());
+            assert_eq!(CsrGraph::from(&converted), graph);
+
+            let mvc_graph = graph.to_mvc_graph();
+            assert_eq!(mvc_graph.order() as usize, graph.order());
+            assert_eq!(mvc_graph.size() as usize, graph.size());
+        }
+    }
+
+    #[test]
+    fn test_load_clq_csr_errors() {
+        let cases = [
+            ("format_edge.clq", "Expecting edge/col format"),
+            ("format_order.clq", "Expecting graph order"),
+            ("format_size.clq", "Expecting 1 edges but read 2 edges"),
+            ("format_symbol.clq", "Invalid file format for line \"a 1 2\""),
+            ("format_vertex_zero.clq", "Vertex 0 out of range 1..=2"),
+            ("format_vertex_range.clq", "Vertex 3 out of range 1..=2"),
+            ("format_self_loop.clq", "Self-loop on vertex 2"),
+            ("format_duplicate.clq", "Expecting 3 edges but read 2 edges"),
+        ];
+        for (name, message) in cases {
+            let err = load_clq_csr(&format!("src/resources/tests/graphs/{}", name)).unwrap_err();
+            assert_eq!(err.message, message, "for {}", name);
+        }
+
+        let err = load_clq_csr("src/resources/tests/graphs/format_vertex_range.clq").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(4), Some(5)));
+        assert!(load_clq_csr("unknown_file.clq").is_err());
+        assert!(load_clq_csr("src/resources/tests/graphs/format_empty_line.clq").is_ok());
+    }
+}
@@ -0,0 +1,839 @@
+//! Database of the instances used in the project.
+//!
+//! Each instance has a single record holding its characteristics (with its [`GraphStats`]), the known optimal
+//! values for the minimum vertex cover, maximum clique and maximum independent set problems (with their provenance)
+//! and the history of the runs made on it. The database is stored in a YAML file
+//! (by default src/resources/instances.yml, see [`crate::workspace::Workspace`]).
+//!
+//! The database is the only source of truth : it replaces the three legacy files graph_data.yml, clique_data.yml and
+//! time_result.yml, which are no longer written and are only read by [`InstanceDatabase::migrate`].
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use petgraph::prelude::UnGraphMap;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use crate::ElapseTime;
+use crate::cache::load_clq_cached;
+use crate::errors::YamlError;
+use crate::fingerprint::GraphFingerprint;
+use crate::graph_utils::GraphStats;
+use crate::persist::{lock_yaml, write_yaml};
+
+/// Problem solved on an instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Problem {
+    /// Minimum vertex cover
+    Mvc,
+    /// Maximum clique
+    Clique,
+    /// Maximum independent set
+    Mis,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Problem::Mvc => "mvc",
+            Problem::Clique => "clique",
+            Problem::Mis => "mis",
+        };
+        f.pad(name)
+    }
+}
+
+impl FromStr for Problem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mvc" => Ok(Problem::Mvc),
+            "clique" => Ok(Problem::Clique),
+            "mis" => Ok(Problem::Mis),
+            _ => Err(format!("Unknown problem {:?} (expecting mvc, clique or mis)", s)),
+        }
+    }
+}
+
+impl Problem {
+    /// Returns the name of the problem. Example : "Minimum vertex cover"
+    pub fn description(&self) -> &'static str {
+        match self {
+            Problem::Mvc => "Minimum vertex cover",
+            Problem::Clique => "Maximum clique",
+            Problem::Mis => "Maximum independent set",
+        }
+    }
+}
+
+/// Known optimal value of an instance along with where it comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownValue {
+    pub value: u64,
+    /// Source of the value (ex: "DIMACS benchmark", "computed by bnb", "derived from mvc")
+    pub provenance: String,
+}
+
+impl KnownValue {
+    pub fn new(value: u64, provenance: &str) -> KnownValue {
+        KnownValue {
+            value,
+            provenance: provenance.to_string(),
+        }
+    }
+}
+
+/// How a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// The algorithm returned before the time limit
+    #[default]
+    Solved,
+    /// The algorithm stopped itself because it reached the time limit
+    TimeLimit,
+    /// The algorithm stopped itself because it exhausted its node budget
+    NodeLimit,
+    /// The algorithm was cancelled before the end of its search
+    Cancelled,
+    /// The algorithm did not stop after the time limit and was killed
+    Killed,
+    /// The algorithm panicked or its process failed
+    Crashed,
+}
+
+impl RunStatus {
+    fn is_solved(&self) -> bool {
+        *self == RunStatus::Solved
+    }
+
+    /// Returns the name of the status in the database. Example : "time_limit"
+    pub fn name(&self) -> &'static str {
+        match self {
+            RunStatus::Solved => "solved",
+            RunStatus::TimeLimit => "time_limit",
+            RunStatus::NodeLimit => "node_limit",
+            RunStatus::Cancelled => "cancelled",
+            RunStatus::Killed => "killed",
+            RunStatus::Crashed => "crashed",
+        }
+    }
+}
+
+impl fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            RunStatus::Solved => "solved",
+            RunStatus::TimeLimit => "time limit",
+            RunStatus::NodeLimit => "node limit",
+            RunStatus::Cancelled => "cancelled",
+            RunStatus::Killed => "killed",
+            RunStatus::Crashed => "crashed",
+        };
+        f.pad(name)
+    }
+}
+
+/// Result of a run of an algorithm on an instance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Date of the run (format "%Y-%m-%d %H:%M:%S")
+    pub date: String,
+    pub problem: Problem,
+    pub algorithm: String,
+    /// Value found by the algorithm (none if it was stopped before finding a solution)
+    pub value: Option<u64>,
+    /// Time taken by the algorithm
+    pub time: Duration,
+    /// Whether the algorithm was stopped because it reached the time limit
+    pub is_time_limit: bool,
+    pub comment: String,
+    /// How the run ended (the value is meaningless if the run was killed or crashed)
+    #[serde(default, skip_serializing_if = "RunStatus::is_solved")]
+    pub status: RunStatus,
+    /// Statistics of the run (ex: time spent in each subroutine, in seconds)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stats: BTreeMap<String, f64>,
+}
+
+impl RunRecord {
+    /// Creates a run dated from now, without statistics, that found a solution of the given value.
+    pub fn new(problem: Problem, algorithm: &str, value: u64, time: Duration, is_time_limit: bool, comment: &str) -> RunRecord {
+        RunRecord {
+            date: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            problem,
+            algorithm: algorithm.to_string(),
+            value: Some(value),
+            time,
+            is_time_limit,
+            comment: comment.to_string(),
+            status: if is_time_limit { RunStatus::TimeLimit } else { RunStatus::Solved },
+            stats: BTreeMap::new(),
+        }
+    }
+}
+
+/// Record of an instance in the database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceRecord {
+    /// The id of the instance. Example : "test.clq"
+    pub id: String,
+    /// Format of the file (clq or col)
+    pub format: String,
+    pub order: usize,
+    pub size: usize,
+    /// Fingerprint of the graph used to check that a file still describes the same instance
+    #[serde(default)]
+    pub hash: Option<GraphFingerprint>,
+    /// Structural statistics of the graph (see [`GraphStats`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<GraphStats>,
+    #[serde(default)]
+    pub mvc: Option<KnownValue>,
+    #[serde(default)]
+    pub clique: Option<KnownValue>,
+    #[serde(default)]
+    pub mis: Option<KnownValue>,
+    #[serde(default)]
+    pub runs: Vec<RunRecord>,
+}
+
+impl InstanceRecord {
+    /// Creates a record without known values nor runs.
+    pub fn new(id: &str, format: &str, order: usize, size: usize) -> InstanceRecord {
+        InstanceRecord {
+            id: id.to_string(),
+            format: format.to_string(),
+            order,
+            size,
+            hash: None,
+            stats: None,
+            mvc: None,
+            clique: None,
+            mis: None,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Creates a record of a graph with its fingerprint and statistics, without known values nor runs.
+    ///
+    /// Computing the fingerprint and the statistics of a large graph takes time : the record has to be created
+    /// before locking the database (see [`InstanceDatabase::update`]), then stored with [`InstanceDatabase::add_record`].
+    pub fn from_graph(id: &str, format: &str, graph: &UnGraphMap<u64, ()>) -> InstanceRecord {
+        InstanceRecord {
+            hash: Some(GraphFingerprint::with_wl(graph)),
+            stats: Some(GraphStats::new(graph)),
+            ..InstanceRecord::new(id, format, graph.node_count(), graph.edge_count())
+        }
+    }
+
+    /// Returns the known optimal value for a problem.
+    pub fn known(&self, problem: Problem) -> Option<&KnownValue> {
+        match problem {
+            Problem::Mvc => self.mvc.as_ref(),
+            Problem::Clique => self.clique.as_ref(),
+            Problem::Mis => self.mis.as_ref(),
+        }
+    }
+
+    /// Sets the known optimal value for a problem.
+    ///
+    /// Since the complement of a minimum vertex cover is a maximum independent set, setting one of
+    /// these two values also sets the other one if it is unknown.
+    pub fn set_known(&mut self, problem: Problem, value: KnownValue) {
+        let order = self.order as u64;
+        match problem {
+            Problem::Mvc => {
+                if self.mis.is_none() && value.value <= order {
+                    self.mis = Some(KnownValue::new(order - value.value, "derived from mvc"));
+                }
+                self.mvc = Some(value);
+            }
+            Problem::Mis => {
+                if self.mvc.is_none() && value.value <= order {
+                    self.mvc = Some(KnownValue::new(order - value.value, "derived from mis"));
+                }
+                self.mis = Some(value);
+            }
+            Problem::Clique => self.clique = Some(value),
+        }
+    }
+
+    /// Checks that a graph is the one described by the record.
+    ///
+    /// A record without fingerprint (for example a record migrated from the legacy files) accepts any graph.
+    ///
+    /// # Throws
+    /// YamlError::FingerprintMismatch if the fingerprint of the graph does not match the stored one.
+    pub fn check_fingerprint(&self, fingerprint: &GraphFingerprint) -> Result<(), YamlError> {
+        match &self.hash {
+            Some(hash) if !hash.matches(fingerprint) => Err(YamlError::FingerprintMismatch(
+                format!("Graph {:?} does not match the instance stored in the database", self.id),
+                format!("Expecting fingerprint {} but the graph has fingerprint {}", hash, fingerprint))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the density of the graph.
+    pub fn density(&self) -> f64 {
+        if self.order < 2 {
+            return 0.0;
+        }
+        (2 * self.size) as f64 / (self.order * (self.order - 1)) as f64
+    }
+}
+
+/// Database of instances. See the module documentation.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use petgraph::prelude::UnGraphMap;
+/// use vertex::database::{InstanceDatabase, KnownValue, Problem, RunRecord};
+///
+/// let mut graph = UnGraphMap::<u64, ()>::new();
+/// graph.add_edge(0, 1, ());
+/// graph.add_edge(1, 2, ());
+///
+/// let mut db = InstanceDatabase::new();
+/// db.add_graph("path.clq", "clq", &graph).set_known(Problem::Mvc, KnownValue::new(1, "by hand"));
+/// db.add_run("path.clq", RunRecord::new(Problem::Mvc, "bnb", 1, Duration::from_millis(3), false, "")).unwrap();
+///
+/// assert_eq!(db.known_value("path.clq", Problem::Mis), Some(2));
+/// assert_eq!(db.is_optimal_value("path.clq", Problem::Mvc, 1, &graph).unwrap(), Some(true));
+/// assert_eq!(db.is_optimal_value("unknown.clq", Problem::Mvc, 1, &graph).unwrap(), None);
+///
+/// // Another graph stored with the same id is detected
+/// graph.add_edge(0, 2, ());
+/// assert!(db.is_optimal_value("path.clq", Problem::Mvc, 1, &graph).is_err());
+/// assert_eq!(db.get("path.clq").unwrap().runs.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InstanceDatabase {
+    instances: Vec<InstanceRecord>,
+}
+
+impl InstanceDatabase {
+    /// Creates an empty database
+    pub fn new() -> InstanceDatabase {
+        Default::default()
+    }
+
+    /// Loads the database from a YAML file. A missing file is an empty database, as in a new workspace.
+    ///
+    /// If a previous write was interrupted, the file is recovered first (see [`crate::persist::recover`]).
+    ///
+    /// # Throws
+    /// - YamlError::IoError if the file cannot be opened or is locked for too long
+    /// - YamlError::YAMLParsingError if there is an error while parsing the file
+    pub fn load(path: &str) -> Result<InstanceDatabase, YamlError> {
+        let _lock = lock_yaml(Path::new(path))?;
+        InstanceDatabase::read(path)
+    }
+
+    /// Saves the database in a YAML file.
+    ///
+    /// The file is replaced atomically, so a crash during the write leaves the previous content intact.
+    /// Use [`InstanceDatabase::update`] to add results to a database shared with other processes.
+    ///
+    /// # Throws
+    /// - YamlError::IoError if the file cannot be written or is locked for too long
+    /// - YamlError::YAMLParsingError if the database cannot be serialized
+    pub fn save(&self, path: &str) -> Result<(), YamlError> {
+        let _lock = lock_yaml(Path::new(path))?;
+        write_yaml(Path::new(path), &serde_yaml::to_string(self)?)
+    }
+
+    /// Updates the database stored in a YAML file.
+    ///
+    /// The file is locked while it is loaded, modified by `f` and saved, so that several processes
+    /// (for example parallel benchmark runs) can add results to the same database without losing any.
+    /// If `f` fails, the file is not modified. A missing file is created by the first update.
+    ///
+    /// # Throws
+    /// - YamlError::IoError if the file cannot be read or written, or is locked for too long
+    /// - YamlError::YAMLParsingError if there is an error while parsing the file
+    /// - the error returned by `f`
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use vertex::database::{InstanceDatabase, InstanceRecord, Problem, RunRecord};
+    ///
+    /// let path = std::env::temp_dir().join("mvc_db_update_example.yml");
+    /// let path = path.to_str().unwrap();
+    /// let mut db = InstanceDatabase::new();
+    /// db.insert(InstanceRecord::new("a.clq", "clq", 4, 3));
+    /// db.save(path).unwrap();
+    ///
+    /// InstanceDatabase::update(path, |db| {
+    ///     db.add_run("a.clq", RunRecord::new(Problem::Mvc, "bnb", 2, Duration::from_millis(3), false, ""))
+    /// }).unwrap();
+    /// assert_eq!(InstanceDatabase::load(path).unwrap().get("a.clq").unwrap().runs.len(), 1);
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn update<T, F>(path: &str, f: F) -> Result<T, YamlError>
+        where F: FnOnce(&mut InstanceDatabase) -> Result<T, YamlError> {
+        let _lock = lock_yaml(Path::new(path))?;
+        let mut db = InstanceDatabase::read(path)?;
+        let result = f(&mut db)?;
+        write_yaml(Path::new(path), &serde_yaml::to_string(&db)?)?;
+        Ok(result)
+    }
+
+    /// Reads the database from a YAML file, without locking it. A missing file is an empty database.
+    fn read(path: &str) -> Result<InstanceDatabase, YamlError> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(InstanceDatabase::new()),
+            Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
+        };
+        Ok(serde_yaml::from_reader(file)?)
+    }
+
+    /// Returns all the records of the database.
+    pub fn instances(&self) -> &[InstanceRecord] {
+        &self.instances
+    }
+
+    /// Returns the record of an instance.
+    pub fn get(&self, id: &str) -> Option<&InstanceRecord> {
+        self.instances.iter().find(|r| r.id == id)
+    }
+
+    /// Returns a mutable reference to the record of an instance.
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut InstanceRecord> {
+        self.instances.iter_mut().find(|r| r.id == id)
+    }
+
+    /// Returns the records matching a predicate.
+    pub fn filter<F: Fn(&InstanceRecord) -> bool>(&self, predicate: F) -> Vec<&InstanceRecord> {
+        self.instances.iter().filter(|r| predicate(r)).collect()
+    }
+
+    /// Inserts a record. If a record with the same id exists, it is replaced.
+    pub fn insert(&mut self, record: InstanceRecord) {
+        match self.get_mut(&record.id) {
+            Some(existing) => *existing = record,
+            None => self.instances.push(record),
+        }
+    }
+
+    /// Adds a graph to the database if it is not in it yet and returns its record.
+    ///
+    /// The fingerprint and the statistics of the graph are stored in the record if it has none. They are computed
+    /// even if the record has them : when the database is locked, use [`InstanceDatabase::add_record`] instead.
+    pub fn add_graph(&mut self, id: &str, format: &str, graph: &UnGraphMap<u64, ()>) -> &mut InstanceRecord {
+        self.add_record(InstanceRecord::from_graph(id, format, graph));
+        self.get_mut(id).unwrap()
+    }
+
+    /// Adds a record to the database. Returns the number of runs added.
+    ///
+    /// If the instance is already in the database, the known values, the fingerprint and the statistics missing
+    /// from the stored record are taken from the given one, and the runs it does not contain yet are appended.
+    /// The order and the size of a record without fingerprint are replaced along with the fingerprint.
+    pub fn add_record(&mut self, record: InstanceRecord) -> usize {
+        let existing = match self.get_mut(&record.id) {
+            Some(existing) => existing,
+            None => {
+                let added = record.runs.len();
+                self.instances.push(record);
+                return added;
+            }
+        };
+        for problem in [Problem::Mvc, Problem::Clique, Problem::Mis] {
+            if let (None, Some(known)) = (existing.known(problem), record.known(problem)) {
+                existing.set_known(problem, known.clone());
+            }
+        }
+        if existing.hash.is_none() && record.hash.is_some() {
+            existing.order = record.order;
+            existing.size = record.size;
+            existing.hash = record.hash;
+        }
+        if existing.stats.is_none() {
+            existing.stats = record.stats;
+        }
+        let mut added = 0;
+        for run in record.runs {
+            if !existing.runs.contains(&run) {
+                existing.runs.push(run);
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Adds a run to the history of an instance.
+    ///
+    /// # Throws
+    /// YamlError::NotFound if the instance is not in the database (see [`InstanceDatabase::add_graph`]).
+    pub fn add_run(&mut self, id: &str, run: RunRecord) -> Result<(), YamlError> {
+        match self.get_mut(id) {
+            Some(record) => {
+                record.runs.push(run);
+                Ok(())
+            }
+            None => Err(YamlError::NotFound(
+                format!("Graph {:?} not found in the database", id),
+                format!("Graph {:?} has to be added to the database before storing a run", id))),
+        }
+    }
+
+    /// Returns the known optimal value of an instance for a problem.
+    pub fn known_value(&self, id: &str, problem: Problem) -> Option<u64> {
+        self.get(id).and_then(|r| r.known(problem)).map(|k| k.value)
+    }
+
+    /// Returns the known optimal value of an instance for a problem, after checking that the graph is the stored instance.
+    ///
+    /// # Throws
+    /// YamlError::FingerprintMismatch if the graph does not match the fingerprint of the instance.
+    pub fn get_optimal_value(&self, id: &str, problem: Problem, graph: &UnGraphMap<u64, ()>) -> Result<Option<u64>, YamlError> {
+        match self.get(id) {
+            Some(record) => {
+                record.check_fingerprint(&GraphFingerprint::new(graph))?;
+                Ok(record.known(problem).map(|k| k.value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Checks if a value is the known optimal value of an instance for a problem.
+    /// Returns None if the instance or its optimal value is unknown.
+    ///
+    /// # Throws
+    /// YamlError::FingerprintMismatch if the graph does not match the fingerprint of the instance.
+    pub fn is_optimal_value(&self, id: &str, problem: Problem, value: u64, graph: &UnGraphMap<u64, ()>)
+                            -> Result<Option<bool>, YamlError> {
+        Ok(self.get_optimal_value(id, problem, graph)?.map(|v| v == value))
+    }
+
+    /// Stores the fingerprint and the statistics of the instances which do not have them, using the graph files of
+    /// a folder. The order and the size of the instances without fingerprint are also taken from the graph files.
+    /// Returns the ids of the instances whose file cannot be loaded.
+    pub fn fill_fingerprints(&mut self, instance_dir: &Path) -> Vec<String> {
+        let mut missing = Vec::new();
+        for record in self.instances.iter_mut().filter(|r| r.hash.is_none() || r.stats.is_none()) {
+            match load_clq_cached(&instance_dir.join(&record.id).to_string_lossy()) {
+                Ok(graph) => {
+                    let graph_map = graph.to_graph_map();
+                    if record.hash.is_none() {
+                        record.order = graph.order();
+                        record.size = graph.size();
+                        record.hash = Some(GraphFingerprint::with_wl(&graph_map));
+                    }
+                    record.stats = Some(GraphStats::new(&graph_map));
+                }
+                Err(_) => missing.push(record.id.clone()),
+            }
+        }
+        missing
+    }
+
+    /// Builds a database from the three legacy files.
+    ///
+    /// * graph_data : the known values of the minimum vertex cover (a value of 0 means unknown)
+    /// * clique_data : the known values of the maximum clique (a value of 0 means unknown)
+    /// * time_result : the runs, stored as maximum clique values since they were all computed on the complement
+    ///
+    /// # Throws
+    /// - YamlError::IoError if a file cannot be opened or is not found
+    /// - YamlError::YAMLParsingError if there is an error while parsing a file
+    /// - YamlError::YAMLFormatError if a file is not in the correct format
+    ///
+    /// # Example
+    /// ```rust
+    /// use vertex::database::{InstanceDatabase, Problem};
+    ///
+    /// let db = InstanceDatabase::migrate("src/resources/graph_data.yml",
+    ///                                    "src/resources/clique_data.yml",
+    ///                                    "src/resources/time_result.yml").unwrap();
+    /// assert_eq!(db.known_value("test.clq", Problem::Mvc), Some(3));
+    /// assert_eq!(db.known_value("test.clq", Problem::Clique), Some(2));
+    /// assert_eq!(db.known_value("test.clq", Problem::Mis), Some(2));
+    /// ```
+    pub fn migrate(graph_data: &str, clique_data: &str, time_result: &str) -> Result<InstanceDatabase, YamlError> {
+        let mut db = InstanceDatabase::new();
+
+        for info in read_legacy_info(graph_data)? {
+            let record = db.add_legacy_info(&info);
+            if info.val != 0 {
+                record.set_known(Problem::Mvc, KnownValue::new(info.val, "graph_data.yml"));
+            }
+        }
+        for info in read_legacy_info(clique_data)? {
+            let record = db.add_legacy_info(&info);
+            if info.val != 0 {
+                record.set_known(Problem::Clique, KnownValue::new(info.val, "clique_data.yml"));
+            }
+        }
+
+        let file = match File::open(time_result) {
+            Ok(file) => file,
+            Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", time_result), e))
+        };
+        let content: Value = serde_yaml::from_reader(file)?;
+        let map = match content.as_mapping() {
+            Some(x) => x.clone(),
+            None => return Err(YamlError::YAMLFormatError("File badly formatted".to_string(),
+                                                          serde::de::Error::custom("The content of the file should be a map"))),
+        };
+        for (id, times) in map {
+            let id = match id.as_str() {
+                Some(id) => id.to_string(),
+                None => continue,
+            };
+            let times: Vec<YamlTime> = serde_yaml::from_value(times)
+                .map_err(|e| YamlError::YAMLFormatError(format!("Times of {:?} badly formatted", id), e))?;
+            for time in times {
+                let duration = time.time.parse::<ElapseTime>()
+                    .map_err(|e| YamlError::YAMLFormatError(format!("Invalid time for {:?}", id),
+                                                            serde::de::Error::custom(e)))?
+                    .duration;
+                db.add_run(&id, RunRecord {
+                    date: time.date,
+                    problem: Problem::Clique,
+                    algorithm: time.algorithm,
+                    value: Some(time.mvc_val),
+                    time: duration,
+                    is_time_limit: time.is_time_limit,
+                    comment: time.comment,
+                    status: if time.is_time_limit { RunStatus::TimeLimit } else { RunStatus::Solved },
+                    stats: BTreeMap::new(),
+                })?;
+            }
+        }
+        Ok(db)
+    }
+
+    /// Merges another database into this one and returns the number of runs added.
+    ///
+    /// The records of this database are kept : an instance of `other` only adds the known values, the fingerprint
+    /// and the statistics that its record is missing, and the runs that it does not have yet. Merging the same
+    /// database twice adds nothing the second time.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use vertex::database::{InstanceDatabase, InstanceRecord, KnownValue, Problem, RunRecord};
+    ///
+    /// let mut db = InstanceDatabase::new();
+    /// let mut record = InstanceRecord::new("a.clq", "clq", 4, 3);
+    /// record.set_known(Problem::Mvc, KnownValue::new(2, "paper"));
+    /// db.insert(record.clone());
+    /// db.add_run("a.clq", RunRecord::new(Problem::Mvc, "bnb", 2, Duration::from_millis(3), false, "")).unwrap();
+    ///
+    /// record.set_known(Problem::Mvc, KnownValue::new(3, "legacy"));
+    /// record.runs.push(RunRecord::new(Problem::Clique, "clique", 2, Duration::from_millis(5), false, ""));
+    /// let mut other = InstanceDatabase::new();
+    /// other.insert(record);
+    ///
+    /// assert_eq!(db.merge(other.clone()), 1);
+    /// assert_eq!(db.merge(other), 0);
+    /// assert_eq!(db.known_value("a.clq", Problem::Mvc), Some(2));
+    /// assert_eq!(db.get("a.clq").unwrap().runs.len(), 2);
+    /// ```
+    pub fn merge(&mut self, other: InstanceDatabase) -> usize {
+        other.instances.into_iter().map(|record| self.add_record(record)).sum()
+    }
+
+    fn add_legacy_info(&mut self, info: &GraphInfo) -> &mut InstanceRecord {
+        if self.get(&info.id).is_none() {
+            self.instances.push(InstanceRecord::new(&info.id, &info.format, info.order, info.size));
+        }
+        self.get_mut(&info.id).unwrap()
+    }
+}
+
+/// Graph of the legacy graph_data.yml and clique_data.yml files, with its optimal value (0 if unknown).
+#[derive(Debug, PartialEq, Deserialize)]
+struct GraphInfo {
+    id: String,
+    format: String,
+    order: usize,
+    size: usize,
+    val: u64,
+}
+
+/// Run of the legacy time_result.yml file.
+#[derive(Debug, PartialEq, Deserialize)]
+struct YamlTime {
+    date: String,
+    mvc_val: u64,
+    time: String,
+    is_time_limit: bool,
+    algorithm: String,
+    comment: String,
+}
+
+fn read_legacy_info(path: &str) -> Result<Vec<GraphInfo>, YamlError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
+    };
+    Ok(serde_yaml::from_reader(file)?)
+}
+
+#[cfg(test)]
+mod database_tests {
+    use crate::workspace::Workspace;
+
+    use super::*;
+
+    #[test]
+    fn test_migrate_legacy_files() {
+        let db = InstanceDatabase::migrate("src/resources/graph_data.yml",
+                                           "src/resources/clique_data.yml",
+                                           "src/resources/time_result.yml").unwrap();
+        assert_eq!(db.instances().len(), 54);
+
+        let record = db.get("myciel3.clq").unwrap();
+        assert_eq!((record.order, record.size), (11, 20));
+        assert_eq!(record.mvc, Some(KnownValue::new(6, "graph_data.yml")));
+        assert_eq!(record.mis, Some(KnownValue::new(5, "derived from mvc")));
+        assert_eq!(record.clique, Some(KnownValue::new(2, "clique_data.yml")));
+        assert_eq!(record.runs.len(), 4);
+        assert_eq!(record.runs[0].algorithm, "clique");
+        assert_eq!(record.runs[0].time, Duration::from_micros(166));
+
+        // A value of 0 in the legacy files means unknown
+        assert!(db.filter(|r| r.mvc.as_ref().is_some_and(|k| k.value == 0)).is_empty());
+    }
+
+    #[test]
+    fn test_merge_migrated_database() {
+        // Migrating into the bundled database keeps its records and adds nothing it already has
+        let migrated = InstanceDatabase::migrate("src/resources/graph_data.yml",
+                                                 "src/resources/clique_data.yml",
+                                                 "src/resources/time_result.yml").unwrap();
+        let mut db = InstanceDatabase::load(&Workspace::default().database_path()).unwrap();
+        let before = db.clone();
+        db.merge(migrated.clone());
+        assert_eq!(db.instances().len(), before.instances().len());
+        for record in before.instances() {
+            let merged = db.get(&record.id).unwrap();
+            assert_eq!((&merged.mvc, &merged.clique, &merged.hash), (&record.mvc, &record.clique, &record.hash));
+            assert!(merged.runs.starts_with(&record.runs));
+        }
+        assert_eq!(db.clone().merge(migrated), 0);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut db = InstanceDatabase::new();
+        let mut record = InstanceRecord::new("a.clq", "clq", 4, 3);
+        record.set_known(Problem::Mis, KnownValue::new(3, "paper"));
+        record.runs.push(RunRecord::new(Problem::Mvc, "bnb", 1, Duration::from_millis(12), false, "test"));
+        db.insert(record);
+
+        let path = std::env::temp_dir().join(format!("mvc_db_test_{}.yml", std::process::id()));
+        let path = path.to_str().unwrap();
+        db.save(path).unwrap();
+        let loaded = InstanceDatabase::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded, db);
+        assert_eq!(loaded.known_value("a.clq", Problem::Mvc), Some(1));
+        assert_eq!(loaded.get("a.clq").unwrap().mvc.as_ref().unwrap().provenance, "derived from mis");
+    }
+
+    #[test]
+    fn test_concurrent_updates() {
+        let path = std::env::temp_dir().join(format!("mvc_db_concurrent_{}.yml", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut db = InstanceDatabase::new();
+        db.insert(InstanceRecord::new("a.clq", "clq", 4, 3));
+        db.save(&path).unwrap();
+
+        let handles: Vec<_> = (0..4).map(|i| {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                for j in 0..5 {
+                    InstanceDatabase::update(&path, |db| {
+                        db.add_run("a.clq", RunRecord::new(Problem::Mvc, "bnb", 2, Duration::from_millis(1), false,
+                                                           &format!("{}-{}", i, j)))
+                    }).unwrap();
+                }
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let db = InstanceDatabase::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(db.get("a.clq").unwrap().runs.len(), 20);
+    }
+
+    #[test]
+    fn test_load_recovers_interrupted_write() {
+        let dir = std::env::temp_dir().join(format!("mvc_db_recover_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("instances.yml");
+        let mut db = InstanceDatabase::new();
+        db.insert(InstanceRecord::new("a.clq", "clq", 4, 3));
+
+        // A crash after writing the temporary file but before the rename, with a corrupted database
+        std::fs::write(&path, "- id: a.clq\n  format: [").unwrap();
+        std::fs::write(dir.join("instances.yml.tmp-42-0"), serde_yaml::to_string(&db).unwrap()).unwrap();
+
+        assert_eq!(InstanceDatabase::load(path.to_str().unwrap()).unwrap(), db);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fingerprints_of_bundled_instances() {
+        use itertools::Itertools;
+
+        let ws = Workspace::default();
+        let db = InstanceDatabase::load(&ws.database_path()).unwrap();
+        for id in ["test.clq", "myciel3.clq", "queen5_5.clq"] {
+            let graph = load_clq_cached(&ws.instance_path(id).to_string_lossy()).unwrap().to_graph_map();
+            assert!(db.get(id).unwrap().hash.is_some());
+            assert_eq!(db.get(id).unwrap().stats, Some(GraphStats::new(&graph)));
+            assert!(db.get_optimal_value(id, Problem::Mvc, &graph).unwrap().is_some());
+        }
+
+        // A modified file with the same id is detected
+        let mut graph = load_clq_cached(&ws.instance_path("test.clq").to_string_lossy()).unwrap().to_graph_map();
+        let (u, v) = graph.nodes().cartesian_product(graph.nodes())
+            .find(|&(u, v)| u != v && !graph.contains_edge(u, v))
+            .unwrap();
+        graph.add_edge(u, v, ());
+        assert!(matches!(db.get_optimal_value("test.clq", Problem::Mvc, &graph),
+                         Err(YamlError::FingerprintMismatch(_, _))));
+    }
+
+    #[test]
+    fn test_add_run_unknown_instance() {
+        let mut db = InstanceDatabase::new();
+        let run = RunRecord::new(Problem::Mvc, "bnb", 1, Duration::from_millis(12), false, "");
+        assert!(matches!(db.add_run("unknown.clq", run), Err(YamlError::NotFound(_, _))));
+    }
+
+    #[test]
+    fn test_missing_database_is_empty() {
+        let dir = std::env::temp_dir().join(format!("mvc_db_missing_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("instances.yml");
+        let path = path.to_str().unwrap();
+
+        assert_eq!(InstanceDatabase::load(path).unwrap(), InstanceDatabase::new());
+        InstanceDatabase::update(path, |db| {
+            db.insert(InstanceRecord::new("a.clq", "clq", 4, 3));
+            Ok(())
+        }).unwrap();
+        assert_eq!(InstanceDatabase::load(path).unwrap().instances().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,7 +3,10 @@ use std::fmt;
 use std::io;
 use std::num::ParseIntError;
 
-/// Error returned by the Clock when trying to exit a subroutine that has not been started.
+use crate::database::RunStatus;
+use crate::StopReason;
+
+/// Error returned by the Clock when trying to exit a subroutine that is not the last one entered.
 #[derive(Debug)]
 pub struct ClockError {
     pub message: String,
@@ -29,22 +32,43 @@ impl Error for ClockError {
     }
 }
 
+/// Error returned when a DIMACS file cannot be parsed.
+///
+/// When the error is caused by a specific token of the file, its position (1-based line and column)
+/// is stored along with the message.
 #[derive(Debug)]
 pub struct InvalidClqFileFormat {
     pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
 }
 
 impl InvalidClqFileFormat {
     pub fn new(message: &str) -> InvalidClqFileFormat {
         InvalidClqFileFormat {
             message: message.to_string(),
+            line: None,
+            column: None,
+        }
+    }
+
+    /// Creates an error located at the given line and column of the file.
+    pub fn at(message: &str, line: usize, column: usize) -> InvalidClqFileFormat {
+        InvalidClqFileFormat {
+            message: message.to_string(),
+            line: Some(line),
+            column: Some(column),
         }
     }
 }
 
 impl fmt::Display for InvalidClqFileFormat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.message)
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: {}", line, column, self.message),
+            (Some(line), None) => write!(f, "line {}: {}", line, self.message),
+            _ => write!(f, "{}", self.message),
+        }
     }
 }
 
@@ -76,6 +100,8 @@ pub enum YamlError {
     YAMLParsingError(String, serde_yaml::Error),
     /// Error returned when the YAML file is not formatted correctly.
     YAMLFormatError(String, serde_yaml::Error),
+    /// Error returned when a graph does not match the fingerprint stored for its id (message, details).
+    FingerprintMismatch(String, String),
 }
 
 impl fmt::Display for YamlError {
@@ -85,6 +111,7 @@ impl fmt::Display for YamlError {
             YamlError::NotFound(msg, _err) => write!(f, "{}", msg),
             YamlError::YAMLParsingError(msg, _err) => write!(f, "{}.", msg),
             YamlError::YAMLFormatError(msg, _err) => write!(f, "{}.", msg),
+            YamlError::FingerprintMismatch(msg, _err) => write!(f, "{}", msg),
         }
     }
 }
@@ -96,6 +123,7 @@ impl fmt::Debug for YamlError {
             YamlError::NotFound(msg, err) => write!(f, "{}:\n {:?}", msg, err),
             YamlError::YAMLParsingError(msg, err) => write!(f, "{}:\n {:?}", msg, err),
             YamlError::YAMLFormatError(msg, err) => write!(f, "{}:\n {:?}", msg, err),
+            YamlError::FingerprintMismatch(msg, err) => write!(f, "{}:\n {:?}", msg, err),
         }
     }
 }
@@ -107,6 +135,7 @@ impl Error for YamlError {
             YamlError::NotFound(msg, _err) => msg,
             YamlError::YAMLParsingError(msg, _err) => msg,
             YamlError::YAMLFormatError(msg, _err) => msg,
+            YamlError::FingerprintMismatch(msg, _err) => msg,
         }
     }
 }
@@ -125,3 +154,204 @@ impl From<io::Error> for YamlError {
 
 
 
+
+/// Error returned when a graph cache file cannot be written or read.
+pub enum CacheError {
+    /// Error returned when there is an error while creating / reading a file.
+    IoError(String, io::Error),
+    /// Error returned when the content of the cache file is not valid (bad header, version, checksum...).
+    InvalidFormat(String),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CacheError::IoError(msg, _err) => write!(f, "{}", msg),
+            CacheError::InvalidFormat(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl fmt::Debug for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CacheError::IoError(msg, err) => write!(f, "{}:\n {:?}", msg, err),
+            CacheError::InvalidFormat(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for CacheError {
+    fn description(&self) -> &str {
+        match self {
+            CacheError::IoError(msg, _err) => msg,
+            CacheError::InvalidFormat(msg) => msg,
+        }
+    }
+}
+
+impl From<io::Error> for CacheError {
+    fn from(err: io::Error) -> Self {
+        CacheError::IoError("Error while creating / opening file".to_string(), err)
+    }
+}
+
+/// Error returned by the public APIs of the crate.
+///
+/// It wraps the errors of the modules (graph files, YAML files, cache files, clock) along with the errors of the
+/// solvers, so that `?` can be used across the whole crate.
+pub enum MvcError {
+    /// Error returned when a DIMACS file cannot be parsed.
+    GraphFormat(InvalidClqFileFormat),
+    /// Error returned when a YAML file (database, checkpoint...) cannot be read or written.
+    Yaml(YamlError),
+    /// Error returned when a graph cache file cannot be read or written.
+    Cache(CacheError),
+    /// Error returned when the subroutines of the clock are not entered and exited in order.
+    Clock(ClockError),
+    /// Error returned when a solver returns a solution that is not valid (message, details).
+    InvalidResult(String, String),
+    /// Error returned when a thread of a solver panicked (message).
+    ThreadFailure(String),
+    /// Error returned when a solver is stopped by a limit (time, node budget or cancellation) before it finds any
+    /// solution.
+    Timeout(StopReason),
+    /// Error returned when the parameters of a graph generator describe no graph (message).
+    InvalidParameters(String),
+}
+
+impl fmt::Display for MvcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MvcError::GraphFormat(err) => write!(f, "Invalid graph file : {}", err),
+            MvcError::Yaml(err) => write!(f, "{}", err),
+            MvcError::Cache(err) => write!(f, "{}", err),
+            MvcError::Clock(err) => write!(f, "{}", err),
+            MvcError::InvalidResult(msg, _details) => write!(f, "{}", msg),
+            MvcError::ThreadFailure(msg) => write!(f, "{}", msg),
+            MvcError::Timeout(reason) => write!(f, "The solver was stopped ({}) before finding a solution",
+                                                RunStatus::from(*reason)),
+            MvcError::InvalidParameters(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl fmt::Debug for MvcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MvcError::GraphFormat(err) => write!(f, "Invalid graph file : {:?}", err),
+            MvcError::Yaml(err) => write!(f, "{:?}", err),
+            MvcError::Cache(err) => write!(f, "{:?}", err),
+            MvcError::Clock(err) => write!(f, "{:?}", err),
+            MvcError::InvalidResult(msg, details) => write!(f, "{}:\n {}", msg, details),
+            MvcError::ThreadFailure(msg) => write!(f, "{}", msg),
+            MvcError::Timeout(reason) => write!(f, "Stopped by the {:?} before finding a solution", reason),
+            MvcError::InvalidParameters(msg) => write!(f, "Invalid parameters : {}", msg),
+        }
+    }
+}
+
+impl Error for MvcError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MvcError::GraphFormat(err) => Some(err),
+            MvcError::Yaml(err) => Some(err),
+            MvcError::Cache(err) => Some(err),
+            MvcError::Clock(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<InvalidClqFileFormat> for MvcError {
+    fn from(err: InvalidClqFileFormat) -> Self {
+        MvcError::GraphFormat(err)
+    }
+}
+
+impl From<YamlError> for MvcError {
+    fn from(err: YamlError) -> Self {
+        MvcError::Yaml(err)
+    }
+}
+
+impl From<CacheError> for MvcError {
+    fn from(err: CacheError) -> Self {
+        MvcError::Cache(err)
+    }
+}
+
+impl From<ClockError> for MvcError {
+    fn from(err: ClockError) -> Self {
+        MvcError::Clock(err)
+    }
+}
+
+/// Error returned by a command of the mvc bin (see the cli module for the exit code of each variant).
+pub enum CliError {
+    /// Error returned when the command line is not valid (message, usage of the command).
+    Usage(String, String),
+    /// Error returned when an input cannot be read or an output cannot be written (message, details).
+    IoError(String, String),
+    /// Error returned when a solver fails (message, details).
+    Solver(String, String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(msg, _usage) => write!(f, "{}", msg),
+            CliError::IoError(msg, _err) => write!(f, "{}", msg),
+            CliError::Solver(msg, _err) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl fmt::Debug for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(msg, usage) => write!(f, "{}:\n {}", msg, usage),
+            CliError::IoError(msg, err) => write!(f, "{}:\n {}", msg, err),
+            CliError::Solver(msg, err) => write!(f, "{}:\n {}", msg, err),
+        }
+    }
+}
+
+impl Error for CliError {
+    fn description(&self) -> &str {
+        match self {
+            CliError::Usage(msg, _usage) => msg,
+            CliError::IoError(msg, _err) => msg,
+            CliError::Solver(msg, _err) => msg,
+        }
+    }
+}
+
+impl From<YamlError> for CliError {
+    fn from(err: YamlError) -> Self {
+        CliError::IoError(err.to_string(), format!("{:?}", err))
+    }
+}
+
+impl From<InvalidClqFileFormat> for CliError {
+    fn from(err: InvalidClqFileFormat) -> Self {
+        CliError::IoError(format!("Invalid graph file : {}", err), format!("{:?}", err))
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::IoError(format!("Error while reading or writing a file : {}", err), format!("{:?}", err))
+    }
+}
+
+impl From<MvcError> for CliError {
+    fn from(err: MvcError) -> Self {
+        match err {
+            MvcError::GraphFormat(err) => err.into(),
+            MvcError::Yaml(err) => err.into(),
+            MvcError::Cache(err) => CliError::IoError(err.to_string(), format!("{:?}", err)),
+            err => CliError::Solver(err.to_string(), format!("{:?}", err)),
+        }
+    }
+}
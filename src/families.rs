@@ -0,0 +1,477 @@
+//! Constructive generators for the classic DIMACS families (Hamming, Johnson, Keller, Mycielski, queen, MANN and
+//! p_hat).
+//!
+//! A [`Family`] is named like the DIMACS files (`hamming8-4`, `keller4`, `MANN_a27`, ...) and builds a graph with the
+//! same structure as the bundled file : the vertices may be numbered differently, but the graphs are isomorphic, which
+//! can be checked with the Weisfeiler–Lehman invariant of their fingerprints (see [`crate::fingerprint`]).
+//! The p_hat graphs are random, so only their parameters match the bundled files.
+//!
+//! The optimal values of an instance are known when there is a formula for them (for example the maximum clique of
+//! a Mycielski graph, which is triangle free) or when they are published for the DIMACS instance.
+use std::fmt;
+use std::str::FromStr;
+
+use petgraph::prelude::UnGraphMap;
+
+use crate::database::{InstanceRecord, KnownValue, Problem};
+use crate::generators::{empty_graph, Rng};
+
+/// A graph of one of the classic DIMACS families. See the module documentation.
+///
+/// # Example
+/// ```rust
+/// use vertex::database::Problem;
+/// use vertex::families::Family;
+///
+/// let family: Family = "hamming6-2".parse().unwrap();
+/// assert_eq!(family, Family::Hamming { bits: 6, distance: 2 });
+/// assert_eq!(family.to_string(), "hamming6-2");
+///
+/// let graph = family.generate(0);
+/// assert_eq!((graph.node_count(), graph.edge_count()), (64, 1824));
+/// assert_eq!(family.known_values()[0].0, Problem::Clique);
+/// assert_eq!(family.known_values()[0].1.value, 32);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    /// Words of `bits` bits, adjacent if they are at least at Hamming distance `distance` (`hamming<bits>-<distance>`)
+    Hamming { bits: u32, distance: u32 },
+    /// Words of `bits` bits with `weight` ones, adjacent if they are at least at Hamming distance `distance`
+    /// (`johnson<bits>-<weight>-<distance>`)
+    Johnson { bits: u32, weight: u32, distance: u32 },
+    /// Neighbourhood of a vertex in the Keller graph of dimension `dimension` (`keller<dimension>`)
+    Keller { dimension: u32 },
+    /// Graph obtained from an edge with `k - 1` Mycielski transformations (`myciel<k>`)
+    Mycielski { k: u32 },
+    /// Squares of a chessboard, adjacent if a queen can move from one to the other (`queen<rows>_<cols>`)
+    Queen { rows: u32, cols: u32 },
+    /// Clique formulation of the Steiner triple covering problem with 3^k points (`MANN_a<3^k>`)
+    Mann { k: u32 },
+    /// p_hat random graph with `order` vertices and a density of about `density / 4` (`p_hat<order>-<density>`)
+    PHat { order: u32, density: u32 },
+}
+
+impl Family {
+    /// Returns the id of the instance in the database (`<name>.clq`).
+    pub fn id(&self) -> String {
+        format!("{}.clq", self)
+    }
+
+    /// Builds the graph. The seed is only used by the random families (p_hat).
+    ///
+    /// # Panics
+    /// If the parameters are too large for the vertices to be enumerated (more than 2^32 candidates).
+    pub fn generate(&self, seed: u64) -> UnGraphMap<u64, ()> {
+        match *self {
+            Family::Hamming { bits, distance } => {
+                let words: Vec<u64> = (0..1u64 << bits).collect();
+                words_graph(&words, distance)
+            }
+            Family::Johnson { bits, weight, distance } => {
+                let words: Vec<u64> = (0..1u64 << bits).filter(|w| w.count_ones() == weight).collect();
+                words_graph(&words, distance)
+            }
+            Family::Keller { dimension } => keller(dimension),
+            Family::Mycielski { k } => mycielski(k),
+            Family::Queen { rows, cols } => queen(rows, cols),
+            Family::Mann { k } => mann(k),
+            Family::PHat { order, density } => {
+                let a = (density as f64 - 1.0) / 4.0;
+                p_hat(order as usize, a, a + 0.5, seed)
+            }
+        }
+    }
+
+    /// Returns the known optimal values of the instance, with their provenance.
+    pub fn known_values(&self) -> Vec<(Problem, KnownValue)> {
+        let formula = |value: u64| KnownValue::new(value, "family formula");
+        let dimacs = |value: u64| KnownValue::new(value, "DIMACS");
+        let mut values = Vec::new();
+        match *self {
+            Family::Hamming { bits, distance } => {
+                let clique = match (bits, distance) {
+                    (_, 0..=1) => Some(formula(1 << bits)),
+                    // The words of even weight
+                    (_, 2) => Some(formula(1 << (bits - 1))),
+                    (b, d) if d > b => Some(formula(1)),
+                    // A word and its complement
+                    (b, d) if d == b => Some(formula(2)),
+                    (6, 4) => Some(dimacs(4)),
+                    (8, 4) => Some(dimacs(16)),
+                    (10, 4) => Some(dimacs(40)),
+                    _ => None,
+                };
+                values.extend(clique.map(|v| (Problem::Clique, v)));
+            }
+            Family::Johnson { bits, weight, distance } => {
+                // Two words of the same weight are at an even distance 2(weight - common ones)
+                let clique = match (bits, weight, distance) {
+                    (b, w, 0..=2) if w <= b => Some(formula(binomial(b as u64, w as u64))),
+                    (_, w, d) if d > 2 * w => Some(formula(1)),
+                    // Disjoint pairs
+                    (b, 2, 3..=4) => Some(formula(b as u64 / 2)),
+                    (8, 4, 4) => Some(dimacs(14)),
+                    _ => None,
+                };
+                values.extend(clique.map(|v| (Problem::Clique, v)));
+            }
+            Family::Keller { dimension } => {
+                // The clique number of the Keller graph minus the fixed vertex
+                let clique = match dimension {
+                    4 => Some(dimacs(11)),
+                    5 => Some(dimacs(27)),
+                    6 => Some(dimacs(59)),
+                    _ => None,
+                };
+                values.extend(clique.map(|v| (Problem::Clique, v)));
+            }
+            Family::Mycielski { .. } => values.push((Problem::Clique, formula(2))),
+            Family::Queen { rows, cols } => {
+                // A row is a maximum clique, and n non attacking queens can be placed on a n x n board if n >= 4
+                if rows == cols && rows >= 4 {
+                    values.push((Problem::Clique, formula(rows as u64)));
+                    values.push((Problem::Mis, formula(rows as u64)));
+                }
+            }
+            Family::Mann { k } => {
+                // One vertex per triple plus the points left out of a minimum covering
+                let clique = match k {
+                    2 => Some(dimacs(16)),
+                    3 => Some(dimacs(126)),
+                    4 => Some(dimacs(1100)),
+                    _ => None,
+                };
+                values.extend(clique.map(|v| (Problem::Clique, v)));
+            }
+            Family::PHat { .. } => {}
+        }
+        values
+    }
+
+    /// Returns the record of the graph of the family with its known values. Once stored with
+    /// [`crate::database::InstanceDatabase::add_record`], the values already in the database are kept.
+    pub fn record(&self, graph: &UnGraphMap<u64, ()>) -> InstanceRecord {
+        let mut record = InstanceRecord::from_graph(&self.id(), "clq", graph);
+        for (problem, value) in self.known_values() {
+            record.set_known(problem, value);
+        }
+        record
+    }
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Family::Hamming { bits, distance } => format!("hamming{}-{}", bits, distance),
+            Family::Johnson { bits, weight, distance } => format!("johnson{}-{}-{}", bits, weight, distance),
+            Family::Keller { dimension } => format!("keller{}", dimension),
+            Family::Mycielski { k } => format!("myciel{}", k),
+            Family::Queen { rows, cols } => format!("queen{}_{}", rows, cols),
+            Family::Mann { k } => format!("MANN_a{}", 3u64.pow(k)),
+            Family::PHat { order, density } => format!("p_hat{}-{}", order, density),
+        };
+        f.pad(&name)
+    }
+}
+
+impl FromStr for Family {
+    type Err = String;
+
+    /// Parses a DIMACS name, with or without the `.clq` extension.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Unknown DIMACS family instance {:?}", s);
+        let name = s.strip_suffix(".clq").unwrap_or(s);
+        let numbers = |rest: &str, separator: char| -> Result<Vec<u32>, String> {
+            rest.split(separator).map(|n| n.parse::<u32>().map_err(|_| error())).collect()
+        };
+
+        let family = if let Some(rest) = name.strip_prefix("hamming") {
+            match numbers(rest, '-')?[..] {
+                [bits, distance] if (1..=32).contains(&bits) && distance >= 1 => Family::Hamming { bits, distance },
+                _ => return Err(error()),
+            }
+        } else if let Some(rest) = name.strip_prefix("johnson") {
+            match numbers(rest, '-')?[..] {
+                [bits, weight, distance] if bits <= 32 && weight <= bits => Family::Johnson { bits, weight, distance },
+                _ => return Err(error()),
+            }
+        } else if let Some(rest) = name.strip_prefix("keller") {
+            match numbers(rest, '-')?[..] {
+                [dimension] if (1..=16).contains(&dimension) => Family::Keller { dimension },
+                _ => return Err(error()),
+            }
+        } else if let Some(rest) = name.strip_prefix("myciel") {
+            match numbers(rest, '-')?[..] {
+                [k] if (1..=30).contains(&k) => Family::Mycielski { k },
+                _ => return Err(error()),
+            }
+        } else if let Some(rest) = name.strip_prefix("queen") {
+            match numbers(rest, '_')?[..] {
+                [rows, cols] if rows > 0 && cols > 0 => Family::Queen { rows, cols },
+                _ => return Err(error()),
+            }
+        } else if let Some(rest) = name.strip_prefix("MANN_a") {
+            let points = numbers(rest, '-')?;
+            match (1..=10).find(|k| Some(&3u32.pow(*k)) == points.first()) {
+                Some(k) if points.len() == 1 => Family::Mann { k },
+                _ => return Err(format!("{} (only the MANN_a<3^k> instances can be built)", error())),
+            }
+        } else if let Some(rest) = name.strip_prefix("p_hat") {
+            match numbers(rest, '-')?[..] {
+                [order, density] if (1..=3).contains(&density) => Family::PHat { order, density },
+                _ => return Err(error()),
+            }
+        } else {
+            return Err(error());
+        };
+        Ok(family)
+    }
+}
+
+fn binomial(n: u64, k: u64) -> u64 {
+    (0..k.min(n - k)).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// Graph whose vertices are the given words, adjacent if they are at least at the given Hamming distance.
+fn words_graph(words: &[u64], distance: u32) -> UnGraphMap<u64, ()> {
+    let mut graph = empty_graph(words.len());
+    for (i, u) in words.iter().enumerate() {
+        for (j, v) in words.iter().enumerate().skip(i + 1) {
+            if (u ^ v).count_ones() >= distance {
+                graph.add_edge(i as u64, j as u64, ());
+            }
+        }
+    }
+    graph
+}
+
+/// Keller graph of dimension n restricted to the neighbours of (0, ..., 0), as in the DIMACS keller instances.
+///
+/// The vertices of the Keller graph are the words of {0, 1, 2, 3}^n. Two words are adjacent if they differ in at
+/// least two positions and differ by exactly 2 in at least one position.
+fn keller(dimension: u32) -> UnGraphMap<u64, ()> {
+    let adjacent = |mut u: u64, mut v: u64| {
+        let (mut different, mut opposite) = (0, false);
+        for _ in 0..dimension {
+            let (a, b) = (u & 3, v & 3);
+            if a != b {
+                different += 1;
+                opposite |= (a + 4 - b) % 4 == 2;
+            }
+            u >>= 2;
+            v >>= 2;
+        }
+        different >= 2 && opposite
+    };
+    let words: Vec<u64> = (1..1u64 << (2 * dimension)).filter(|&w| adjacent(0, w)).collect();
+    let mut graph = empty_graph(words.len());
+    for (i, &u) in words.iter().enumerate() {
+        for (j, &v) in words.iter().enumerate().skip(i + 1) {
+            if adjacent(u, v) {
+                graph.add_edge(i as u64, j as u64, ());
+            }
+        }
+    }
+    graph
+}
+
+/// Applies k - 1 Mycielski transformations to an edge.
+///
+/// The transformation of a graph with the vertices 0..n adds a copy n + v of each vertex v, linked to the
+/// neighbours of v, and a vertex 2n linked to all the copies.
+fn mycielski(k: u32) -> UnGraphMap<u64, ()> {
+    let mut graph = UnGraphMap::from_edges([(0, 1)]);
+    for _ in 1..k {
+        let n = graph.node_count() as u64;
+        let edges: Vec<(u64, u64)> = graph.all_edges().map(|(u, v, _)| (u, v)).collect();
+        for (u, v) in edges {
+            graph.add_edge(u, n + v, ());
+            graph.add_edge(v, n + u, ());
+        }
+        for v in 0..n {
+            graph.add_edge(n + v, 2 * n, ());
+        }
+    }
+    graph
+}
+
+/// Queen graph : the square (r, c) is the vertex r * cols + c.
+fn queen(rows: u32, cols: u32) -> UnGraphMap<u64, ()> {
+    let squares: Vec<(i64, i64)> = (0..rows as i64).flat_map(|r| (0..cols as i64).map(move |c| (r, c))).collect();
+    let mut graph = empty_graph(squares.len());
+    for (i, &(r1, c1)) in squares.iter().enumerate() {
+        for (j, &(r2, c2)) in squares.iter().enumerate().skip(i + 1) {
+            if r1 == r2 || c1 == c2 || (r1 - r2).abs() == (c1 - c2).abs() {
+                graph.add_edge(i as u64, j as u64, ());
+            }
+        }
+    }
+    graph
+}
+
+/// Clique formulation (Mannino and Sassano) of the covering of the Steiner triple system of the affine space
+/// AG(k, 3) : its points are the words of {0, 1, 2}^k and its triples are the sets {a, b, c} with a + b + c = 0.
+///
+/// Each triple t has three vertices (t, p), one for each of its points p, and each point has a vertex. Two vertices
+/// of different triples are adjacent, the points are adjacent to each other and a point is adjacent to the vertices
+/// (t, p) of the other points. A maximum clique takes a set S of points containing no triple and one vertex (t, p)
+/// with p not in S for each triple, so its size is the number of triples plus the number of points left out of a
+/// minimum covering.
+fn mann(k: u32) -> UnGraphMap<u64, ()> {
+    let points = 3u64.pow(k);
+    let third = |a: u64, b: u64| {
+        // Digit by digit, c = -(a + b) mod 3
+        let (mut a, mut b, mut c, mut unit) = (a, b, 0, 1);
+        for _ in 0..k {
+            c += (6 - a % 3 - b % 3) % 3 * unit;
+            a /= 3;
+            b /= 3;
+            unit *= 3;
+        }
+        c
+    };
+    let mut triples = Vec::new();
+    for a in 0..points {
+        for b in a + 1..points {
+            let c = third(a, b);
+            if c > b {
+                triples.push([a, b, c]);
+            }
+        }
+    }
+
+    let first_point = 3 * triples.len() as u64;
+    let mut graph = empty_graph((first_point + points) as usize);
+    for i in 0..first_point {
+        for j in (i / 3 + 1) * 3..first_point {
+            graph.add_edge(i, j, ());
+        }
+    }
+    for p in 0..points {
+        for q in p + 1..points {
+            graph.add_edge(first_point + p, first_point + q, ());
+        }
+        for (t, triple) in triples.iter().enumerate() {
+            for (s, &point) in triple.iter().enumerate() {
+                if point != p {
+                    graph.add_edge(first_point + p, 3 * t as u64 + s as u64, ());
+                }
+            }
+        }
+    }
+    graph
+}
+
+/// p_hat random graph (Gendreau, Soriano and Salvail) : each vertex v gets a probability p(v) uniformly distributed
+/// in [a, b] and the edge {u, v} exists with probability (p(u) + p(v)) / 2. The degrees are more spread than in a
+/// G(n, p) graph with the same density.
+///
+/// # Example
+/// ```rust
+/// use vertex::families::p_hat;
+///
+/// let graph = p_hat(100, 0.25, 0.75, 1);
+/// let density = 2.0 * graph.edge_count() as f64 / (100.0 * 99.0);
+/// assert!((0.4..0.6).contains(&density));
+/// ```
+pub fn p_hat(n: usize, a: f64, b: f64, seed: u64) -> UnGraphMap<u64, ()> {
+    let mut rng = Rng::new(seed);
+    let p: Vec<f64> = (0..n).map(|_| a + (b - a) * rng.next_f64()).collect();
+    let mut graph = empty_graph(n);
+    for u in 0..n {
+        for v in u + 1..n {
+            if rng.chance((p[u] + p[v]) / 2.0) {
+                graph.add_edge(u as u64, v as u64, ());
+            }
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod families_tests {
+    use crate::database::{InstanceDatabase, Problem};
+    use crate::fingerprint::GraphFingerprint;
+    use crate::graph_utils::complement;
+    use crate::{branch_and_bound, Clock};
+
+    use super::*;
+
+    fn bundled_database() -> InstanceDatabase {
+        InstanceDatabase::load("src/resources/instances.yml").unwrap()
+    }
+
+    #[test]
+    fn test_names() {
+        for name in ["hamming8-4", "johnson8-2-4", "keller5", "myciel3", "queen5_5", "MANN_a27", "p_hat300-1"] {
+            let family: Family = name.parse().unwrap();
+            assert_eq!(family.to_string(), name);
+            assert_eq!(family.id(), format!("{}.clq", name));
+        }
+        assert_eq!("MANN_a9.clq".parse::<Family>().unwrap(), Family::Mann { k: 2 });
+        for name in ["MANN_a45", "hamming8", "keller", "p_hat300-4", "C125.9", "queen0_5", "hamming0-2", "hamming8-0"] {
+            assert!(name.parse::<Family>().is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_bundled_instances_have_the_same_structure() {
+        let db = bundled_database();
+        for name in ["hamming8-4", "keller4", "keller5", "myciel3", "myciel4", "queen5_5", "MANN_a27"] {
+            let family: Family = name.parse().unwrap();
+            let fingerprint = GraphFingerprint::with_wl(&family.generate(0));
+            let record = db.get(&family.id()).unwrap();
+            assert!(fingerprint.may_be_isomorphic(&record.hash.unwrap()), "{} : {} but the file has {}",
+                    name, fingerprint, record.hash.unwrap());
+
+            // The known values agree with the database
+            for (problem, value) in family.known_values() {
+                assert_eq!(record.known(problem).map(|v| v.value), Some(value.value), "{} {}", name, problem);
+            }
+        }
+    }
+
+    #[test]
+    fn test_p_hat_has_the_density_of_the_bundled_instances() {
+        let db = bundled_database();
+        for density in 1..=3 {
+            let family = Family::PHat { order: 300, density };
+            let graph = family.generate(density as u64);
+            let record = db.get(&family.id()).unwrap();
+            assert_eq!(graph.node_count(), record.order);
+            let expected = record.density();
+            let actual = 2.0 * graph.edge_count() as f64 / (300.0 * 299.0);
+            assert!((actual - expected).abs() < 0.03, "{} : {} instead of {}", family, actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_known_values_of_small_instances() {
+        // Property : the computed or recorded clique numbers are the ones found by the branch and bound
+        let families = [
+            Family::Hamming { bits: 6, distance: 2 }, Family::Hamming { bits: 6, distance: 4 },
+            Family::Hamming { bits: 5, distance: 5 }, Family::Johnson { bits: 8, weight: 2, distance: 4 },
+            Family::Johnson { bits: 7, weight: 3, distance: 2 }, Family::Mycielski { k: 4 },
+            Family::Queen { rows: 6, cols: 6 }, Family::Mann { k: 2 },
+        ];
+        for family in families {
+            let graph = family.generate(0);
+            for (problem, value) in family.known_values() {
+                let target = if problem == Problem::Clique { complement(&graph) } else { graph.clone() };
+                let cover = branch_and_bound(&target, &mut Clock::new(60)).unwrap().0;
+                assert_eq!(graph.node_count() as u64 - cover, value.value, "{} {}", family, problem);
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_to_database() {
+        let mut db = InstanceDatabase::new();
+        let family = Family::Queen { rows: 5, cols: 5 };
+        db.add_record(family.record(&family.generate(0)));
+        let record = db.get("queen5_5.clq").unwrap();
+        assert_eq!((record.order, record.size), (25, 160));
+        assert_eq!(db.known_value("queen5_5.clq", Problem::Clique), Some(5));
+        assert_eq!(db.known_value("queen5_5.clq", Problem::Mvc), Some(20));
+    }
+}
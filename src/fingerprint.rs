@@ -0,0 +1,242 @@
+//! Structural fingerprint of a graph, used to check that a file still describes the instance stored in the database.
+//!
+//! A fingerprint is made of :
+//! * the order and the size of the graph
+//! * a digest of the sorted list of edges, which identifies the graph with its labels
+//! * optionally, a Weisfeiler–Lehman invariant (1-dimensional colour refinement), which does not depend on the
+//!   labels of the vertices : two isomorphic graphs have the same invariant.
+//!
+//! It is stored as a string in the database, for example `5:6:9ae16a3b2f90404f:2c8b4e1d0a7f3c55`.
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use petgraph::prelude::UnGraphMap;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::fnv1a;
+
+/// Number of refinement rounds of the Weisfeiler–Lehman invariant.
+pub const WL_ROUNDS: usize = 3;
+
+/// Structural fingerprint of a graph. See the module documentation.
+///
+/// # Example
+/// ```rust
+/// use petgraph::prelude::UnGraphMap;
+/// use vertex::fingerprint::GraphFingerprint;
+///
+/// let path = UnGraphMap::<u64, ()>::from_edges(&[(0, 1), (1, 2)]);
+/// let relabelled = UnGraphMap::<u64, ()>::from_edges(&[(1, 0), (0, 2)]);
+///
+/// let a = GraphFingerprint::with_wl(&path);
+/// let b = GraphFingerprint::with_wl(&relabelled);
+/// // Not the same labelled graph, but isomorphic graphs
+/// assert!(!a.matches(&b));
+/// assert_eq!(a.wl, b.wl);
+/// assert!(a.may_be_isomorphic(&b));
+///
+/// // Fingerprints are stored as strings
+/// assert_eq!(a.to_string().parse::<GraphFingerprint>().unwrap(), a);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct GraphFingerprint {
+    pub order: usize,
+    pub size: usize,
+    /// Digest of the sorted list of edges
+    pub edges: u64,
+    /// Weisfeiler–Lehman invariant (see [`wl_invariant`])
+    pub wl: Option<u64>,
+}
+
+impl GraphFingerprint {
+    /// Computes the fingerprint of a graph without the Weisfeiler–Lehman invariant.
+    pub fn new(graph: &UnGraphMap<u64, ()>) -> GraphFingerprint {
+        let mut edges: Vec<(u64, u64)> = graph.all_edges()
+            .map(|(u, v, _)| (u.min(v), u.max(v)))
+            .collect();
+        edges.sort_unstable();
+        let mut bytes = Vec::with_capacity(16 * edges.len());
+        for (u, v) in edges {
+            bytes.extend_from_slice(&u.to_le_bytes());
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        GraphFingerprint {
+            order: graph.node_count(),
+            size: graph.edge_count(),
+            edges: fnv1a(&bytes),
+            wl: None,
+        }
+    }
+
+    /// Computes the fingerprint of a graph with the Weisfeiler–Lehman invariant.
+    pub fn with_wl(graph: &UnGraphMap<u64, ()>) -> GraphFingerprint {
+        GraphFingerprint {
+            wl: Some(wl_invariant(graph, WL_ROUNDS)),
+            ..GraphFingerprint::new(graph)
+        }
+    }
+
+    /// Returns true if both fingerprints describe the same labelled graph.
+    ///
+    /// The Weisfeiler–Lehman invariants are only compared when both fingerprints have one.
+    pub fn matches(&self, other: &GraphFingerprint) -> bool {
+        let wl_matches = match (self.wl, other.wl) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+        self.order == other.order && self.size == other.size && self.edges == other.edges && wl_matches
+    }
+
+    /// Returns false if both fingerprints cannot describe isomorphic graphs (different orders, sizes or
+    /// Weisfeiler–Lehman invariants). The labels of the vertices are ignored.
+    ///
+    /// The invariants are only compared when both fingerprints have one, and equal invariants do not prove that
+    /// the graphs are isomorphic.
+    pub fn may_be_isomorphic(&self, other: &GraphFingerprint) -> bool {
+        let wl_matches = match (self.wl, other.wl) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+        self.order == other.order && self.size == other.size && wl_matches
+    }
+}
+
+impl fmt::Display for GraphFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{:016x}", self.order, self.size, self.edges)?;
+        if let Some(wl) = self.wl {
+            write!(f, ":{:016x}", wl)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GraphFingerprint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid graph fingerprint {:?} (expecting order:size:edges[:wl])", s);
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() != 3 && parts.len() != 4 {
+            return Err(error());
+        }
+        let hex = |p: &str| u64::from_str_radix(p, 16).map_err(|_| error());
+        Ok(GraphFingerprint {
+            order: parts[0].parse().map_err(|_| error())?,
+            size: parts[1].parse().map_err(|_| error())?,
+            edges: hex(parts[2])?,
+            wl: parts.get(3).map(|p| hex(p)).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<String> for GraphFingerprint {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<GraphFingerprint> for String {
+    fn from(value: GraphFingerprint) -> Self {
+        value.to_string()
+    }
+}
+
+/// Computes the Weisfeiler–Lehman invariant of a graph.
+///
+/// Every vertex starts with its degree as colour. At each round, the new colour of a vertex is the hash of its colour
+/// and of the sorted colours of its neighbours. The invariant is the hash of the sorted colours after the last round.
+/// It does not depend on the labels of the vertices, so isomorphic graphs have the same invariant
+/// (the converse is not true, for example for regular graphs of the same order and degree).
+///
+/// # Example
+/// ```rust
+/// use petgraph::prelude::UnGraphMap;
+/// use vertex::fingerprint::wl_invariant;
+///
+/// let star = UnGraphMap::<u64, ()>::from_edges(&[(0, 1), (0, 2), (0, 3)]);
+/// let other_star = UnGraphMap::<u64, ()>::from_edges(&[(3, 0), (3, 1), (3, 2)]);
+/// let path = UnGraphMap::<u64, ()>::from_edges(&[(0, 1), (1, 2), (2, 3)]);
+/// assert_eq!(wl_invariant(&star, 3), wl_invariant(&other_star, 3));
+/// assert_ne!(wl_invariant(&star, 3), wl_invariant(&path, 3));
+/// ```
+pub fn wl_invariant(graph: &UnGraphMap<u64, ()>, rounds: usize) -> u64 {
+    let nodes: Vec<u64> = graph.nodes().collect();
+    let mut colors: HashMap<u64, u64> = nodes.iter()
+        .map(|&v| (v, graph.neighbors(v).count() as u64))
+        .collect();
+
+    let mut bytes = Vec::new();
+    for _ in 0..rounds {
+        let mut next = HashMap::with_capacity(colors.len());
+        for &v in &nodes {
+            let mut neighbors: Vec<u64> = graph.neighbors(v).map(|u| colors[&u]).collect();
+            neighbors.sort_unstable();
+            bytes.clear();
+            bytes.extend_from_slice(&colors[&v].to_le_bytes());
+            for c in neighbors {
+                bytes.extend_from_slice(&c.to_le_bytes());
+            }
+            next.insert(v, fnv1a(&bytes));
+        }
+        colors = next;
+    }
+
+    let mut sorted: Vec<u64> = colors.into_values().collect();
+    sorted.sort_unstable();
+    bytes.clear();
+    for c in sorted {
+        bytes.extend_from_slice(&c.to_le_bytes());
+    }
+    fnv1a(&bytes)
+}
+
+#[cfg(test)]
+mod fingerprint_tests {
+    use crate::graph_utils::load_clq_file;
+
+    use super::*;
+
+    #[test]
+    fn test_modified_graph() {
+        let graph = load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+        let fingerprint = GraphFingerprint::with_wl(&graph);
+        assert!(fingerprint.matches(&GraphFingerprint::new(&graph)));
+
+        // Moving an edge keeps the order and the size but not the fingerprint
+        let mut modified = graph.clone();
+        let (u, v, _) = modified.all_edges().next().unwrap();
+        modified.remove_edge(u, v);
+        let w = modified.nodes().find(|&w| w != u && w != v && !modified.contains_edge(u, w)).unwrap();
+        modified.add_edge(u, w, ());
+        let other = GraphFingerprint::with_wl(&modified);
+        assert_eq!((other.order, other.size), (fingerprint.order, fingerprint.size));
+        assert!(!fingerprint.matches(&other));
+    }
+
+    #[test]
+    fn test_wl_invariant_of_relabelled_graph() {
+        let graph = load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+        let n = graph.node_count() as u64;
+        let mut relabelled = UnGraphMap::<u64, ()>::new();
+        for (u, v, _) in graph.all_edges() {
+            relabelled.add_edge((u * 7 + 3) % n, (v * 7 + 3) % n, ());
+        }
+        assert_eq!(wl_invariant(&graph, WL_ROUNDS), wl_invariant(&relabelled, WL_ROUNDS));
+        assert_ne!(GraphFingerprint::new(&graph).edges, GraphFingerprint::new(&relabelled).edges);
+    }
+
+    #[test]
+    fn test_parse_invalid_fingerprint() {
+        assert!("5:6".parse::<GraphFingerprint>().is_err());
+        assert!("5:6:xyz".parse::<GraphFingerprint>().is_err());
+        assert!("a:6:00ff".parse::<GraphFingerprint>().is_err());
+        let fingerprint: GraphFingerprint = "5:6:00ff".parse().unwrap();
+        assert_eq!(fingerprint.wl, None);
+        assert_eq!(fingerprint.to_string(), "5:6:00000000000000ff");
+    }
+}
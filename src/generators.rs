@@ -0,0 +1,468 @@
+//! Random graph generators.
+//!
+//! Every generator is deterministic for a given seed (the random numbers come from [`Rng`], not from the system),
+//! so that a generated instance can be rebuilt from its parameters. The vertices of the graphs are `0..n` and the
+//! graphs can be written in the DIMACS format with [`crate::graph_utils::graph_to_string`].
+//!
+//! The planted generators also return their planted solution : [`planted_cover`] builds graphs whose minimum vertex
+//! cover is known, which is useful to test the exact algorithms on more than the bundled instances.
+//!
+//! The generators whose parameters can describe no graph (more edges than pairs of vertices, a planted solution larger
+//! than the graph...) return an error instead of a graph.
+use std::collections::HashSet;
+
+use petgraph::prelude::UnGraphMap;
+
+use crate::errors::MvcError;
+
+/// Small pseudo-random number generator (SplitMix64).
+///
+/// It is not suitable for cryptography but is fast, has a good statistical quality and, unlike an external crate,
+/// is guaranteed to produce the same sequence in every version of the project.
+///
+/// # Example
+/// ```rust
+/// use vertex::generators::Rng;
+///
+/// let mut a = Rng::new(42);
+/// let mut b = Rng::new(42);
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// assert!(a.below(10) < 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number uniformly distributed in [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number uniformly distributed in 0..n (n > 0).
+    pub fn below(&mut self, n: u64) -> u64 {
+        // Rejection of the last incomplete range to avoid the modulo bias
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// Returns true with probability p.
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Shuffles a slice (Fisher–Yates).
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            values.swap(i, j);
+        }
+    }
+
+    /// Returns k distinct values of 0..n in a random order (k <= n).
+    pub fn sample(&mut self, n: u64, k: usize) -> Vec<u64> {
+        let mut values: Vec<u64> = (0..n).collect();
+        // Partial Fisher–Yates
+        for i in 0..k {
+            let j = i + self.below(n - i as u64) as usize;
+            values.swap(i, j);
+        }
+        values.truncate(k);
+        values
+    }
+}
+
+/// Returns a graph with the vertices 0..n and no edge.
+pub(crate) fn empty_graph(n: usize) -> UnGraphMap<u64, ()> {
+    let mut graph = UnGraphMap::with_capacity(n, 0);
+    for v in 0..n as u64 {
+        graph.add_node(v);
+    }
+    graph
+}
+
+/// Erdős–Rényi graph G(n, p) : each of the n(n-1)/2 possible edges exists with probability p.
+///
+/// # Example
+/// ```rust
+/// use vertex::generators::gnp;
+/// use vertex::graph_utils::graph_to_string;
+///
+/// let graph = gnp(50, 0.3, 7);
+/// assert_eq!(graph.node_count(), 50);
+/// assert_eq!(graph_to_string(&graph), graph_to_string(&gnp(50, 0.3, 7)));
+/// ```
+pub fn gnp(n: usize, p: f64, seed: u64) -> UnGraphMap<u64, ()> {
+    let mut rng = Rng::new(seed);
+    let mut graph = empty_graph(n);
+    for u in 0..n as u64 {
+        for v in u + 1..n as u64 {
+            if rng.chance(p) {
+                graph.add_edge(u, v, ());
+            }
+        }
+    }
+    graph
+}
+
+/// Erdős–Rényi graph G(n, m) : a graph chosen uniformly among the graphs with n vertices and m edges.
+///
+/// # Throws
+/// MvcError::InvalidParameters if m is greater than n(n-1)/2.
+///
+/// # Example
+/// ```rust
+/// use vertex::generators::gnm;
+///
+/// assert_eq!(gnm(10, 20, 3).unwrap().edge_count(), 20);
+/// assert!(gnm(10, 46, 3).is_err());
+/// ```
+pub fn gnm(n: usize, m: usize, seed: u64) -> Result<UnGraphMap<u64, ()>, MvcError> {
+    let max = n * n.saturating_sub(1) / 2;
+    if m > max {
+        return Err(MvcError::InvalidParameters(format!("A graph with {} vertices has at most {} edges", n, max)));
+    }
+    let mut rng = Rng::new(seed);
+
+    // Draw the edges (or the missing edges for dense graphs) until there are enough of them
+    let dense = 2 * m > max;
+    let target = if dense { max - m } else { m };
+    let mut drawn: HashSet<(u64, u64)> = HashSet::with_capacity(target);
+    while drawn.len() < target {
+        let u = rng.below(n as u64);
+        let v = rng.below(n as u64);
+        if u != v {
+            drawn.insert((u.min(v), u.max(v)));
+        }
+    }
+
+    let mut graph = empty_graph(n);
+    if dense {
+        for u in 0..n as u64 {
+            for v in u + 1..n as u64 {
+                if !drawn.contains(&(u, v)) {
+                    graph.add_edge(u, v, ());
+                }
+            }
+        }
+    } else {
+        for (u, v) in drawn {
+            graph.add_edge(u, v, ());
+        }
+    }
+    Ok(graph)
+}
+
+/// Random d-regular graph with n vertices.
+///
+/// The edges are built by pairing the free "stubs" of the vertices at random while avoiding loops and multiple
+/// edges, and the construction is restarted when it gets stuck (Steger and Wormald). Dense graphs are built as the
+/// complement of a sparse regular graph.
+///
+/// # Throws
+/// MvcError::InvalidParameters if there is no such graph (n * d odd or d >= n).
+///
+/// # Example
+/// ```rust
+/// use vertex::generators::random_regular;
+///
+/// let graph = random_regular(20, 3, 1).unwrap();
+/// assert!(graph.nodes().all(|v| graph.neighbors(v).count() == 3));
+/// assert!(random_regular(5, 3, 1).is_err());
+/// ```
+pub fn random_regular(n: usize, d: usize, seed: u64) -> Result<UnGraphMap<u64, ()>, MvcError> {
+    if d >= n || (n * d) % 2 == 1 {
+        return Err(MvcError::InvalidParameters(format!("There is no {}-regular graph with {} vertices", d, n)));
+    }
+    if 2 * d > n {
+        return random_regular(n, n - 1 - d, seed).map(|g| crate::graph_utils::complement(&g));
+    }
+    let mut rng = Rng::new(seed);
+    loop {
+        if let Some(graph) = try_pairing(n, d, &mut rng) {
+            return Ok(graph);
+        }
+    }
+}
+
+fn try_pairing(n: usize, d: usize, rng: &mut Rng) -> Option<UnGraphMap<u64, ()>> {
+    let mut graph = empty_graph(n);
+    let mut stubs: Vec<u64> = (0..n as u64).flat_map(|v| std::iter::repeat_n(v, d)).collect();
+    while !stubs.is_empty() {
+        // Try a few random pairs before declaring the construction stuck
+        let mut paired = false;
+        for _ in 0..100 {
+            let i = rng.below(stubs.len() as u64) as usize;
+            let j = rng.below(stubs.len() as u64) as usize;
+            let (u, v) = (stubs[i], stubs[j]);
+            if u != v && !graph.contains_edge(u, v) {
+                graph.add_edge(u, v, ());
+                let (hi, lo) = (i.max(j), i.min(j));
+                stubs.swap_remove(hi);
+                stubs.swap_remove(lo);
+                paired = true;
+                break;
+            }
+        }
+        if !paired {
+            return None;
+        }
+    }
+    Some(graph)
+}
+
+/// Barabási–Albert preferential attachment graph.
+///
+/// The graph starts with a clique of m + 1 vertices. Each new vertex is then linked to m distinct existing vertices
+/// chosen with a probability proportional to their degree. The graph has (m + 1)m / 2 + (n - m - 1)m edges.
+///
+/// # Throws
+/// MvcError::InvalidParameters if m is 0 or n <= m.
+pub fn barabasi_albert(n: usize, m: usize, seed: u64) -> Result<UnGraphMap<u64, ()>, MvcError> {
+    if m == 0 || n <= m {
+        return Err(MvcError::InvalidParameters(format!("Expecting 1 <= m < n but read n = {} and m = {}", n, m)));
+    }
+    let mut rng = Rng::new(seed);
+    let mut graph = empty_graph(n);
+    // Each vertex appears once per incident edge, so a uniform draw is proportional to the degree
+    let mut endpoints: Vec<u64> = Vec::with_capacity(2 * m * n);
+    for u in 0..=m as u64 {
+        for v in u + 1..=m as u64 {
+            graph.add_edge(u, v, ());
+            endpoints.push(u);
+            endpoints.push(v);
+        }
+    }
+    for v in m as u64 + 1..n as u64 {
+        let mut targets = HashSet::with_capacity(m);
+        while targets.len() < m {
+            targets.insert(endpoints[rng.below(endpoints.len() as u64) as usize]);
+        }
+        for u in targets {
+            graph.add_edge(u, v, ());
+            endpoints.push(u);
+            endpoints.push(v);
+        }
+    }
+    Ok(graph)
+}
+
+/// Random geometric graph : n points are drawn uniformly in the unit square and two vertices are adjacent if the
+/// distance between their points is at most the radius. The points are also returned.
+pub fn random_geometric(n: usize, radius: f64, seed: u64) -> (UnGraphMap<u64, ()>, Vec<(f64, f64)>) {
+    let mut rng = Rng::new(seed);
+    let points: Vec<(f64, f64)> = (0..n).map(|_| (rng.next_f64(), rng.next_f64())).collect();
+    let mut graph = empty_graph(n);
+    for u in 0..n {
+        for v in u + 1..n {
+            let (dx, dy) = (points[u].0 - points[v].0, points[u].1 - points[v].1);
+            if dx * dx + dy * dy <= radius * radius {
+                graph.add_edge(u as u64, v as u64, ());
+            }
+        }
+    }
+    (graph, points)
+}
+
+/// Graph with a planted minimum vertex cover of size k. The cover is also returned.
+///
+/// The vertices are split into a cover C of k vertices and an independent set I of n - k vertices. Every edge inside
+/// C or between C and I exists with probability p, and a matching of size k between C and I is added. Since I has no
+/// internal edge, C is a vertex cover, and since the matching needs k vertices to be covered, C is minimum.
+///
+/// # Throws
+/// MvcError::InvalidParameters if 2k > n (the matching cannot be built).
+///
+/// # Example
+/// ```rust
+/// use vertex::{branch_and_bound, Clock};
+/// use vertex::generators::planted_cover;
+/// use vertex::graph_utils::is_vertex_cover;
+///
+/// let (graph, cover) = planted_cover(30, 10, 0.2, 3).unwrap();
+/// assert!(is_vertex_cover(&graph, &cover));
+/// assert_eq!(branch_and_bound(&graph, &mut Clock::new(60)).unwrap().0, 10);
+/// assert!(planted_cover(30, 16, 0.2, 3).is_err());
+/// ```
+pub fn planted_cover(n: usize, k: usize, p: f64, seed: u64) -> Result<(UnGraphMap<u64, ()>, Vec<u64>), MvcError> {
+    if 2 * k > n {
+        return Err(MvcError::InvalidParameters(format!("A planted cover of size {} needs at least {} vertices",
+                                                       k, 2 * k)));
+    }
+    let mut rng = Rng::new(seed);
+    let mut vertices: Vec<u64> = (0..n as u64).collect();
+    rng.shuffle(&mut vertices);
+    let (cover, independent) = vertices.split_at(k);
+
+    let mut graph = empty_graph(n);
+    for (i, &u) in cover.iter().enumerate() {
+        graph.add_edge(u, independent[i], ());
+        for &v in cover[i + 1..].iter().chain(independent) {
+            if rng.chance(p) {
+                graph.add_edge(u, v, ());
+            }
+        }
+    }
+    let mut cover = cover.to_vec();
+    cover.sort_unstable();
+    Ok((graph, cover))
+}
+
+/// G(n, p) graph with a hidden clique of size k, in the style of the brock instances. The clique is also returned.
+///
+/// The clique vertices get more edges than the other vertices, which makes them easy to find with their degree.
+/// When `hide` is true, each clique vertex loses random edges to the other vertices so that its expected degree is
+/// the one of the other vertices, as in the brock generator of Brockington and Culberson.
+/// The maximum clique of the graph has at least k vertices.
+///
+/// # Throws
+/// MvcError::InvalidParameters if k > n.
+///
+/// # Example
+/// ```rust
+/// use vertex::generators::hidden_clique;
+/// use vertex::graph_utils::is_clique;
+///
+/// let (graph, clique) = hidden_clique(100, 0.5, 12, true, 9).unwrap();
+/// assert!(is_clique(&graph, &clique));
+/// assert!(hidden_clique(10, 0.5, 12, true, 9).is_err());
+/// ```
+pub fn hidden_clique(n: usize, p: f64, k: usize, hide: bool, seed: u64)
+                     -> Result<(UnGraphMap<u64, ()>, Vec<u64>), MvcError> {
+    if k > n {
+        return Err(MvcError::InvalidParameters(format!("A clique of size {} cannot be hidden in a graph with {} vertices",
+                                                       k, n)));
+    }
+    let mut graph = gnp(n, p, seed);
+    // Another stream for the clique, so that the base graph only depends on (n, p, seed)
+    let mut rng = Rng::new(seed ^ 0xC11C_0E5E_ED00_0000);
+    let mut clique = rng.sample(n as u64, k);
+    clique.sort_unstable();
+
+    let in_clique: HashSet<u64> = clique.iter().copied().collect();
+    for (i, &u) in clique.iter().enumerate() {
+        for &v in &clique[i + 1..] {
+            graph.add_edge(u, v, ());
+        }
+    }
+    if hide {
+        for &u in &clique {
+            let mut outside: Vec<u64> = graph.neighbors(u).filter(|v| !in_clique.contains(v)).collect();
+            rng.shuffle(&mut outside);
+            // The clique gave (k - 1)(1 - p) edges more than expected to u
+            let extra = ((k.saturating_sub(1)) as f64 * (1.0 - p)).round() as usize;
+            for &v in outside.iter().take(extra) {
+                graph.remove_edge(u, v);
+            }
+        }
+    }
+    Ok((graph, clique))
+}
+
+#[cfg(test)]
+mod generators_tests {
+    use crate::{branch_and_bound, Clock};
+    use crate::graph_utils::{graph_to_string, is_clique, is_vertex_cover, parse_clq, ParseMode};
+
+    use super::*;
+
+    #[test]
+    fn test_rng_below_is_uniform() {
+        let mut rng = Rng::new(1);
+        let mut counts = [0; 6];
+        for _ in 0..60_000 {
+            counts[rng.below(6) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| (9_000..11_000).contains(&c)), "{:?}", counts);
+        let mut sample = rng.sample(10, 10);
+        sample.sort_unstable();
+        assert_eq!(sample, (0..10).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn test_gnp_and_gnm() {
+        for seed in 0..5 {
+            let graph = gnp(100, 0.2, seed);
+            // 990 edges expected, with a standard deviation of about 28
+            assert!((850..1130).contains(&graph.edge_count()), "{}", graph.edge_count());
+
+            assert_eq!(gnm(40, 100, seed).unwrap().edge_count(), 100);
+            assert_eq!(gnm(40, 700, seed).unwrap().edge_count(), 700);
+            assert_eq!(gnm(40, 780, seed).unwrap().edge_count(), 780);
+        }
+        assert!(matches!(gnm(40, 781, 0), Err(MvcError::InvalidParameters(_))));
+        assert_ne!(graph_to_string(&gnp(30, 0.5, 1)), graph_to_string(&gnp(30, 0.5, 2)));
+    }
+
+    #[test]
+    fn test_regular_and_barabasi_albert() {
+        for (n, d) in [(10, 3), (50, 4), (31, 20), (12, 11), (7, 0)] {
+            let graph = random_regular(n, d, 5).unwrap();
+            assert_eq!(graph.node_count(), n);
+            assert!(graph.nodes().all(|v| graph.neighbors(v).count() == d), "n = {}, d = {}", n, d);
+        }
+        assert!(matches!(random_regular(4, 4, 1), Err(MvcError::InvalidParameters(_))));
+
+        let (n, m) = (200, 3);
+        let graph = barabasi_albert(n, m, 11).unwrap();
+        assert_eq!(graph.edge_count(), (m + 1) * m / 2 + (n - m - 1) * m);
+        assert!(graph.nodes().all(|v| graph.neighbors(v).count() >= m));
+        assert!(matches!(barabasi_albert(3, 0, 11), Err(MvcError::InvalidParameters(_))));
+        assert!(matches!(barabasi_albert(3, 3, 11), Err(MvcError::InvalidParameters(_))));
+    }
+
+    #[test]
+    fn test_random_geometric() {
+        let (graph, points) = random_geometric(100, 0.2, 4);
+        for u in 0..100 {
+            for v in u + 1..100 {
+                let d = ((points[u].0 - points[v].0).powi(2) + (points[u].1 - points[v].1).powi(2)).sqrt();
+                assert_eq!(graph.contains_edge(u as u64, v as u64), d <= 0.2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_planted_optima() {
+        // Property : the branch and bound finds the planted optimum, whatever the seed
+        for seed in 0..8 {
+            let (graph, cover) = planted_cover(24, 8, 0.3, seed).unwrap();
+            assert!(is_vertex_cover(&graph, &cover));
+            assert_eq!(branch_and_bound(&graph, &mut Clock::new(60)).unwrap().0, 8, "seed {}", seed);
+
+            let (graph, clique) = hidden_clique(30, 0.3, 8, seed % 2 == 0, seed).unwrap();
+            assert!(is_clique(&graph, &clique));
+            let cover = branch_and_bound(&crate::graph_utils::complement(&graph), &mut Clock::new(60)).unwrap().0;
+            assert!(30 - cover >= 8, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_generated_graphs_are_valid_dimacs() {
+        let graphs = [gnp(20, 0.4, 1), gnm(20, 50, 2).unwrap(), barabasi_albert(20, 2, 3).unwrap(),
+            planted_cover(20, 5, 0.5, 4).unwrap().0];
+        for graph in graphs {
+            let (parsed, warnings) = parse_clq(graph_to_string(&graph).as_bytes(), ParseMode::Strict).unwrap();
+            assert!(warnings.is_empty());
+            assert_eq!((parsed.node_count(), parsed.edge_count()), (graph.node_count(), graph.edge_count()));
+        }
+    }
+}
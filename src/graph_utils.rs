@@ -1,16 +1,15 @@
 //! Module containing functions to manipulate graphs used in the project.
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Write};
+use std::io::{BufRead, BufReader};
 
+use itertools::Itertools;
 use petgraph::prelude::UnGraphMap;
 use serde::{Deserialize, Serialize};
-use serde::de::Error;
-use serde_yaml::{Sequence, Value};
 
-use crate::ElapseTime;
-use crate::errors::{InvalidClqFileFormat, YamlError};
+use crate::errors::InvalidClqFileFormat;
 
 /// Check if a given vertex cover is a vertex cover of a given graph.
 ///
@@ -138,15 +137,43 @@ pub fn complement(graph: &UnGraphMap<u64, ()>) -> UnGraphMap<u64, ()> {
     complement
 }
 
+/// Strictness of the DIMACS parser (see [`parse_clq`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Every anomaly of the file is an error.
+    Strict,
+    /// Self-loops, duplicate edges, extra tokens and a wrong number of edges are reported as
+    /// warnings and the graph is still returned.
+    Lenient,
+}
+
+/// Anomaly found in a DIMACS file that did not prevent the parser from building the graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClqWarning {
+    /// Line of the anomaly (starting at 1).
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ClqWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
 /// Load a graph from a DIMACS .col file.
 ///
 /// The format of the file is the following:
-/// * p <#vertex> <#edges> : the number of vertices and edges of the graph
-/// * e <vertex1> <vertex2> : an edge between vertex1 and vertex2
+/// * p <format> <#vertex> <#edges> : the format (edge or col), the number of vertices and edges of the graph
+/// * e <vertex1> <vertex2> : an edge between vertex1 and vertex2 (vertices are numbered from 1)
 /// * c <comment> : a comment
 ///
+/// Empty lines are ignored. The file is parsed in [`ParseMode::Strict`] mode, see [`parse_clq`]
+/// for the list of checks.
+///
 /// # Throws
-/// InvalidClqFileFormat if the file is not in the correct format.
+/// InvalidClqFileFormat if the file is not in the correct format. The error contains the line and
+/// the column of the faulty token when it can be located.
 ///
 /// # Test file
 /// ```text
@@ -174,56 +201,237 @@ pub fn complement(graph: &UnGraphMap<u64, ()>) -> UnGraphMap<u64, ()> {
 /// assert!(graph.contains_edge(4, 1));
 /// ```
 pub fn load_clq_file(path: &str) -> Result<UnGraphMap<u64, ()>, InvalidClqFileFormat> {
-    let file = match File::open(path) {
-        Ok(file) => file,
-        Err(e) => return Err(
+    let reader = open_clq_file(path)?;
+    let (graph, _) = parse_clq(reader, ParseMode::Strict)?;
+    Ok(graph)
+}
+
+/// Load a graph from a DIMACS .col file in [`ParseMode::Lenient`] mode.
+///
+/// Returns the graph along with the warnings raised while parsing the file.
+///
+/// # Throws
+/// InvalidClqFileFormat if the file cannot be parsed even in lenient mode.
+///
+/// # Example
+/// ```rust
+/// use vertex::graph_utils::load_clq_file_lenient;
+///
+/// let (graph, warnings) = load_clq_file_lenient("src/resources/tests/graphs/format_self_loop.clq").unwrap();
+/// assert_eq!(graph.edge_count(), 1);
+/// assert_eq!(warnings.len(), 2); // The self-loop and the wrong number of edges
+/// ```
+pub fn load_clq_file_lenient(path: &str) -> Result<(UnGraphMap<u64, ()>, Vec<ClqWarning>), InvalidClqFileFormat> {
+    let reader = open_clq_file(path)?;
+    parse_clq(reader, ParseMode::Lenient)
+}
+
+fn open_clq_file(path: &str) -> Result<BufReader<File>, InvalidClqFileFormat> {
+    match File::open(path) {
+        Ok(file) => Ok(BufReader::new(file)),
+        Err(e) => Err(
             InvalidClqFileFormat::new(&format!("File {:?} not found \n {:?}", path, e))
         ),
-    };
-    let reader = BufReader::new(file);
+    }
+}
 
+/// Parse a graph in the DIMACS format from a reader.
+///
+/// The following anomalies are always errors :
+/// * a line that does not start with `c`, `p` or `e`
+/// * a problem line (`p`) with a format other than edge/col, missing or invalid values, or defined twice
+/// * an edge defined before the problem line, with missing or invalid vertices
+/// * a vertex equal to 0 or greater than the order of the graph
+///
+/// The following anomalies are errors in [`ParseMode::Strict`] mode and warnings in [`ParseMode::Lenient`] mode :
+/// * a self-loop (the edge is ignored)
+/// * extra tokens at the end of an edge line (they are ignored)
+/// * a duplicate edge (the edge is merged)
+/// * a number of edges different from the one declared in the problem line
+///
+/// Since some instances list every edge in both directions, duplicate edges are accepted in strict mode
+/// when the declared size is equal to the number of distinct edges. Otherwise, the first duplicate is reported.
+///
+/// # Throws
+/// InvalidClqFileFormat with the position of the faulty token.
+///
+/// # Example
+/// ```rust
+/// use vertex::graph_utils::{parse_clq, ParseMode};
+///
+/// let content = "c Example\np edge 3 3\ne 1 2\ne 2 3\ne 3 3\n";
+///
+/// let err = parse_clq(content.as_bytes(), ParseMode::Strict).unwrap_err();
+/// assert_eq!((err.line, err.column), (Some(5), Some(5)));
+///
+/// let (graph, warnings) = parse_clq(content.as_bytes(), ParseMode::Lenient).unwrap();
+/// assert_eq!(graph.edge_count(), 2);
+/// assert_eq!(warnings.len(), 2);
+/// ```
+pub fn parse_clq<R: BufRead>(mut reader: R, mode: ParseMode)
+                             -> Result<(UnGraphMap<u64, ()>, Vec<ClqWarning>), InvalidClqFileFormat> {
     let mut g = UnGraphMap::<u64, ()>::new();
+    let mut warnings = Vec::new();
+    let mut order: Option<u64> = None;
     let mut exp_edges = 0;
+    // Line where each edge was first defined, used to report duplicate edges
+    let mut first_definition: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut first_duplicate: Option<InvalidClqFileFormat> = None;
+
+    let mut line = String::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        line_number += 1;
+        match reader.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => return Err(InvalidClqFileFormat::at(&e.to_string(), line_number, 1)),
+        }
+        let tokens = tokenize(&line);
+        let (_, symbol) = match tokens.first() {
+            Some(token) => *token,
+            None => continue, // Empty line
+        };
+        // Column just after the end of the line, used when a token is missing
+        let end_column = line.trim_end().len() + 1;
 
-    for line in reader.lines() {
-        let line = line?;
-        let values: Vec<&str> = line.split_whitespace().collect();
-
-        match values[0] {
+        match symbol {
             "c" => {
                 continue;
             }
             "p" => {
-                if values[1] != "edge" && values[1] != "col" {
-                    return Err(InvalidClqFileFormat::new("Expecting edge/col format"))
+                if order.is_some() {
+                    return Err(InvalidClqFileFormat::at("Problem line defined twice", line_number, 1));
+                }
+                match tokens.get(1) {
+                    Some((_, "edge")) | Some((_, "col")) => {}
+                    Some((column, _)) => {
+                        return Err(InvalidClqFileFormat::at("Expecting edge/col format", line_number, *column));
+                    }
+                    None => {
+                        return Err(InvalidClqFileFormat::at("Expecting edge/col format", line_number, end_column));
+                    }
+                }
+                let n = parse_token::<u64>(&tokens, 2, "order", line_number, end_column)?;
+                exp_edges = parse_token::<usize>(&tokens, 3, "number of edges", line_number, end_column)?;
+                if let Some((column, _)) = tokens.get(4) {
+                    return Err(InvalidClqFileFormat::at("Unexpected token after the number of edges",
+                                                        line_number, *column));
                 }
-                let order = values[2].parse::<u64>()?;
-                exp_edges = values[3].parse::<usize>()?;
-                for i in 0..order {
+                for i in 0..n {
                     g.add_node(i);
                 }
+                order = Some(n);
             }
             "e" => {
-                if g.node_count() == 0 {
-                    return Err(InvalidClqFileFormat::new("Expecting graph order"));
+                let n = match order {
+                    Some(n) if n > 0 => n,
+                    _ => return Err(InvalidClqFileFormat::at("Expecting graph order", line_number, 1)),
+                };
+                let i = parse_vertex(&tokens, 1, n, line_number, end_column)?;
+                let j = parse_vertex(&tokens, 2, n, line_number, end_column)?;
+
+                if let Some((column, _)) = tokens.get(3) {
+                    let message = "Unexpected token after the edge";
+                    match mode {
+                        ParseMode::Strict => return Err(InvalidClqFileFormat::at(message, line_number, *column)),
+                        ParseMode::Lenient => warnings.push(ClqWarning { line: line_number, message: message.to_string() }),
+                    }
                 }
-                let i = values[1].parse::<u64>()? - 1;
-                let j = values[2].parse::<u64>()? - 1;
 
-                g.add_edge(i, j, ());
+                if i == j {
+                    let message = format!("Self-loop on vertex {}", i + 1);
+                    match mode {
+                        ParseMode::Strict => return Err(InvalidClqFileFormat::at(&message, line_number, tokens[2].0)),
+                        ParseMode::Lenient => {
+                            warnings.push(ClqWarning { line: line_number, message });
+                            continue;
+                        }
+                    }
+                }
+
+                let key = (i.min(j), i.max(j));
+                match first_definition.get(&key) {
+                    Some(first_line) => {
+                        let message = format!("Duplicate edge ({}, {}) already defined at line {}",
+                                              i + 1, j + 1, first_line);
+                        match mode {
+                            ParseMode::Strict => {
+                                if first_duplicate.is_none() {
+                                    first_duplicate = Some(InvalidClqFileFormat::at(&message, line_number, 1));
+                                }
+                            }
+                            ParseMode::Lenient => warnings.push(ClqWarning { line: line_number, message }),
+                        }
+                    }
+                    None => {
+                        first_definition.insert(key, line_number);
+                        g.add_edge(i, j, ());
+                    }
+                }
             }
             _ => {
-                return Err(InvalidClqFileFormat::new(&format!("Invalid file format for line {:?}", line)));
+                return Err(InvalidClqFileFormat::at(&format!("Invalid file format for line {:?}", line.trim_end()),
+                                                    line_number, 1));
             }
         }
     }
-    if g.edge_count() != exp_edges {
-        return Err(InvalidClqFileFormat::new(&format!("Expecting {} edges but read {} edges", exp_edges, g.edge_count())));
-    }
+
     if g.node_count() == 0 {
         return Err(InvalidClqFileFormat::new("Expecting graph order"));
     }
-    Ok(g)
+    if g.edge_count() != exp_edges {
+        let message = format!("Expecting {} edges but read {} edges", exp_edges, g.edge_count());
+        match (mode, first_duplicate) {
+            // The duplicate explains why the number of edges is wrong
+            (ParseMode::Strict, Some(err)) => return Err(err),
+            (ParseMode::Strict, None) => return Err(InvalidClqFileFormat::new(&message)),
+            (ParseMode::Lenient, _) => warnings.push(ClqWarning { line: line_number - 1, message }),
+        }
+    }
+    Ok((g, warnings))
+}
+
+/// Splits a line into its tokens along with their column (starting at 1).
+pub(crate) fn tokenize(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices() {
+        if c.is_whitespace() {
+            if let Some(s) = start {
+                tokens.push((s + 1, &line[s..i]));
+                start = None;
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s + 1, &line[s..]));
+    }
+    tokens
+}
+
+fn parse_token<T: std::str::FromStr>(tokens: &[(usize, &str)], index: usize, name: &str, line: usize, end_column: usize)
+                                     -> Result<T, InvalidClqFileFormat> {
+    match tokens.get(index) {
+        Some((column, token)) => token.parse::<T>().map_err(|_| {
+            InvalidClqFileFormat::at(&format!("Invalid {} {:?}", name, token), line, *column)
+        }),
+        None => Err(InvalidClqFileFormat::at(&format!("Missing {}", name), line, end_column)),
+    }
+}
+
+/// Parses the vertex at the given index and converts it to the 0-based numbering.
+fn parse_vertex(tokens: &[(usize, &str)], index: usize, order: u64, line: usize, end_column: usize)
+                -> Result<u64, InvalidClqFileFormat> {
+    let vertex = parse_token::<u64>(tokens, index, "vertex", line, end_column)?;
+    if vertex == 0 || vertex > order {
+        return Err(InvalidClqFileFormat::at(&format!("Vertex {} out of range 1..={}", vertex, order),
+                                            line, tokens[index].0));
+    }
+    Ok(vertex - 1)
 }
 
 /// Returns the string of a given file in the DIMACS .clq format.
@@ -252,6 +460,143 @@ pub fn graph_to_string(graph: &UnGraphMap<u64, ()>) -> String {
     string
 }
 
+/// Kind of solution that can be highlighted when a graph is exported with [`graph_to_dot`] or
+/// [`graph_to_graphml`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// The highlighted vertices form a vertex cover of the graph.
+    VertexCover,
+    /// The highlighted vertices form a clique of the graph. The edges of the clique are highlighted too.
+    Clique,
+    /// The highlighted vertices form an independent set of the graph.
+    IndependentSet,
+}
+
+impl Highlight {
+    /// Name of the attribute used to mark the highlighted vertices in the exported file.
+    pub fn attribute_name(&self) -> &'static str {
+        match self {
+            Highlight::VertexCover => "vertex_cover",
+            Highlight::Clique => "clique",
+            Highlight::IndependentSet => "independent_set",
+        }
+    }
+
+    /// Colour used to fill the highlighted vertices in the DOT output.
+    fn color(&self) -> &'static str {
+        match self {
+            Highlight::VertexCover => "#e6550d",
+            Highlight::Clique => "#3182bd",
+            Highlight::IndependentSet => "#31a354",
+        }
+    }
+}
+
+/// Returns the string of a given graph in the Graphviz DOT format.
+///
+/// The vertices are numbered from 1 like in the DIMACS format (see [`graph_to_string`]).
+/// If a highlight is given, the vertices of the set are filled with a colour depending on the kind
+/// of set and get a `<kind>=true` attribute. When the set is a clique, its edges are coloured too.
+///
+/// # Example
+/// ```rust
+/// use petgraph::prelude::UnGraphMap;
+/// use vertex::graph_utils::{graph_to_dot, Highlight};
+///
+/// let mut graph = Box::new(UnGraphMap::<u64, ()>::new());
+/// for i in 0..3 {
+///     graph.add_node(i);
+/// }
+/// graph.add_edge(0, 1, ());
+/// graph.add_edge(1, 2, ());
+///
+/// let dot = graph_to_dot(&graph, None);
+/// assert_eq!(dot, "graph G {\n  1;\n  2;\n  3;\n  1 -- 2;\n  2 -- 3;\n}\n");
+///
+/// let dot = graph_to_dot(&graph, Some((Highlight::VertexCover, &[1])));
+/// assert!(dot.contains("2 [vertex_cover=true, style=filled, fillcolor=\"#e6550d\"];"));
+/// ```
+pub fn graph_to_dot(graph: &UnGraphMap<u64, ()>, highlight: Option<(Highlight, &[u64])>) -> String {
+    let mut string = String::new();
+    string.push_str("graph G {\n");
+    for node in graph.nodes() {
+        match highlight {
+            Some((kind, set)) if set.contains(&node) => {
+                string.push_str(&format!("  {} [{}=true, style=filled, fillcolor=\"{}\"];\n",
+                                         node + 1,
+                                         kind.attribute_name(),
+                                         kind.color()));
+            }
+            _ => string.push_str(&format!("  {};\n", node + 1)),
+        }
+    }
+    for (i, j, _) in graph.all_edges() {
+        match highlight {
+            Some((Highlight::Clique, set)) if set.contains(&i) && set.contains(&j) => {
+                string.push_str(&format!("  {} -- {} [color=\"{}\", penwidth=2];\n",
+                                         i + 1,
+                                         j + 1,
+                                         Highlight::Clique.color()));
+            }
+            _ => string.push_str(&format!("  {} -- {};\n", i + 1, j + 1)),
+        }
+    }
+    string.push_str("}\n");
+    string
+}
+
+/// Returns the string of a given graph in the GraphML format.
+///
+/// The vertices are numbered from 1 like in the DIMACS format (see [`graph_to_string`]).
+/// If a highlight is given, a boolean node attribute named after the kind of set
+/// (`vertex_cover`, `clique` or `independent_set`) is declared and set to true for the vertices of the set.
+///
+/// # Example
+/// ```rust
+/// use petgraph::prelude::UnGraphMap;
+/// use vertex::graph_utils::{graph_to_graphml, Highlight};
+///
+/// let mut graph = Box::new(UnGraphMap::<u64, ()>::new());
+/// for i in 0..3 {
+///     graph.add_node(i);
+/// }
+/// graph.add_edge(0, 1, ());
+/// graph.add_edge(1, 2, ());
+///
+/// let graphml = graph_to_graphml(&graph, Some((Highlight::Clique, &[0, 1])));
+/// assert!(graphml.contains("<key id=\"clique\" for=\"node\" attr.name=\"clique\" attr.type=\"boolean\">"));
+/// assert!(graphml.contains("<node id=\"n1\"><data key=\"clique\">true</data></node>"));
+/// assert!(graphml.contains("<node id=\"n3\"/>"));
+/// assert!(graphml.contains("<edge source=\"n1\" target=\"n2\"/>"));
+/// ```
+pub fn graph_to_graphml(graph: &UnGraphMap<u64, ()>, highlight: Option<(Highlight, &[u64])>) -> String {
+    let mut string = String::new();
+    string.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    string.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    if let Some((kind, _)) = highlight {
+        string.push_str(&format!("  <key id=\"{0}\" for=\"node\" attr.name=\"{0}\" attr.type=\"boolean\">\
+                                  <default>false</default></key>\n",
+                                 kind.attribute_name()));
+    }
+    string.push_str("  <graph id=\"G\" edgedefault=\"undirected\">\n");
+    for node in graph.nodes() {
+        match highlight {
+            Some((kind, set)) if set.contains(&node) => {
+                string.push_str(&format!("    <node id=\"n{}\"><data key=\"{}\">true</data></node>\n",
+                                         node + 1,
+                                         kind.attribute_name()));
+            }
+            _ => string.push_str(&format!("    <node id=\"n{}\"/>\n", node + 1)),
+        }
+    }
+    for (i, j, _) in graph.all_edges() {
+        string.push_str(&format!("    <edge source=\"n{}\" target=\"n{}\"/>\n", i + 1, j + 1));
+    }
+    string.push_str("  </graph>\n");
+    string.push_str("</graphml>\n");
+    string
+}
+
 /// Returns the vertex with the maximum degree in the graph and its degree.
 ///
 /// # Example
@@ -320,364 +665,192 @@ pub fn copy_graph(graph: &UnGraphMap<u64, ()>) -> UnGraphMap<u64, ()> {
     copy
 }
 
-/// Structure used to store the information of a graph such as its exact value of the MVC.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
-pub struct GraphInfo {
-    pub id: String,
-    format: String,
-    order: usize,
-    size: usize,
-    val: u64,
-}
-
-/// Structure used to store the information of a computation of the MVC for a given graph.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
-pub struct YamlTime {
-    date: String,
-    mvc_val: u64,
-    time: String,
-    is_time_limit: bool,
-    algorithm: String,
-    comment: String,
-}
-
-/// Add the graph id with its format in the yaml file located at src/resources/graph_data.yml.
+/// Structural statistics of a graph, used to describe the instances and to study which algorithm suits which graphs.
 ///
-/// The default value for mvc_val is 0, it has to be updated manually.
-/// If the graph id is already in the file, it is not added again.
+/// The bounds give an idea of the difficulty of the instance :
+/// * a proper colouring with k colours splits the vertices into k independent sets, so the maximum clique has at most
+///   `greedy_colours` vertices and the maximum independent set has at least order / `greedy_colours` vertices
+/// * each edge of a matching needs its own vertex in a vertex cover, so the minimum vertex cover has at least
+///   `max_matching` vertices (and at most 2 * `max_matching` vertices since a maximum matching is maximal)
 ///
-/// # Throws
-/// - YamlError::IoError if the file cannot be opened or written
-/// - YamlError::YAMLFormatError if the file is not in the correct format
-/// - YamlError::YamlParsingError if there is an error while parsing the file
-pub fn add_graph_to_yaml(id: &str, format: &str, graph: &UnGraphMap<u64, ()>, path: &str)
-                         -> Result<(), YamlError> {
-    let file = match File::open(path) {
-        Ok(file) => file,
-        Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
-    };
-    let mut data: Vec<GraphInfo> = serde_yaml::from_reader(file)?;
-
-    if data.iter().any(|x| x.id == id) {
-        // If the graph is already in the file, we don't add it again
-        return Ok(());
-    }
-
-    let info = GraphInfo {
-        id: id.to_string(),
-        format: format.to_string(),
-        order: graph.node_count(),
-        size: graph.edge_count(),
-        val: 0,
-    };
-    data.push(info);
-
-    // Update the file
-    let mut file = File::create(path)?;
-    let tmp = serde_yaml::to_string(&data)?;
-    file.write_all(tmp.as_bytes())?;
-
-    // When we add a graph to the yaml file, we also add it to the time file
-    add_graph_to_time_file(id)
-}
-
-fn add_graph_to_time_file(id: &str) -> Result<(), YamlError> {
-    let time_path = "src/resources/time_result.yml";
-    let mut file = match File::open(time_path) {
-        Ok(file) => file,
-        Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", time_path), e))
-    };
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-
-    let time: Value = serde_yaml::from_str(&contents)?;
-    let mut map = match time.as_mapping() {
-        Some(x) => x.clone(),
-        None => return Err(YamlError::YAMLFormatError("File badly formatted".to_string(),
-                                                      serde_yaml::Error::custom("The content of the file should be a map"))),
-    };
-
-    let vec: Sequence = Vec::new();
-
-    map.insert(Value::String(id.to_string()), Value::Sequence(vec));
-
-    let mut file = File::create(time_path)?;
-    serde_yaml::to_writer(&mut file, &map)?;
-    Ok(())
-}
-
-/// Update the known value of the minimum vertex cover for a given graph id.
-///
-/// # Parameters
-/// - id : the id of the graph (ex: test.clq)
-/// - mvc_val : the new value of the minimum vertex cover
-/// - path : the path to the yaml file containing the graph info (optional-> None or Some(path))
-///
-/// # Throws
-/// - YamlError::IoError if the file cannot be opened or written
-/// - YamlError::YAMLParsingError if there is an error while parsing the file
-/// - YamlError::NotFound if the graph id is not in the file
-///
-/// # example
-/// ```
-/// use vertex::errors::YamlError;
-/// use vertex::graph_utils::update_mvc_value;
-///
-/// let res = match update_mvc_value("test.clq", 2, Some("src/resources/graph_data.yml")) {
-///     Ok(_) => {println!("The value of the minimum vertex cover for the test.clq graph is now 2");},
-///     Err(e) => {println!("Error: {:?}", e);},
-/// };
+/// # Example
+/// ```rust
+/// use vertex::graph_utils::{GraphStats, load_clq_file};
 ///
-/// update_mvc_value("test.clq", 3, None).expect("Error while updating the value of the minimum vertex cover");
-/// // The value of the minimum vertex cover for the test.clq graph is now 3
+/// let graph = load_clq_file("src/resources/graphs/test.clq").unwrap();
+/// let stats = GraphStats::new(&graph);
+/// assert_eq!((stats.order, stats.size), (graph.node_count(), graph.edge_count()));
+/// assert_eq!(stats.triangles, 2);
+/// assert!(stats.max_matching <= 3); // The minimum vertex cover of test.clq has 3 vertices
 /// ```
-pub fn update_mvc_value(id: &str, mvc_val: u64, path: Option<&str>) -> Result<(), YamlError> {
-    // TODO : this function has to be deleted later on
-    let path = path.unwrap_or("src/resources/graph_data.yml");
-    let file = match File::open(path) {
-        Ok(file) => file,
-        Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
-    };
-
-    let mut data: Vec<GraphInfo> = serde_yaml::from_reader(file)?;
-
-    let mut found = false;
-    for info in data.iter_mut() {
-        if info.id == id {
-            info.val = mvc_val;
-            found = true;
-            break;
-        }
-    }
-    if !found {
-        return Err(YamlError::NotFound(
-            format!("Graph {:?} not found in the YAML file", id),
-            format!("Graph {:?} not found in {:?} to store the mvc : {:?}", id, path, mvc_val)));
-    }
-
-    // Update the file
-    let mut file = File::create(path)?;
-    let tmp = serde_yaml::to_string(&data)?;
-    file.write_all(tmp.as_bytes())?;
-    Ok(())
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphStats {
+    pub order: usize,
+    pub size: usize,
+    pub density: f64,
+    pub min_degree: usize,
+    pub max_degree: usize,
+    pub mean_degree: f64,
+    pub degree_variance: f64,
+    /// Largest minimum degree of a subgraph (the vertices can be removed in an order where each one has at most
+    /// `degeneracy` neighbours left)
+    pub degeneracy: usize,
+    pub triangles: u64,
+    /// Global clustering coefficient : 3 * triangles / number of paths of length 2
+    pub clustering: f64,
+    /// Number of connected components
+    pub components: usize,
+    pub bipartite: bool,
+    /// Number of colours of a greedy colouring in the smallest-last order (at most `degeneracy` + 1)
+    pub greedy_colours: usize,
+    /// Size of a maximum matching
+    pub max_matching: usize,
 }
 
-/// Check if a given value is the optimal value for a given graph id. If the graph id is not in the file, return None.
-///
-/// The optimal value is the value stored in the yaml file. So, if the value in the yaml file is wrong, this function will return the wrong result.
-///
-/// # Parameters
-/// - id : the id of the graph (ex: test.clq)
-/// - val : the value to check
-/// - path : the path to the yaml file containing the graph info (optional-> None or Some(path))
-///
-/// # Throws
-/// - YamlError::IoError if the file cannot be opened or is not found
-/// - YamlError::YAMLParsingError if there is an error while parsing the file
-///
-/// # example
-/// ```
-/// use vertex::graph_utils::is_optimal_value;
-///
-/// let true_res = is_optimal_value("test.clq", 3, Some("src/resources/graph_data.yml")).unwrap_or_else(|e| {
-///    panic!("Error while checking if value is optimal : {}", e);
-/// });
-/// assert!(true_res.unwrap());
-///
-/// let false_res = is_optimal_value("test.clq", 2, Some("src/resources/graph_data.yml")).unwrap_or_else(|e| {
-/// panic!("Error while checking if value is optimal : {}", e);
-/// });
-/// assert!(!false_res.unwrap());
-/// ```
-pub fn is_optimal_value(id: &str, val: u64, path: Option<&str>) -> Result<Option<bool>, YamlError> {
-    let path = path.unwrap_or("src/resources/graph_data.yml");
-    let file = match File::open(path) {
-        Ok(file) => file,
-        Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
-    };
-
-    let data: Vec<GraphInfo> = serde_yaml::from_reader(file)?;
+impl GraphStats {
+    /// Computes the statistics of a graph.
+    pub fn new(graph: &UnGraphMap<u64, ()>) -> GraphStats {
+        let order = graph.node_count();
+        let degrees: Vec<usize> = graph.nodes().map(|v| graph.neighbors(v).count()).collect();
+        let mean_degree = if order == 0 { 0.0 } else { degrees.iter().sum::<usize>() as f64 / order as f64 };
+        let degree_variance = if order == 0 {
+            0.0
+        } else {
+            degrees.iter().map(|&d| (d as f64 - mean_degree).powi(2)).sum::<f64>() / order as f64
+        };
 
-    for info in data.iter() {
-        if info.id == id {
-            return if info.val == val {
-                Ok(Some(true))
-            } else {
-                Ok(Some(false))
-            };
+        let triangles = count_triangles(graph);
+        let paths: u64 = degrees.iter().map(|&d| (d * d.saturating_sub(1) / 2) as u64).sum();
+        let (components, bipartite) = components_and_bipartiteness(graph);
+        let (degeneracy, ordering) = smallest_last_ordering(graph);
+
+        GraphStats {
+            order,
+            size: graph.edge_count(),
+            density: if order < 2 { 0.0 } else { (2 * graph.edge_count()) as f64 / (order * (order - 1)) as f64 },
+            min_degree: degrees.iter().copied().min().unwrap_or(0),
+            max_degree: degrees.iter().copied().max().unwrap_or(0),
+            mean_degree,
+            degree_variance,
+            degeneracy,
+            triangles,
+            clustering: if paths == 0 { 0.0 } else { 3.0 * triangles as f64 / paths as f64 },
+            components,
+            bipartite,
+            greedy_colours: greedy_colouring(graph, &ordering),
+            max_matching: petgraph::algo::maximum_matching(graph).len(),
         }
     }
-    Ok(None)
 }
 
-/// Get the optimal value for a given graph id.
-/// The optimal value is the value stored in the yaml file. So, if the value in the yaml file is wrong,
-/// this function will return the wrong result.
-///
-/// # Parameters
-/// - id : the id of the graph (ex: test.clq)
-/// - path : the path to the yaml file containing the graph info (optional-> None or Some(path))
-///
-/// # Throws
-/// - YamlError::IoError if the file cannot be opened or is not found
-/// - YamlError::YAMLParsingError if there is an error while parsing the file
-///
-/// # example
-/// ```
-/// use vertex::graph_utils::get_optimal_value;
-///
-/// let res = get_optimal_value("test.clq", Some("src/resources/graph_data.yml")).unwrap_or_else(|e| {
-///   panic!("Error while getting the optimal value : {}", e);
-/// });
-/// assert_eq!(res, Some(3));
-///
-/// let none_res = get_optimal_value("unknown_graph.clq", Some("src/resources/graph_data.yml")).unwrap_or_else(|e| {
-///  panic!("Error while getting the optimal value : {}", e);
-/// });
-/// assert_eq!(none_res, None);
-///
-/// ```
-pub fn get_optimal_value(id: &str, path: Option<&str>) -> Result<Option<u64>, YamlError> {
-    let path = path.unwrap_or("src/resources/graph_data.yml");
-    let file = match File::open(path) {
-        Ok(file) => file,
-        Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
-    };
-
-    let data: Vec<GraphInfo> = serde_yaml::from_reader(file)?;
+impl std::fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Order = {}, size = {}, density = {:.4}, degree = {}..{} (mean {:.2}, variance {:.2}), \
+                   degeneracy = {}, triangles = {}, clustering = {:.4}, components = {}, bipartite = {}, \
+                   greedy colours = {}, maximum matching = {}",
+               self.order, self.size, self.density, self.min_degree, self.max_degree, self.mean_degree,
+               self.degree_variance, self.degeneracy, self.triangles, self.clustering, self.components,
+               self.bipartite, self.greedy_colours, self.max_matching)
+    }
+}
 
-    for info in data.iter() {
-        if info.id == id {
-            return Ok(Some(info.val));
-        }
+/// Counts the triangles of a graph : each triangle is counted once, from its edge between the two smallest vertices.
+fn count_triangles(graph: &UnGraphMap<u64, ()>) -> u64 {
+    let mut triangles = 0;
+    for (u, v, _) in graph.all_edges() {
+        let (u, v) = (u.min(v), u.max(v));
+        triangles += graph.neighbors(u).filter(|&w| w > v && graph.contains_edge(v, w)).count() as u64;
     }
-    Ok(None)
+    triangles
 }
 
-/// Adds a new time for the given graph to the yaml file located at src/resources/time_result.yml.
-///
-/// # Parameters
-/// - id : the id of the graph (ex: test.clq)
-/// - mvc_val : the value of the minimum vertex cover
-/// - time : the time taken to compute the mvc
-/// - is_time_limit : true if the time limit was reached, false otherwise
-/// - algorithm : the algorithm used to compute the mvc
-/// - comment : a comment about the computation
-///
-/// # Throws
-/// - YamlError::IoError if the file cannot be opened, is not found or cannot be written
-/// - YamlError::YAMLParsingError if there is an error while parsing the file
-/// - YamlError::NotFound if the graph id is not in the file
-/// - YamlError::YAMLFormatError if the file is not in the correct format (should be a map / content of a graph should be a vector)
-///
-pub fn add_time_to_yaml(id: &str, mvc_val: u64, time: ElapseTime, is_time_limit: bool, algorithm: &str, comment: &str)
-                        -> Result<(), YamlError> {
-    let path = "src/resources/time_result.yml";
-    let mut file = match File::open(path) {
-        Ok(file) => file,
-        Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
-    };
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
-
-    let content: Value = serde_yaml::from_str(&content)?;
-    let mut map = match content.as_mapping() {
-        Some(x) => x.clone(),
-        None => return Err(YamlError::YAMLFormatError("File badly formatted".to_string(),
-                                                      serde_yaml::Error::custom("The content of the file should be a map"))),
-    };
-
-    let graph = match map.get(id) {
-        Some(graph) => graph.clone(),
-        None => return Err(YamlError::NotFound(
-            format!("Graph {:?} not found in the YAML file", id),
-            format!("Graph {:?} not found in {:?} to store the mvc : {:?}", id, path, mvc_val))),
-    };
-
-    let mut graph_data = get_graph_data(graph)?;
-
-    let new_time = YamlTime {
-        date: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-        mvc_val,
-        time: time.to_string(),
-        is_time_limit,
-        algorithm: algorithm.to_string(),
-        comment: comment.to_string(),
-    };
-
-    let as_value = serde_yaml::to_value(new_time)?;
-    graph_data.push(as_value);
-
-    map.insert(Value::String(id.to_string()), Value::Sequence(graph_data));
-
-
-    // Update the file
-    let mut file = File::create(path)?;
-    serde_yaml::to_writer(&mut file, &map)?;
-    Ok(())
+/// Returns the number of connected components of a graph and whether it is bipartite (breadth-first 2-colouring).
+fn components_and_bipartiteness(graph: &UnGraphMap<u64, ()>) -> (usize, bool) {
+    let mut side: HashMap<u64, bool> = HashMap::with_capacity(graph.node_count());
+    let mut components = 0;
+    let mut bipartite = true;
+    for start in graph.nodes() {
+        if side.contains_key(&start) {
+            continue;
+        }
+        components += 1;
+        side.insert(start, false);
+        let mut queue = std::collections::VecDeque::from([start]);
+        while let Some(u) = queue.pop_front() {
+            let s = side[&u];
+            for v in graph.neighbors(u) {
+                match side.get(&v) {
+                    Some(&t) => bipartite &= t != s,
+                    None => {
+                        side.insert(v, !s);
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+    }
+    (components, bipartite)
 }
 
-/// Get all the times for a given graph id.
-///
-/// # Parameters
-/// - id : the id of the graph (ex: test.clq)
+/// Returns the degeneracy of a graph and its vertices in the smallest-last order : the vertices are removed one by one
+/// with the minimum degree in the remaining graph, and the order is the reverse of the removal order.
 ///
-/// # Throws
-/// - YamlError::IoError if the file cannot be opened or is not found
-/// - YamlError::YAMLParsingError if there is an error while parsing the file
-/// - YamlError::NotFound if the graph id is not in the file
-/// - YamlError::YAMLFormatError if the file is not in the correct format.
-///
-pub fn get_time_data(id: &str) -> Result<Vec<YamlTime>, YamlError> {
-    let path = "src/resources/time_result.yml";
-    let mut file = match File::open(path) {
-        Ok(file) => file,
-        Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
-    };
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
-
-    let content: Value = serde_yaml::from_str(&content)?;
-    let map = match content.as_mapping() {
-        Some(x) => x.clone(),
-        None => return Err(YamlError::YAMLFormatError("File badly formatted".to_string(),
-                                                      serde_yaml::Error::custom("The content of the file should be a map"))),
-    };
-
-    let graph = match map.get(id) {
-        Some(graph) => graph.clone(),
-        None => return Err(YamlError::NotFound(
-            format!("Graph {:?} not found in the YAML file", id),
-            format!("Graph {:?} not found in {:?} to store the time", id, path))),
-    };
-
-    let graph_data = get_graph_data(graph)?;
-    let mut res: Vec<YamlTime> = Vec::new();
-
-    for time in graph_data.iter() {
-        let time: YamlTime = match serde_yaml::from_value(time.clone()) {
-            Ok(x) => x,
-            Err(e) => return Err(YamlError::YAMLFormatError(
-                "File badly formatted".to_string(), serde_yaml::Error::custom(format!("the content of the graph should be a vector: {:?}", e))))
+/// The ties are broken with the labels of the vertices, so the order does not depend on the insertion order.
+fn smallest_last_ordering(graph: &UnGraphMap<u64, ()>) -> (usize, Vec<u64>) {
+    let mut nodes: Vec<u64> = graph.nodes().collect();
+    nodes.sort_unstable();
+    let adjacency: HashMap<u64, Vec<u64>> = nodes.iter()
+        .map(|&v| (v, graph.neighbors(v).sorted_unstable().collect()))
+        .collect();
+    let mut degree: HashMap<u64, usize> = nodes.iter().map(|&v| (v, adjacency[&v].len())).collect();
+    let max_degree = degree.values().copied().max().unwrap_or(0);
+    // Buckets of vertices by degree, the stale entries are skipped
+    let mut buckets: Vec<Vec<u64>> = vec![Vec::new(); max_degree + 1];
+    for &v in nodes.iter().rev() {
+        buckets[degree[&v]].push(v);
+    }
+    let mut removed: HashMap<u64, bool> = HashMap::with_capacity(nodes.len());
+    let mut removal = Vec::with_capacity(nodes.len());
+    let (mut degeneracy, mut current) = (0, 0);
+    while removal.len() < nodes.len() {
+        let Some(v) = buckets[current].pop() else {
+            current += 1;
+            continue;
         };
-        res.push(time);
+        if removed.contains_key(&v) || degree[&v] != current {
+            continue;
+        }
+        degeneracy = degeneracy.max(current);
+        removed.insert(v, true);
+        removal.push(v);
+        for &u in &adjacency[&v] {
+            if !removed.contains_key(&u) {
+                let d = degree.get_mut(&u).unwrap();
+                *d -= 1;
+                buckets[*d].push(u);
+                current = current.min(*d);
+            }
+        }
     }
-    Ok(res)
+    removal.reverse();
+    (degeneracy, removal)
 }
 
-fn get_graph_data(id: Value) -> Result<Sequence, YamlError> {
-    let res = match serde_yaml::from_value(id) {
-        Ok(x) => x,
-        Err(e) => return Err(YamlError::YAMLFormatError(
-            "File badly formatted".to_string(), serde_yaml::Error::custom(format!("the content of the graph should be a vector: {:?}", e))))
-    };
-    Ok(res)
+/// Colours the vertices greedily in the given order and returns the number of colours.
+fn greedy_colouring(graph: &UnGraphMap<u64, ()>, ordering: &[u64]) -> usize {
+    let mut colour: HashMap<u64, usize> = HashMap::with_capacity(ordering.len());
+    let mut colours = 0;
+    for &v in ordering {
+        let used: std::collections::HashSet<usize> = graph.neighbors(v).filter_map(|u| colour.get(&u).copied()).collect();
+        let c = (0..).find(|c| !used.contains(c)).unwrap();
+        colour.insert(v, c);
+        colours = colours.max(c + 1);
+    }
+    colours
 }
 
 #[cfg(test)]
 mod graph_utils_tests {
-    use std::time::Duration;
-
     use super::*;
 
     #[test]
@@ -698,6 +871,40 @@ mod graph_utils_tests {
         assert!(is_vertex_cover(&graph, &vertex_cover));
     }
 
+    #[test]
+    fn test_graph_stats() {
+        // Two components : an even cycle and a triangle with a pendant vertex
+        let graph = UnGraphMap::<u64, ()>::from_edges([(0, 1), (1, 2), (2, 3), (3, 0), (4, 5), (5, 6), (6, 4), (6, 7)]);
+        let stats = GraphStats::new(&graph);
+        assert_eq!((stats.order, stats.size), (8, 8));
+        assert_eq!((stats.min_degree, stats.max_degree, stats.mean_degree), (1, 3, 2.0));
+        assert_eq!(stats.degree_variance, 0.25);
+        assert_eq!((stats.degeneracy, stats.triangles, stats.components), (2, 1, 2));
+        // 1 triangle and 4 + 1 + 1 + 3 = 9 paths of length 2
+        assert!((stats.clustering - 1.0 / 3.0).abs() < 1e-12);
+        assert!(!stats.bipartite);
+        assert_eq!((stats.greedy_colours, stats.max_matching), (3, 4));
+
+        let cycle = UnGraphMap::<u64, ()>::from_edges([(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert!(GraphStats::new(&cycle).bipartite);
+
+        let mut clique = UnGraphMap::<u64, ()>::new();
+        for u in 0..6 {
+            for v in u + 1..6 {
+                clique.add_edge(u, v, ());
+            }
+        }
+        let stats = GraphStats::new(&clique);
+        assert_eq!((stats.degeneracy, stats.greedy_colours, stats.max_matching, stats.triangles), (5, 6, 3, 20));
+        assert_eq!((stats.density, stats.clustering), (1.0, 1.0));
+
+        let empty = GraphStats::new(&UnGraphMap::<u64, ()>::new());
+        assert_eq!((empty.order, empty.components, empty.greedy_colours, empty.density), (0, 0, 0, 0.0));
+
+        let yaml = serde_yaml::to_string(&stats).unwrap();
+        assert_eq!(serde_yaml::from_str::<GraphStats>(&yaml).unwrap(), stats);
+    }
+
     #[test]
     fn test_is_clique() {
         let mut graph = Box::new(UnGraphMap::<u64, ()>::new());
@@ -868,95 +1075,184 @@ mod graph_utils_tests {
     }
 
     #[test]
-    fn test_graph_to_string() {
-        let mut graph = Box::new(UnGraphMap::<u64, ()>::new());
-        for i in 0..4 {
-            graph.add_node(i);
-        }
-        graph.add_edge(0, 1, ());
-        graph.add_edge(1, 2, ());
+    fn test_load_clq_error_position() {
+        let filename = "src/resources/tests/graphs/format_symbol.clq";
+        let err = load_clq_file(filename).unwrap_err();
+        assert_eq!(err.line, Some(4));
+        assert_eq!(err.column, Some(1));
+        assert_eq!(err.to_string(), "line 4, column 1: Invalid file format for line \"a 1 2\"");
+    }
 
-        let string = graph_to_string(&graph);
-        assert_eq!(string, "p edge 4 2\ne 1 2\ne 2 3\n");
+    #[test]
+    fn test_load_clq_throw_when_vertex_zero() {
+        let filename = "src/resources/tests/graphs/format_vertex_zero.clq";
+        let err = load_clq_file(filename).unwrap_err();
+        assert_eq!(err.message, "Vertex 0 out of range 1..=2");
+        assert_eq!((err.line, err.column), (Some(4), Some(3)));
     }
 
-    // ========== ADD GRAPH TO YAML ==========
     #[test]
-    fn test_add_graph_to_yaml_file_not_found() {
-        let result = add_graph_to_yaml("test.clq",
-                                       "edge",
-                                       &UnGraphMap::<u64, ()>::new(),
-                                       "unknown_file.yml");
-        assert!(result.is_err());
-        // Check error types is IoError
-        assert!(matches!(result.unwrap_err(), YamlError::IoError(_, _)));
+    fn test_load_clq_throw_when_vertex_out_of_range() {
+        let filename = "src/resources/tests/graphs/format_vertex_range.clq";
+        let err = load_clq_file(filename).unwrap_err();
+        assert_eq!(err.message, "Vertex 3 out of range 1..=2");
+        assert_eq!((err.line, err.column), (Some(4), Some(5)));
     }
 
-    // ======= UPDATE MVC VALUE =========
     #[test]
-    fn test_update_mvc_value_file_not_found() {
-        let result = update_mvc_value("test.clq", 2, Some("unknown_file.yml"));
-        assert!(result.is_err());
-        // Check error types is IoError
-        assert!(matches!(result.unwrap_err(), YamlError::IoError(_, _)));
+    fn test_load_clq_throw_when_duplicate_edge() {
+        let filename = "src/resources/tests/graphs/format_duplicate.clq";
+        let err = load_clq_file(filename).unwrap_err();
+        assert_eq!(err.message, "Duplicate edge (2, 1) already defined at line 4");
+        assert_eq!(err.line, Some(6));
+
+        let (graph, warnings) = load_clq_file_lenient(filename).unwrap();
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(warnings.len(), 2); // The duplicate edge and the wrong number of edges
+        assert_eq!(warnings[0].line, 6);
     }
 
     #[test]
-    fn test_update_mvc_value_graph_not_found() {
-        let result = update_mvc_value("unknown_graph.clq", 2, None);
-        assert!(result.is_err());
-        // Check error types is NotFound
-        assert!(matches!(result.unwrap_err(), YamlError::NotFound(_, _)));
+    fn test_load_clq_throw_when_self_loop() {
+        let filename = "src/resources/tests/graphs/format_self_loop.clq";
+        let err = load_clq_file(filename).unwrap_err();
+        assert_eq!(err.message, "Self-loop on vertex 2");
+        assert_eq!((err.line, err.column), (Some(5), Some(5)));
     }
 
-    // ======= IS OPTIMAL VALUE =========
     #[test]
-    fn test_is_optimal_value_file_not_found() {
-        let result = is_optimal_value("test.clq", 3, Some("unknown_file.yml"));
-        assert!(result.is_err());
-        // Check error types is IoError
-        assert!(matches!(result.unwrap_err(), YamlError::IoError(_, _)));
+    fn test_load_clq_ignore_empty_lines() {
+        let filename = "src/resources/tests/graphs/format_empty_line.clq";
+        let graph = load_clq_file(filename).unwrap();
+        assert_eq!(graph.node_count(), 2);
+        assert!(graph.contains_edge(0, 1));
     }
 
     #[test]
-    fn test_is_optimal_value() {
-        let result = is_optimal_value("test.clq", 3, Some("src/resources/graph_data.yml"));
-        assert!(result.is_ok());
-        assert!(result.unwrap().unwrap());
+    fn test_load_clq_accept_edges_in_both_directions() {
+        // queen5_5 lists every edge twice and declares the number of distinct edges
+        let graph = load_clq_file("src/resources/graphs/queen5_5.clq").unwrap();
+        assert_eq!(graph.node_count(), 25);
+        assert_eq!(graph.edge_count(), 160);
     }
 
-    // ======= GET OPTIMAL VALUE =========
     #[test]
-    fn test_get_optimal_value_file_not_found() {
-        let result = get_optimal_value("test.clq", Some("unknown_file.yml"));
-        assert!(result.is_err());
-        // Check error types is IoError
-        assert!(matches!(result.unwrap_err(), YamlError::IoError(_, _)));
+    fn test_parse_clq_malformed_lines() {
+        let cases = [
+            ("p edge 2", "Missing number of edges", 1, 9),
+            ("p edge x 1", "Invalid order \"x\"", 1, 8),
+            ("p edge 2 1 3", "Unexpected token after the number of edges", 1, 12),
+            ("p", "Expecting edge/col format", 1, 2),
+            ("p edge 2 1\np edge 2 1", "Problem line defined twice", 2, 1),
+            ("p edge 2 1\ne 1", "Missing vertex", 2, 4),
+            ("p edge 2 1\ne 1 -2", "Invalid vertex \"-2\"", 2, 5),
+            ("p edge 2 1\ne 1 2 3", "Unexpected token after the edge", 2, 7),
+            ("p edge 0 0\ne 1 2", "Expecting graph order", 2, 1),
+        ];
+        for (content, message, line, column) in cases {
+            let err = parse_clq(content.as_bytes(), ParseMode::Strict).unwrap_err();
+            assert_eq!(err.message, message, "for {:?}", content);
+            assert_eq!((err.line, err.column), (Some(line), Some(column)), "for {:?}", content);
+        }
+
+        let err = parse_clq("c only comments\n\n".as_bytes(), ParseMode::Lenient).unwrap_err();
+        assert_eq!(err.message, "Expecting graph order");
     }
 
     #[test]
-    fn test_get_optimal_value() {
-        let result = get_optimal_value("test.clq", Some("src/resources/graph_data.yml"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().unwrap(), 3);
+    fn test_parse_clq_lenient_size_mismatch() {
+        let (graph, warnings) = parse_clq("p edge 3 1\ne 1 2\ne 2 3 7\n".as_bytes(), ParseMode::Lenient).unwrap();
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(warnings, vec![
+            ClqWarning { line: 3, message: "Unexpected token after the edge".to_string() },
+            ClqWarning { line: 3, message: "Expecting 1 edges but read 2 edges".to_string() },
+        ]);
     }
 
+    #[test]
+    fn test_parse_clq_random_mutations() {
+        // Applies random mutations to a valid file and checks that the parser never panics and
+        // that every graph it accepts is consistent with its problem line.
+        let original = std::fs::read("src/resources/graphs/test.clq").unwrap();
+        let alphabet = b"cpe0123456789 \n\r\t-+xedgecol";
+        let mut seed: u64 = 0x2545F4914F6CDD1D;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        for _ in 0..2000 {
+            let mut content = original.clone();
+            for _ in 0..(next() % 4 + 1) {
+                let pos = (next() % (content.len() as u64 + 1)) as usize;
+                match next() % 3 {
+                    0 if pos < content.len() => { content.remove(pos); }
+                    1 if pos < content.len() => content[pos] = alphabet[(next() % alphabet.len() as u64) as usize],
+                    _ => content.insert(pos, alphabet[(next() % alphabet.len() as u64) as usize]),
+                }
+            }
+            for mode in [ParseMode::Strict, ParseMode::Lenient] {
+                if let Ok((graph, _)) = parse_clq(content.as_slice(), mode) {
+                    let order = graph.node_count() as u64;
+                    for (i, j, _) in graph.all_edges() {
+                        assert!(i < order && j < order && i != j);
+                    }
+                }
+            }
+        }
+    }
 
-    // ======= ADD TIME TO YAML =========
     #[test]
-    fn test_add_time_to_yaml_graph_not_found() {
-        let result = add_time_to_yaml("unknown_graph.clq", 2, ElapseTime::new(Duration::new(0, 0)), false, "algo", "comment");
-        assert!(result.is_err());
-        // Check error types is NotFound
-        assert!(matches!(result.unwrap_err(), YamlError::NotFound(_, _)));
+    fn test_graph_to_string() {
+        let mut graph = Box::new(UnGraphMap::<u64, ()>::new());
+        for i in 0..4 {
+            graph.add_node(i);
+        }
+        graph.add_edge(0, 1, ());
+        graph.add_edge(1, 2, ());
+
+        let string = graph_to_string(&graph);
+        assert_eq!(string, "p edge 4 2\ne 1 2\ne 2 3\n");
     }
 
-    // ======= GET TIME DATA =========
     #[test]
-    fn test_get_time_data_graph_not_found() {
-        let result = get_time_data("unknown_graph.clq");
-        assert!(result.is_err());
-        // Check error types is NotFound
-        assert!(matches!(result.unwrap_err(), YamlError::NotFound(_, _)));
+    fn test_graph_to_dot_clique() {
+        let mut graph = Box::new(UnGraphMap::<u64, ()>::new());
+        for i in 0..4 {
+            graph.add_node(i);
+        }
+        graph.add_edge(0, 1, ());
+        graph.add_edge(1, 2, ());
+        graph.add_edge(2, 0, ());
+        graph.add_edge(2, 3, ());
+
+        let dot = graph_to_dot(&graph, Some((Highlight::Clique, &[0, 1, 2])));
+        assert!(dot.starts_with("graph G {\n"));
+        assert!(dot.contains("  1 [clique=true, style=filled, fillcolor=\"#3182bd\"];\n"));
+        assert!(dot.contains("  4;\n"));
+        assert!(dot.contains("  1 -- 2 [color=\"#3182bd\", penwidth=2];\n"));
+        assert!(dot.contains("  3 -- 4;\n"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_graph_to_graphml() {
+        let mut graph = Box::new(UnGraphMap::<u64, ()>::new());
+        for i in 0..3 {
+            graph.add_node(i);
+        }
+        graph.add_edge(0, 1, ());
+        graph.add_edge(1, 2, ());
+
+        let graphml = graph_to_graphml(&graph, None);
+        assert!(!graphml.contains("<key"));
+        assert_eq!(graphml.matches("<node ").count(), 3);
+        assert_eq!(graphml.matches("<edge ").count(), 2);
+
+        let graphml = graph_to_graphml(&graph, Some((Highlight::IndependentSet, &[0, 2])));
+        assert!(graphml.contains("attr.name=\"independent_set\""));
+        assert!(graphml.contains("<node id=\"n3\"><data key=\"independent_set\">true</data></node>"));
+        assert!(graphml.contains("<node id=\"n2\"/>"));
+    }
+}
@@ -0,0 +1,272 @@
+//! Graphs whose vertices are identified by arbitrary labels.
+//!
+//! The solvers work on graphs whose vertices are numbered from 0 to n - 1. A [`LabelledGraph`] maps
+//! external labels (strings, sparse integers, ...) to these dense indices and translates the solutions
+//! back into the original labels.
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufRead, BufReader};
+
+use petgraph::prelude::UnGraphMap;
+
+use crate::{Algorithm, Clock};
+use crate::errors::MvcError;
+use crate::errors::InvalidClqFileFormat;
+use crate::graph_utils::tokenize;
+
+/// Undirected graph whose vertices are identified by labels of type `L`.
+///
+/// Each label is associated to a dense index (in insertion order) which is the vertex used in the
+/// underlying graph given to the solvers.
+///
+/// # Example
+/// ```rust
+/// use vertex::{branch_and_bound, Clock};
+/// use vertex::labelled::LabelledGraph;
+///
+/// let mut graph = LabelledGraph::new();
+/// graph.add_edge("paris", "lyon");
+/// graph.add_edge("lyon", "nice");
+/// graph.add_edge("nice", "paris");
+/// graph.add_edge("nice", "lille");
+///
+/// assert_eq!(graph.index_of(&"lyon"), Some(1));
+/// assert_eq!(graph.label_of(3), Some(&"lille"));
+///
+/// let (value, cover) = graph.solve(&branch_and_bound, &mut Clock::new(3600)).unwrap();
+/// assert_eq!(value, 2);
+/// assert!(cover.contains(&"nice"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LabelledGraph<L> {
+    graph: UnGraphMap<u64, ()>,
+    labels: Vec<L>,
+    indices: HashMap<L, u64>,
+}
+
+impl<L: Clone + Eq + Hash> Default for LabelledGraph<L> {
+    fn default() -> Self {
+        LabelledGraph {
+            graph: UnGraphMap::new(),
+            labels: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+}
+
+impl<L: Clone + Eq + Hash> LabelledGraph<L> {
+    /// Create an empty graph
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a vertex to the graph and returns its index. If the label is already in the graph,
+    /// the index of the existing vertex is returned.
+    pub fn add_vertex(&mut self, label: L) -> u64 {
+        if let Some(&index) = self.indices.get(&label) {
+            return index;
+        }
+        let index = self.labels.len() as u64;
+        self.labels.push(label.clone());
+        self.indices.insert(label, index);
+        self.graph.add_node(index);
+        index
+    }
+
+    /// Add an edge between two labels. The vertices are added if they are not in the graph yet.
+    ///
+    /// Returns false and leaves the graph unchanged if both labels are the same : a self-loop cannot be covered
+    /// without its vertex, and the solvers do not expect it.
+    pub fn add_edge(&mut self, a: L, b: L) -> bool {
+        if a == b {
+            return false;
+        }
+        let i = self.add_vertex(a);
+        let j = self.add_vertex(b);
+        self.graph.add_edge(i, j, ());
+        true
+    }
+
+    /// Returns the underlying graph, whose vertices are numbered from 0 to order - 1.
+    pub fn graph(&self) -> &UnGraphMap<u64, ()> {
+        &self.graph
+    }
+
+    /// Returns the labels of the vertices, sorted by index.
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
+    /// Returns the index of the vertex with the given label.
+    pub fn index_of(&self, label: &L) -> Option<u64> {
+        self.indices.get(label).copied()
+    }
+
+    /// Returns the label of the vertex with the given index.
+    pub fn label_of(&self, index: u64) -> Option<&L> {
+        self.labels.get(index as usize)
+    }
+
+    /// Translates a set of indices into their labels.
+    ///
+    /// # Panics
+    /// If an index is not a vertex of the graph.
+    pub fn to_labels(&self, indices: &[u64]) -> Vec<L> {
+        indices.iter().map(|&i| self.labels[i as usize].clone()).collect()
+    }
+
+    /// Translates a set of labels into their indices. Returns None if a label is not in the graph.
+    pub fn to_indices(&self, labels: &[L]) -> Option<Vec<u64>> {
+        labels.iter().map(|l| self.index_of(l)).collect()
+    }
+
+    /// Runs an algorithm on the graph and returns the value found along with the set in the original labels.
+    ///
+    /// # Throws
+    /// The error returned by the algorithm.
+    pub fn solve(&self, f: &Algorithm, clock: &mut Clock) -> Result<(u64, Vec<L>), MvcError> {
+        let (value, set) = f(&self.graph, clock)?;
+        Ok((value, self.to_labels(&set)))
+    }
+}
+
+impl LabelledGraph<u64> {
+    /// Relabels a graph whose vertices are not numbered from 0 to n - 1.
+    /// The original vertices become the labels of the new graph, and the self-loops are dropped (see
+    /// [`LabelledGraph::add_edge`]).
+    ///
+    /// # Example
+    /// ```rust
+    /// use petgraph::prelude::UnGraphMap;
+    /// use vertex::labelled::LabelledGraph;
+    ///
+    /// let mut graph = UnGraphMap::<u64, ()>::new();
+    /// graph.add_edge(4, 5, ());
+    /// graph.add_edge(5, 60, ());
+    ///
+    /// let labelled = LabelledGraph::from_graph_map(&graph);
+    /// assert_eq!(labelled.graph().node_count(), 3);
+    /// assert!(labelled.graph().contains_edge(1, 2));
+    /// assert_eq!(labelled.to_labels(&[2]), vec![60]);
+    /// ```
+    pub fn from_graph_map(graph: &UnGraphMap<u64, ()>) -> LabelledGraph<u64> {
+        let mut labelled = LabelledGraph::new();
+        for v in graph.nodes() {
+            labelled.add_vertex(v);
+        }
+        for (i, j, _) in graph.all_edges() {
+            labelled.add_edge(i, j);
+        }
+        labelled
+    }
+}
+
+/// Load a graph from an edge list file where vertices are identified by labels.
+///
+/// Each line contains two labels separated by whitespace (an edge) or a single label (an isolated vertex).
+/// Empty lines and lines starting with `#` or `%` are ignored. The labels are kept as strings : a label
+/// can be any token without whitespace.
+///
+/// # Throws
+/// InvalidClqFileFormat if the file cannot be read, if a line contains more than two labels or if an edge joins a
+/// label to itself (a self-loop, which no vertex cover can cover without the vertex).
+///
+/// # Example
+/// ```rust
+/// use vertex::labelled::load_edge_list;
+///
+/// let graph = load_edge_list("src/resources/tests/graphs/labelled.txt").unwrap();
+/// assert_eq!(graph.graph().node_count(), 5);
+/// assert_eq!(graph.graph().edge_count(), 4);
+/// assert!(graph.index_of(&"alice".to_string()).is_some());
+/// ```
+pub fn load_edge_list(path: &str) -> Result<LabelledGraph<String>, InvalidClqFileFormat> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => return Err(
+            InvalidClqFileFormat::new(&format!("File {:?} not found \n {:?}", path, e))
+        ),
+    };
+    let reader = BufReader::new(file);
+
+    let mut g = LabelledGraph::new();
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| InvalidClqFileFormat::at(&e.to_string(), line_number + 1, 1))?;
+        let tokens = tokenize(&line);
+        let values: Vec<&str> = tokens.iter().map(|(_, token)| *token).collect();
+        match values.as_slice() {
+            [] => continue,
+            [first, ..] if first.starts_with('#') || first.starts_with('%') => continue,
+            [a] => {
+                g.add_vertex(a.to_string());
+            }
+            [a, b] => {
+                if !g.add_edge(a.to_string(), b.to_string()) {
+                    return Err(InvalidClqFileFormat::at(&format!("Self-loop on vertex {:?}", a), line_number + 1,
+                                                        tokens[1].0));
+                }
+            }
+            _ => {
+                return Err(InvalidClqFileFormat::at(&format!("Expecting at most 2 labels but read {}", values.len()),
+                                                    line_number + 1, 1));
+            }
+        }
+    }
+    Ok(g)
+}
+
+#[cfg(test)]
+mod labelled_tests {
+    use crate::{branch_and_bound, naive_search};
+    use crate::graph_utils::is_vertex_cover;
+
+    use super::*;
+
+    #[test]
+    fn test_solve_with_labels() {
+        let graph = load_edge_list("src/resources/tests/graphs/labelled.txt").unwrap();
+        let (value, cover) = graph.solve(&naive_search, &mut Clock::new(3600)).unwrap();
+        assert_eq!(value, 2);
+        let indices = graph.to_indices(&cover).unwrap();
+        assert!(is_vertex_cover(graph.graph(), &indices));
+
+        let (value, _) = graph.solve(&branch_and_bound, &mut Clock::new(3600)).unwrap();
+        assert_eq!(value, 2);
+    }
+
+    #[test]
+    fn test_from_graph_map_non_contiguous() {
+        let mut graph = UnGraphMap::<u64, ()>::new();
+        graph.add_node(4);
+        graph.add_node(5);
+        graph.add_node(6);
+        graph.add_edge(4, 5, ());
+        graph.add_edge(5, 6, ());
+
+        let labelled = LabelledGraph::from_graph_map(&graph);
+        let (value, cover) = labelled.solve(&branch_and_bound, &mut Clock::new(3600)).unwrap();
+        assert_eq!(value, 1);
+        assert_eq!(cover, vec![5]);
+    }
+
+    #[test]
+    fn test_self_loop_is_rejected() {
+        let mut graph = LabelledGraph::new();
+        assert!(!graph.add_edge("alice", "alice"));
+        assert_eq!(graph.graph().node_count(), 0);
+        assert!(graph.add_edge("alice", "bob"));
+        assert_eq!(graph.graph().edge_count(), 1);
+    }
+
+    #[test]
+    fn test_load_edge_list_errors() {
+        assert!(load_edge_list("unknown_file.txt").is_err());
+        let err = load_edge_list("src/resources/tests/graphs/format_symbol.clq").unwrap_err();
+        assert_eq!(err.line, Some(1));
+
+        let err = load_edge_list("src/resources/tests/graphs/labelled_self_loop.txt").unwrap_err();
+        assert_eq!(err.message, "Self-loop on vertex \"carol\"");
+        assert_eq!((err.line, err.column), (Some(3), Some(8)));
+    }
+}
@@ -1,20 +1,53 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::Display;
-use std::ops::Add;
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use itertools::Itertools;
 use petgraph::prelude::UnGraphMap;
 use serde::{Deserialize, Serialize};
 
-use crate::branch_and_bound::b_and_b;
-use crate::errors::{ClockError, YamlError};
-use crate::graph_utils::{copy_graph, get_optimal_value, is_optimal_value, is_vertex_cover};
+use crate::branch_and_bound::{b_and_b, OpenNode};
+use crate::checkpoint::Subproblem;
+use crate::errors::{ClockError, MvcError, YamlError};
+use crate::database::{InstanceDatabase, Problem, RunStatus};
+use crate::graph_utils::{copy_graph, is_vertex_cover, GraphStats};
+use crate::observer::{Observer, Progress};
+use crate::profiler::{Profile, Profiler};
+use crate::workspace::Workspace;
 
 pub mod graph_utils;
 mod branch_and_bound;
 pub mod mvcgraph;
 pub mod errors;
+pub mod csr;
+pub mod labelled;
+pub mod cache;
+pub mod database;
+pub mod workspace;
+pub mod persist;
+pub mod fingerprint;
+pub mod bench;
+pub mod compare;
+pub mod report;
+pub mod generators;
+pub mod families;
+pub mod cli;
+pub mod portfolio;
+pub mod checkpoint;
+pub mod observer;
+pub mod profiler;
+pub mod symmetry;
+
+/// Signature shared by every algorithm that can be given to [`run_algorithm`].
+///
+/// The algorithm receives the graph and a clock and returns the value of the vertex cover found
+/// along with the vertices that form it. An algorithm stopped by a limit returns the best vertex cover found so far,
+/// or MvcError::Timeout if it has none.
+pub type Algorithm = dyn Fn(&UnGraphMap<u64, ()>, &mut Clock) -> Result<(u64, Vec<u64>), MvcError>;
 
 /// Naïve algorithm that searches for the minimum vertex cover of a given graph.
 ///
@@ -23,6 +56,9 @@ pub mod errors;
 ///
 /// This algorithm can be used on any graph with order < 65.
 ///
+/// # Throws
+/// MvcError::Timeout if the algorithm is stopped by a limit : it has no vertex cover until the end of the search.
+///
 /// # Example
 /// ```rust
 /// use petgraph::prelude::UnGraphMap;
@@ -38,87 +74,177 @@ pub mod errors;
 /// graph.add_edge(2, 3, ());
 ///
 /// let expected_vertex_cover = 2; //[0, 2] or [1, 2]
-/// assert_eq!(naive_search(&graph, &mut Clock::new(3600)).0, expected_vertex_cover);
+/// assert_eq!(naive_search(&graph, &mut Clock::new(3600)).unwrap().0, expected_vertex_cover);
 /// ```
-pub fn naive_search(graph: &UnGraphMap<u64, ()>, clock: &mut Clock) -> (u64, Vec<u64>) {
-    let possible_values: Vec<u64> = (0..graph.node_count() as u64).collect();
+pub fn naive_search(graph: &UnGraphMap<u64, ()>, clock: &mut Clock) -> Result<(u64, Vec<u64>), MvcError> {
+    let possible_values: Vec<u64> = graph.nodes().collect();
     for i in 0..graph.node_count() {
+        if i > 0 {
+            // No subset of i - 1 vertices is a vertex cover
+            clock.improve_lower_bound(i as u64);
+        }
         for t in possible_values.iter().combinations(i) {
-            if clock.is_time_up() {
-                return (0, Vec::new());
+            if clock.should_stop() {
+                return Err(MvcError::Timeout(clock.stop_reason().unwrap_or(StopReason::TimeLimit)));
             }
+            clock.count_node();
             let subset: Vec<u64> = itertools::cloned(t).collect();
 
 
             if is_vertex_cover(graph, &subset) {
-                return (subset.len() as u64, subset);
+                clock.improve_incumbent(subset.len() as u64);
+                return Ok((subset.len() as u64, subset));
             }
         }
     }
-    (0, Vec::new())
+    Ok((0, Vec::new()))
 }
 
-/// Run a given algorithm on a given graph and print the result.
+/// Run a given algorithm on a given graph with a time limit of 1 hour.
 ///
 /// It is the default function when you want to test your algorithm on a certain graph.
-/// It prints the result and tell you if it is optimal or not based on the data in the yaml file.
-/// The algorithm must take an UnGraphMap as input and returns u64.
+/// The result tells you if it is optimal or not based on the instance database of the workspace.
+/// Nothing is printed : use [`solve_problem`] with an [`Observer`] to follow the run.
+///
+/// If cmpl is true, the algorithm is run on the complement of the graph and the result is the maximum clique of the
+/// graph (see [`solve_problem`]).
 ///
 /// # Example
 /// ```rust
 /// use petgraph::prelude::UnGraphMap;use vertex::graph_utils::load_clq_file;
 /// use vertex::{naive_search, run_algorithm};
+/// use vertex::workspace::Workspace;
 ///
+/// let ws = Workspace::default();
 /// let mut graph = load_clq_file("src/resources/graphs/test.clq").unwrap();
-/// let res = run_algorithm("test.clq", &graph, &naive_search, false).unwrap_or_else(|e| {
+/// let res = run_algorithm(&ws, "test.clq", &graph, &naive_search, false).unwrap_or_else(|e| {
 ///    panic!("Error while running algorithm : {}", e);
 /// });
 /// println!("{}", res);
 /// ```
-pub fn run_algorithm(graph_id: &str,
+pub fn run_algorithm(ws: &Workspace,
+                     graph_id: &str,
                      graph: &UnGraphMap<u64, ()>,
-                     f: &dyn Fn(&UnGraphMap<u64, ()>, &mut Clock) -> (u64, Vec<u64>),
-                     cmpl: bool) -> Result<MVCResult, YamlError> {
-    let g: UnGraphMap<u64, ()>;
-    if cmpl {
-        g = graph_utils::complement(graph);
-        let density = (2 * g.edge_count()) as f64 / (g.node_count() * (g.node_count() - 1)) as f64;
-        println!("Running algorithm the complement of the graph. Order = {} and size = {}. Density = {}",
-                 g.node_count(),
-                 g.edge_count(),
-                 density);
-    } else {
-        g = copy_graph(graph);
-        let density = (2 * g.edge_count()) as f64 / (g.node_count() * (g.node_count() - 1)) as f64;
-        println!("Running algorithm on the graph. Order = {} and size = {}, density = {}",
-                 graph.node_count(),
-                 graph.edge_count(),
-                 density);
-    }
+                     f: &Algorithm,
+                     cmpl: bool) -> Result<MVCResult, MvcError> {
+    solve_problem(ws, graph_id, graph, f, MVCResult::problem_of(cmpl), Limits::seconds(3600))
+}
 
-    let limit = 3600;
+/// Solves a problem on a graph with a minimum vertex cover algorithm and checks the result against the instance
+/// database of the workspace.
+///
+/// * Mvc : the algorithm is run on the graph
+/// * Mis : the algorithm is run on the graph and the independent set is the complement of the vertex cover
+/// * Clique : the algorithm is run on the complement of the graph, whose independent sets are the cliques of the graph
+///
+/// The algorithm is stopped when it reaches the time limit or exhausts the node budget of the limits, and the status of
+/// the result tells which limit was reached. The solution is checked when the algorithm was not stopped. Otherwise,
+/// the bounds of the result come from a maximum matching of the graph (see [`GraphStats`]).
+///
+/// The observer of the limits, if any, is notified when the algorithm starts and ends. The maximum matching is its
+/// first lower bound.
+///
+/// # Throws
+/// - MvcError::Yaml if the database cannot be loaded or if the graph does not match the instance stored under this id
+/// - MvcError::InvalidResult if the algorithm was not stopped and its solution is not a vertex cover
+/// - the error returned by the algorithm, except MvcError::Timeout : a stopped algorithm without vertex cover gives no
+///   value and an empty set, whatever the problem
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::{branch_and_bound, Limits, solve_problem};
+/// use vertex::database::{Problem, RunStatus};
+/// use vertex::graph_utils::{is_clique, load_clq_file};
+/// use vertex::workspace::Workspace;
+///
+/// let ws = Workspace::default();
+/// let graph = load_clq_file("src/resources/graphs/test.clq").unwrap();
+/// let res = solve_problem(&ws, "test.clq", &graph, &branch_and_bound, Problem::Clique, Limits::seconds(60)).unwrap();
+/// assert_eq!(res.value, Some(2));
+/// assert!(is_clique(&graph, &res.set));
+///
+/// let limits = Limits::new(Duration::from_millis(500)).with_node_limit(1);
+/// let res = solve_problem(&ws, "myciel4.clq", &load_clq_file("src/resources/graphs/myciel4.clq").unwrap(),
+///                         &branch_and_bound, Problem::Mvc, limits).unwrap();
+/// assert_eq!(res.status, RunStatus::NodeLimit);
+/// assert_eq!(res.nodes, 1);
+/// assert!(res.value.is_none_or(|value| res.lower_bound <= value && value <= res.upper_bound));
+/// ```
+pub fn solve_problem(ws: &Workspace,
+                     graph_id: &str,
+                     graph: &UnGraphMap<u64, ()>,
+                     f: &Algorithm,
+                     problem: Problem,
+                     limits: Limits) -> Result<MVCResult, MvcError> {
+    let g = if problem == Problem::Clique { graph_utils::complement(graph) } else { copy_graph(graph) };
+    let stats = GraphStats::new(&g);
 
-    let mut clock: Clock = Clock::new(limit);
+    let mut clock: Clock = Clock::with_limits(limits);
+    clock.notify_start(&stats);
+    clock.improve_lower_bound(stats.max_matching as u64);
 
-    let res = f(&g, &mut clock);
+    let res = match f(&g, &mut clock) {
+        Err(MvcError::Timeout(_)) => (0, Vec::new()),
+        res => res?,
+    };
+    clock.stop_timer();
+    clock.notify_finish();
 
     let elapsed = clock.get_time();
-    if !clock.is_time_up() {
-        assert!(is_vertex_cover(&g, &res.1));
-        assert_eq!(res.0, res.1.len() as u64);
+    let stop_reason = clock.stop_reason();
+    let is_cover = is_vertex_cover(&g, &res.1);
+    if stop_reason.is_none() {
+        check_vertex_cover(&g, &res)?;
     }
 
-    MVCResult::new(graph_id.to_string(), res.0, res.1, elapsed, clock.is_time_up(), cmpl)
+    let (value, set) = match problem {
+        // The complement of a set that is not a vertex cover is not an independent set
+        _ if !is_cover => (None, Vec::new()),
+        Problem::Mvc => (Some(res.0), res.1),
+        Problem::Clique | Problem::Mis => {
+            // The vertices outside a vertex cover form an independent set
+            let cover: HashSet<u64> = res.1.into_iter().collect();
+            let set: Vec<u64> = g.nodes().filter(|v| !cover.contains(v)).sorted().collect();
+            (Some(set.len() as u64), set)
+        }
+    };
+    let mut result = MVCResult::with_problem(ws, graph_id.to_string(), graph, problem, value, set, elapsed,
+                                             stop_reason == Some(StopReason::TimeLimit))?;
+    if let Some(reason) = stop_reason {
+        result.status = reason.into();
+        // Every vertex cover contains a vertex of each edge of a matching. The value is only a bound if the
+        // algorithm returned a vertex cover.
+        let order = g.node_count() as u64;
+        let matching = stats.max_matching as u64;
+        (result.lower_bound, result.upper_bound) = match problem {
+            Problem::Mvc => (matching, value.unwrap_or(order)),
+            _ => (value.unwrap_or(0), order - matching),
+        };
+    }
+    result.subroutines = clock.subroutines().into_iter()
+        .map(|(name, duration)| (name, duration.as_secs_f64()))
+        .collect();
+    result.profile = clock.profile();
+    result.nodes = clock.nodes();
+    result.pruned = clock.pruned().clone();
+    result.graph_stats = Some(stats);
+    Ok(result)
 }
 
 /// Branch and bound algorithm that searches for the minimum vertex cover of a given graph.
 ///
 /// * Branch : The algorithm branches on the vertex with max degree.
-/// It creates 2 branches : one where the vertex is in the vertex cover and one where its neighbors are in the vertex cover.
+///   It creates 2 branches : one where the vertex is in the vertex cover and one where its neighbors are in the vertex cover.
 /// * Bound : The algorithm has 2 lower bounds : clqLB and degLB. (see the paper linked in README for more details)
 ///
 /// The clock is used to stop the algorithm if it reaches the time limit defined in the clock.
 /// It is also used to measure the time taken by the algorithm (and some of its subroutines).
+/// Long runs can be checkpointed and resumed with [`checkpoint::checkpointed_branch_and_bound`].
+///
+/// # Throws
+/// - MvcError::ThreadFailure if a thread computing a lower bound panicked
+/// - MvcError::InvalidResult if the vertex cover found is not valid
 ///
 /// # Example
 /// ```rust
@@ -130,21 +256,188 @@ pub fn run_algorithm(graph_id: &str,
 ///             .expect("Error while loading graph");
 /// let mut clock = Clock::new(3600); // 1 hour time limit
 ///
-/// let res = branch_and_bound(&graph, &mut clock);
+/// let res = branch_and_bound(&graph, &mut clock).unwrap();
 ///
 /// assert_eq!(res.0, 3);
 /// assert_eq!(res.1, vec![0, 4, 2]);
 /// ```
 ///
-pub fn branch_and_bound(graph: &UnGraphMap<u64, ()>, clock: &mut Clock) -> (u64, Vec<u64>) {
-    // Initialize the upper bound to the number of nodes in the graph
-    // and the vertex cover found so far is empty
-    let upper_bound_vc = &graph.nodes().collect();
-    let u = b_and_b(graph, graph, graph.node_count() as u64,
-                    upper_bound_vc, vec![], clock);
+pub fn branch_and_bound(graph: &UnGraphMap<u64, ()>, clock: &mut Clock) -> Result<(u64, Vec<u64>), MvcError> {
+    branch_and_bound_with(graph, clock, &BnbOptions::default())
+}
+
+/// Branch and bound (see [`branch_and_bound`]) with a branching strategy and a polynomial solver for the subgraphs of
+/// maximum degree 2.
+///
+/// # Throws
+/// The errors of [`branch_and_bound`].
+///
+/// # Example
+/// ```rust
+/// use vertex::{BnbOptions, branch_and_bound, branch_and_bound_with, Branching, Clock};
+/// use vertex::graph_utils::load_clq_file;
+///
+/// let graph = load_clq_file("src/resources/graphs/queen5_5.clq").unwrap();
+/// let mut default_clock = Clock::new(60);
+/// branch_and_bound(&graph, &mut default_clock).unwrap();
+///
+/// let options = BnbOptions::new().with_branching(Branching::Mirror).with_degree_two(true);
+/// let mut clock = Clock::new(60);
+/// let (value, _) = branch_and_bound_with(&graph, &mut clock, &options).unwrap();
+/// assert_eq!(value, 20);
+/// assert!(clock.nodes() <= default_clock.nodes());
+/// ```
+pub fn branch_and_bound_with(graph: &UnGraphMap<u64, ()>, clock: &mut Clock, options: &BnbOptions)
+                             -> Result<(u64, Vec<u64>), MvcError> {
+    // Initialize the incumbent to all the nodes of the graph
+    // and start from the whole graph with no vertex fixed
+    let mut best: Vec<u64> = graph.nodes().collect();
+    let mut open = vec![OpenNode::from_subproblem(graph, Subproblem::default())];
+    b_and_b(&mut open, &mut best, clock, options, |_, _, _| Ok(()))?;
+
+    let res = (best.len() as u64, best);
+    check_vertex_cover(graph, &res)?;
+    Ok(res)
+}
+
+/// Vertex the branch and bound branches on, and the vertices fixed with it in each branch.
+///
+/// Each strategy explores the branch where the vertex is in the vertex cover first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Branching {
+    /// Branches on a vertex of maximum degree v : either v is in the vertex cover, or its neighbours are.
+    #[default]
+    MaxDegree,
+    /// Branches on a vertex of maximum degree v, whose mirrors go in the vertex cover with it (a mirror of v is a
+    /// vertex u at distance 2 such that the neighbours of v that are not neighbours of u form a clique).
+    Mirror,
+    /// Branches on a vertex of maximum degree v, whose satellites are left out of the vertex cover with it (a
+    /// satellite of v is a vertex u at distance 2 that is the only neighbour outside N\[v\] of a neighbour of v).
+    Satellite,
+    /// Branches on the vertex whose neighbours share the most neighbours with it (the vertex of most triangles), the
+    /// ties being broken by degree.
+    Overlap,
+}
+
+impl Branching {
+    pub const ALL: [Branching; 4] = [Branching::MaxDegree, Branching::Mirror, Branching::Satellite, Branching::Overlap];
+
+    /// Name of the strategy, used on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Branching::MaxDegree => "max-degree",
+            Branching::Mirror => "mirror",
+            Branching::Satellite => "satellite",
+            Branching::Overlap => "overlap",
+        }
+    }
+}
+
+impl Display for Branching {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Branching {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Branching::ALL.iter()
+            .find(|branching| branching.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown branching {:?} (expecting one of max-degree, mirror, satellite, overlap)", s))
+    }
+}
+
+/// Options of the branch and bound (see [`branch_and_bound_with`]). The default options are those of
+/// [`branch_and_bound`].
+///
+/// The pruning rules count the subtrees they skip with [`Clock::count_pruned`] :
+/// * dominance : when the vertex v of the branching dominates one of its neighbours u (N\[u\] ⊆ N\[v\]), a minimum
+///   vertex cover contains v and the branch leaving v out is pruned.
+/// * symmetry : the vertices of the orbit of v (see [`symmetry::orbit`]) go in the vertex cover with v, since a
+///   minimum vertex cover leaving one of them out leaves v out too, up to an automorphism. The subtrees of the
+///   other vertices of the orbit are counted as pruned. The orbits are only searched in the first
+///   [`symmetry::MAX_DEPTH`] levels of the tree.
+///
+/// # Example
+/// ```rust
+/// use vertex::{BnbOptions, Branching};
+///
+/// let options = BnbOptions::new().with_branching(Branching::Satellite).with_degree_two(true);
+/// assert_eq!(options.to_string(), "satellite+degree-two");
+/// assert_eq!(BnbOptions::default().to_string(), "max-degree");
+/// assert_eq!(BnbOptions::new().with_dominance(true).with_symmetry(true).to_string(), "max-degree+dominance+symmetry");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BnbOptions {
+    pub branching: Branching,
+    /// Solves the subgraphs of maximum degree 2 (paths and cycles) in linear time instead of branching
+    pub degree_two: bool,
+    /// Prunes the branch leaving out a vertex that dominates one of its neighbours
+    pub dominance: bool,
+    /// Puts the orbit of the vertex of the branching in the vertex cover with it
+    pub symmetry: bool,
+}
+
+impl BnbOptions {
+    pub fn new() -> BnbOptions {
+        Default::default()
+    }
+
+    /// Sets the branching strategy.
+    pub fn with_branching(self, branching: Branching) -> BnbOptions {
+        BnbOptions { branching, ..self }
+    }
+
+    /// Sets whether the subgraphs of maximum degree 2 are solved without branching.
+    pub fn with_degree_two(self, degree_two: bool) -> BnbOptions {
+        BnbOptions { degree_two, ..self }
+    }
+
+    /// Sets whether the dominance pruning is used.
+    pub fn with_dominance(self, dominance: bool) -> BnbOptions {
+        BnbOptions { dominance, ..self }
+    }
+
+    /// Sets whether the symmetry pruning is used.
+    pub fn with_symmetry(self, symmetry: bool) -> BnbOptions {
+        BnbOptions { symmetry, ..self }
+    }
+}
+
+impl Display for BnbOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.branching)?;
+        if self.degree_two {
+            write!(f, "+degree-two")?;
+        }
+        if self.dominance {
+            write!(f, "+dominance")?;
+        }
+        if self.symmetry {
+            write!(f, "+symmetry")?;
+        }
+        Ok(())
+    }
+}
 
-    assert!(is_vertex_cover(graph, &u.1));
-    u
+/// Checks that the result of an algorithm is a vertex cover of the graph and that its value is its size.
+///
+/// # Throws
+/// MvcError::InvalidResult if the result is not valid.
+pub(crate) fn check_vertex_cover(graph: &UnGraphMap<u64, ()>, res: &(u64, Vec<u64>)) -> Result<(), MvcError> {
+    if !is_vertex_cover(graph, &res.1) {
+        return Err(MvcError::InvalidResult("The solution is not a vertex cover".to_string(),
+                                           format!("{:?}", res.1)));
+    }
+    if res.0 != res.1.len() as u64 {
+        return Err(MvcError::InvalidResult(
+            format!("The value {} is not the size of the vertex cover ({})", res.0, res.1.len()),
+            format!("{:?}", res.1)));
+    }
+    Ok(())
 }
 
 /// Struct representing the time taken by an algorithm (in minutes, seconds, milliseconds and microseconds)
@@ -195,76 +488,290 @@ impl Display for ElapseTime {
     }
 }
 
+impl FromStr for ElapseTime {
+    type Err = String;
+
+    /// Parses a time written by the Display implementation (ex: "3min 10s 1ms 1µs").
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use vertex::ElapseTime;
+    ///
+    /// let elapsed: ElapseTime = "3min 10s 1ms 1µs".parse().unwrap();
+    /// assert_eq!(elapsed.duration, Duration::new(190, 1001000));
+    /// assert!("3 minutes".parse::<ElapseTime>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut micros: u128 = 0;
+        let mut units = ["min", "s", "ms", "µs"].iter();
+        for part in s.split_whitespace() {
+            let unit = units.next().ok_or_else(|| format!("Unexpected value {:?} in {:?}", part, s))?;
+            let value = part.strip_suffix(unit)
+                .and_then(|v| v.parse::<u128>().ok())
+                .ok_or_else(|| format!("Expecting a value in {} but read {:?}", unit, part))?;
+            micros += value * match *unit {
+                "min" => 60_000_000,
+                "s" => 1_000_000,
+                "ms" => 1_000,
+                _ => 1,
+            };
+        }
+        if units.next().is_some() {
+            return Err(format!("Incomplete time {:?}", s));
+        }
+        Ok(ElapseTime::new(Duration::from_micros(micros as u64)))
+    }
+}
+
 /// Struct representing the result of an algorithm
+///
+/// The result can be written as text (its Display implementation) or in a machine-readable format (JSON, YAML or a
+/// CSV row, see [`OutputFormat`]). In these formats, the times are given in seconds.
+///
+/// # Example
+/// ```rust
+/// use vertex::{branch_and_bound, Limits, MVCResult, OutputFormat, solve_problem};
+/// use vertex::database::{Problem, RunStatus};
+/// use vertex::graph_utils::load_clq_file;
+/// use vertex::workspace::Workspace;
+///
+/// let graph = load_clq_file("src/resources/graphs/test.clq").unwrap();
+/// let res = solve_problem(&Workspace::default(), "test.clq", &graph, &branch_and_bound, Problem::Mvc, Limits::seconds(60)).unwrap();
+/// assert_eq!(res.status, RunStatus::Solved);
+/// assert_eq!((res.lower_bound, res.upper_bound), (3, 3));
+///
+/// let json = res.format(OutputFormat::Json);
+/// assert!(json.contains("\"value\": 3"));
+/// let csv = res.format(OutputFormat::Csv);
+/// assert!(csv.starts_with(MVCResult::CSV_HEADER));
+/// ```
+#[derive(Debug, Serialize)]
 pub struct MVCResult {
     /// The id of the graph. Example : "test.clq"
     pub graph_id: String,
-    /// The value of the minimum vertex cover calculated by the algorithm
-    pub value: u64,
-    /// The set of vertices that form the minimum vertex cover
-    pub set: Vec<u64>,
-    /// Whether the value is optimal or not. (Found in the clique and graph data yaml files)
+    /// The problem solved (the minimum vertex cover, or the maximum clique if the algorithm was run on the complement)
+    pub problem: Problem,
+    /// The value calculated by the algorithm for the problem (none if it was stopped before finding a solution)
+    pub value: Option<u64>,
+    /// How the algorithm ended (solved, or stopped by the time limit or the node budget)
+    pub status: RunStatus,
+    /// Lower bound of the optimal value (equal to the value if the algorithm was not stopped)
+    pub lower_bound: u64,
+    /// Upper bound of the optimal value (equal to the value if the algorithm was not stopped)
+    pub upper_bound: u64,
+    /// Whether the value is optimal or not. (Found in the instance database)
     pub is_optimal: Option<bool>,
+    /// The optimal value stored in the instance database (if known)
+    pub optimal_value: Option<u64>,
     /// The time taken by the algorithm
+    #[serde(serialize_with = "serialize_seconds")]
     pub time: ElapseTime,
+    /// Time spent in each subroutine of the algorithm, in seconds (see [`Clock::subroutines`])
+    pub subroutines: BTreeMap<String, f64>,
+    /// Calls and time of the subroutines in each scope they were called from (see [`Clock::profile`])
+    pub profile: Profile,
+    /// Number of search nodes explored by the algorithm (see [`Clock::count_node`])
+    pub nodes: u64,
+    /// Number of subtrees pruned by each rule of the algorithm (see [`Clock::count_pruned`])
+    pub pruned: BTreeMap<String, u64>,
+    /// Statistics of the graph the algorithm was run on (the complement of the graph for the maximum clique)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graph_stats: Option<GraphStats>,
+    /// The set of vertices that form the solution (the vertex cover, the clique or the independent set)
+    pub set: Vec<u64>,
     /// Whether the algorithm was stopped because it reached the time limit
+    #[serde(skip)]
     pub is_time_limit: bool,
     /// Whether the algorithm was run on the complement of the graph
+    #[serde(skip)]
     pub is_compl: bool,
 }
 
+fn serialize_seconds<S: serde::Serializer>(time: &ElapseTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(time.duration.as_secs_f64())
+}
+
+/// Format in which a result is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text (the Display implementation)
+    Text,
+    Json,
+    Yaml,
+    /// Header line followed by a row (see [`MVCResult::CSV_HEADER`])
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!("Unknown output format {:?} (expecting text, json, yaml or csv)", s)),
+        }
+    }
+}
+
 impl MVCResult {
-    pub fn new(graph_id: String, value: u64, mvc: Vec<u64>, time: ElapseTime, is_time_limit: bool, is_compl: bool) -> Result<MVCResult, YamlError> {
-        let is_optimal = if is_compl {
-            is_optimal_value(&graph_id, value, Some("src/resources/clique_data.yml"))?
-        } else {
-            is_optimal_value(&graph_id, value, None)?
+    /// Columns of the CSV rows written by [`MVCResult::to_csv_row`]. The subroutines are written as
+    /// `name=seconds` pairs separated by `;` and the vertices of the set are separated by spaces.
+    pub const CSV_HEADER: &'static str = "graph_id,problem,value,status,lower_bound,upper_bound,is_optimal,\
+optimal_value,time,nodes,subroutines,set";
+
+    /// Creates the result of an algorithm and checks if the value is optimal using the instance database of the workspace.
+    ///
+    /// If the algorithm was run on the complement, the value is compared to the known maximum clique.
+    /// The graph is the one given to [`run_algorithm`] (not its complement) : it is checked against the fingerprint
+    /// stored in the database, so that a modified file does not inherit the optimal value of the original instance.
+    ///
+    /// # Throws
+    /// - YamlError if the database cannot be loaded
+    /// - YamlError::FingerprintMismatch if the graph is not the instance stored in the database under this id
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(ws: &Workspace, graph_id: String, graph: &UnGraphMap<u64, ()>, value: u64, mvc: Vec<u64>, time: ElapseTime,
+               is_time_limit: bool, is_compl: bool) -> Result<MVCResult, YamlError> {
+        MVCResult::with_problem(ws, graph_id, graph, MVCResult::problem_of(is_compl), Some(value), mvc, time,
+                                is_time_limit)
+    }
+
+    /// Creates the result of an algorithm for a given problem and checks if the value is optimal using the instance
+    /// database of the workspace. See [`MVCResult::new`].
+    ///
+    /// If the algorithm reached the time limit, the bounds are the trivial ones (0 or the order of the graph) :
+    /// [`solve_problem`] tightens them with the maximum matching of the graph. The value is none if the algorithm was
+    /// stopped before finding a solution.
+    ///
+    /// The optimal value is unknown if the workspace has no database yet.
+    ///
+    /// # Throws
+    /// - YamlError if the database cannot be loaded
+    /// - YamlError::FingerprintMismatch if the graph is not the instance stored in the database under this id
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_problem(ws: &Workspace, graph_id: String, graph: &UnGraphMap<u64, ()>, problem: Problem,
+                        value: Option<u64>, set: Vec<u64>, time: ElapseTime, is_time_limit: bool) -> Result<MVCResult, YamlError> {
+        let db = InstanceDatabase::load(&ws.database_path())?;
+        let optimal_value = db.get_optimal_value(&graph_id, problem, graph)?;
+        let order = graph.node_count() as u64;
+        let (lower_bound, upper_bound) = match (is_time_limit, problem) {
+            (false, _) => (value.unwrap_or(0), value.unwrap_or(order)),
+            (true, Problem::Mvc) => (0, value.unwrap_or(order)),
+            (true, _) => (value.unwrap_or(0), order),
         };
         Ok(MVCResult {
             graph_id,
+            problem,
             value,
-            set: mvc,
-            is_optimal,
+            status: if is_time_limit { RunStatus::TimeLimit } else { RunStatus::Solved },
+            lower_bound,
+            upper_bound,
+            is_optimal: optimal_value.map(|v| Some(v) == value),
+            optimal_value,
             time,
+            subroutines: BTreeMap::new(),
+            profile: Profile::default(),
+            nodes: 0,
+            pruned: BTreeMap::new(),
+            graph_stats: None,
+            set,
             is_time_limit,
-            is_compl,
+            is_compl: problem == Problem::Clique,
         })
     }
+
+    /// Returns the problem the value of a result refers to.
+    pub fn problem_of(is_compl: bool) -> Problem {
+        if is_compl {
+            Problem::Clique
+        } else {
+            Problem::Mvc
+        }
+    }
+
+    /// Writes the result in the given format.
+    pub fn format(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => format!("{}\n", self),
+            OutputFormat::Json => format!("{}\n", self.to_json()),
+            OutputFormat::Yaml => self.to_yaml(),
+            OutputFormat::Csv => format!("{}\n{}\n", MVCResult::CSV_HEADER, self.to_csv_row()),
+        }
+    }
+
+    /// Writes the result as a JSON object.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("A result can always be serialized")
+    }
+
+    /// Writes the result as a YAML document.
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("A result can always be serialized")
+    }
+
+    /// Writes the result as a CSV row, without the header (see [`MVCResult::CSV_HEADER`]).
+    pub fn to_csv_row(&self) -> String {
+        let option = |v: Option<String>| v.unwrap_or_default();
+        let subroutines = self.subroutines.iter().map(|(name, time)| format!("{}={}", name, time)).join(";");
+        [
+            csv_field(&self.graph_id),
+            self.problem.to_string(),
+            option(self.value.map(|v| v.to_string())),
+            self.status.name().to_string(),
+            self.lower_bound.to_string(),
+            self.upper_bound.to_string(),
+            option(self.is_optimal.map(|b| b.to_string())),
+            option(self.optimal_value.map(|v| v.to_string())),
+            self.time.duration.as_secs_f64().to_string(),
+            self.nodes.to_string(),
+            csv_field(&subroutines),
+            self.set.iter().join(" "),
+        ].join(",")
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a new line.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
 }
 
 impl Display for MVCResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The value of a stopped run is only the best one found so far
         let opt_message = {
-            if self.is_optimal.is_some() {
-                if self.is_optimal.unwrap() {
-                    "\t The value is optimal (as long as the data is correct in the yaml file)".to_string()
-                } else {
-                    let true_opt = if self.is_compl {
-                        get_optimal_value(&self.graph_id, Some("src/resources/clique_data.yml")).unwrap_or(Some(0))
-                    } else {
-                        get_optimal_value(&self.graph_id, None).unwrap_or(Some(0))
-                    };
-                    format!("\t The value is not optimal and the correct value is {}", true_opt.unwrap_or(0)).to_string()
-                }
-            } else {
-                "\t The graph is not in the yaml file".to_string()
+            match (self.status, self.is_optimal, self.optimal_value) {
+                (_, _, None) => "\t The graph is not in the database".to_string(),
+                (RunStatus::Solved, Some(true), _) =>
+                    "\t The value is optimal (as long as the data is correct in the database)".to_string(),
+                (RunStatus::Solved, _, Some(true_opt)) =>
+                    format!("\t The value is not optimal and the correct value is {}", true_opt),
+                (_, _, Some(true_opt)) => format!("\t The optimal value is {}", true_opt),
             }
         };
 
-        let time_limit_message = {
-            if self.is_time_limit {
-                "\n\t The algorithm was stopped because it reached the time limit".to_string()
-            } else {
-                "".to_string()
-            }
+        let stop_message = match self.status {
+            RunStatus::Solved => "",
+            RunStatus::TimeLimit => "\n\t The algorithm was stopped because it reached the time limit",
+            RunStatus::NodeLimit => "\n\t The algorithm was stopped because it exhausted its node budget",
+            RunStatus::Cancelled => "\n\t The algorithm was cancelled",
+            RunStatus::Killed => "\n\t The algorithm did not stop after the time limit and was killed",
+            RunStatus::Crashed => "\n\t The algorithm crashed",
         };
 
-        write!(f, "Minimum vertex cover for the {:?} graph = {}\n{}\n\t Time taken by the algorithm : {} {}",
+        write!(f, "{} for the {:?} graph = {}\n{}\n\t Time taken by the algorithm : {} {}",
+               self.problem.description(),
                self.graph_id,
-               self.value,
+               self.value.map_or("no solution".to_string(), |v| v.to_string()),
                opt_message,
                self.time,
-               time_limit_message)
+               stop_message)
     }
 }
 
@@ -274,6 +781,10 @@ impl Display for MVCResult {
 /// This clock is based on the std::time::Instant struct.
 /// The clock can also be used to measure the time taken by some subroutines of the algorithm.
 ///
+/// Besides the time limit, the clock can hold a node budget and a [`CancellationToken`] : before exploring a node of
+/// their search, the algorithms call [`Clock::should_stop`] and then [`Clock::count_node`]. When a limit is reached,
+/// they return the best solution found so far and [`Clock::stop_reason`] tells which limit stopped them.
+///
 /// # Example
 /// ```rust
 /// use std::time::Duration;
@@ -300,31 +811,194 @@ impl Display for MVCResult {
 ///```
 pub struct Clock {
     pub start: std::time::Instant,
-    limit: u64,
+    limit: Duration,
     elapsed: Option<Duration>,
+    /// Maximum number of search nodes (no limit if None)
+    node_limit: Option<u64>,
+    /// Number of search nodes counted so far
+    nodes: u64,
+    /// Limit that stopped the algorithm (see [`Clock::should_stop`])
+    stopped: Option<StopReason>,
+    cancellation: Option<CancellationToken>,
+    observer: Option<Arc<dyn Observer>>,
+    last_heartbeat: std::time::Instant,
+    /// Size of the smallest vertex cover reported by the algorithm
+    incumbent: Option<u64>,
+    /// Best lower bound reported by the algorithm
+    lower_bound: Option<u64>,
+    /// Time taken by the subroutines of the algorithm
+    profiler: Profiler,
+    /// Number of subtrees pruned by each rule of the algorithm
+    pruned: BTreeMap<String, u64>,
+}
+
+/// Reason why an algorithm stopped before proving that its solution is optimal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The time limit of the clock was reached
+    TimeLimit,
+    /// The node budget of the clock was exhausted
+    NodeLimit,
+    /// The run was cancelled with its [`CancellationToken`]
+    Cancelled,
+}
 
-    // Hashmap containing the time taken by each subroutine of the algorithm.
-    // Key : name of the subroutine
-    // Value : (start time, time taken)
-    details: HashMap<String, (Option<std::time::Instant>, Duration)>,
+impl From<StopReason> for RunStatus {
+    fn from(reason: StopReason) -> Self {
+        match reason {
+            StopReason::TimeLimit => RunStatus::TimeLimit,
+            StopReason::NodeLimit => RunStatus::NodeLimit,
+            StopReason::Cancelled => RunStatus::Cancelled,
+        }
+    }
 }
+
+/// Token used to cancel a run from another thread (user abort, portfolio of solvers...).
+///
+/// The clones of a token share its state : cancelling one of them cancels them all. The algorithms see the
+/// cancellation the next time they call [`Clock::should_stop`].
+///
+/// # Example
+/// ```rust
+/// use std::thread;
+/// use vertex::{branch_and_bound, CancellationToken, Clock, Limits, StopReason};
+/// use vertex::graph_utils::load_clq_file;
+///
+/// let graph = load_clq_file("src/resources/graphs/keller4.clq").unwrap();
+/// let token = CancellationToken::new();
+/// let mut clock = Clock::with_limits(Limits::seconds(3600).with_cancellation(token.clone()));
+///
+/// let canceller = thread::spawn(move || token.cancel());
+/// canceller.join().unwrap();
+/// branch_and_bound(&graph, &mut clock).unwrap();
+/// assert_eq!(clock.stop_reason(), Some(StopReason::Cancelled));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        Default::default()
+    }
+
+    /// Cancels the runs that use this token or one of its clones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits of a run : a time limit, an optional node budget, an optional cancellation token and the observer of the run.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::Limits;
+///
+/// let limits = Limits::new(Duration::from_millis(500)).with_node_limit(10_000);
+/// assert_eq!(limits.time, Duration::from_millis(500));
+/// assert_eq!(Limits::seconds(60).nodes, None);
+/// ```
+#[derive(Clone)]
+pub struct Limits {
+    pub time: Duration,
+    /// Maximum number of search nodes (no limit if None)
+    pub nodes: Option<u64>,
+    /// Token that cancels the run (see [`CancellationToken`])
+    pub cancellation: Option<CancellationToken>,
+    /// Observer notified of the progress of the run (see [`observer`])
+    pub observer: Option<Arc<dyn Observer>>,
+}
+
+impl std::fmt::Debug for Limits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Limits")
+            .field("time", &self.time)
+            .field("nodes", &self.nodes)
+            .field("cancellation", &self.cancellation)
+            .field("observer", &self.observer.is_some())
+            .finish()
+    }
+}
+
+impl Limits {
+    /// Creates limits with a time limit and no node budget.
+    pub fn new(time: Duration) -> Limits {
+        Limits { time, nodes: None, cancellation: None, observer: None }
+    }
+
+    /// Creates limits with a time limit in seconds and no node budget.
+    pub fn seconds(seconds: u64) -> Limits {
+        Limits::new(Duration::from_secs(seconds))
+    }
+
+    /// Sets the node budget.
+    pub fn with_node_limit(self, nodes: u64) -> Limits {
+        Limits { nodes: Some(nodes), ..self }
+    }
+
+    /// Sets the cancellation token.
+    pub fn with_cancellation(self, token: CancellationToken) -> Limits {
+        Limits { cancellation: Some(token), ..self }
+    }
+
+    /// Sets the observer of the run.
+    pub fn with_observer(self, observer: Arc<dyn Observer>) -> Limits {
+        Limits { observer: Some(observer), ..self }
+    }
+}
+
 impl Clock {
+    /// Creates a clock with a time limit in seconds.
     pub fn new(limit: u64) -> Clock {
+        Clock::with_limits(Limits::seconds(limit))
+    }
+
+    /// Creates a clock with a time limit (with sub-second precision) and an optional node budget.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use vertex::{branch_and_bound, Clock, Limits, StopReason};
+    /// use vertex::graph_utils::load_clq_file;
+    ///
+    /// let graph = load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+    /// let mut clock = Clock::with_limits(Limits::new(Duration::from_millis(800)).with_node_limit(3));
+    /// let (value, _) = branch_and_bound(&graph, &mut clock).unwrap();
+    ///
+    /// // The search stopped after 3 nodes and returned the best vertex cover found so far
+    /// assert!(value >= 12);
+    /// assert_eq!(clock.nodes(), 3);
+    /// assert_eq!(clock.stop_reason(), Some(StopReason::NodeLimit));
+    /// ```
+    pub fn with_limits(limits: Limits) -> Clock {
         Clock {
             start: std::time::Instant::now(),
-            limit,
+            limit: limits.time,
             elapsed: None,
-            details: HashMap::new(),
+            node_limit: limits.nodes,
+            nodes: 0,
+            stopped: None,
+            cancellation: limits.cancellation,
+            observer: limits.observer,
+            last_heartbeat: std::time::Instant::now(),
+            incumbent: None,
+            lower_bound: None,
+            profiler: Profiler::new(),
+            pruned: BTreeMap::new(),
         }
     }
 
     /// Returns the time off the clock since it was created.
     pub fn get_time(&self) -> ElapseTime {
-        if self.elapsed.is_none() {
-            let elapsed = self.start.elapsed();
-            ElapseTime::new(elapsed)
-        } else {
-            ElapseTime::new(self.elapsed.unwrap())
+        match self.elapsed {
+            Some(elapsed) => ElapseTime::new(elapsed),
+            None => ElapseTime::new(self.start.elapsed()),
         }
     }
 
@@ -335,16 +1009,124 @@ impl Clock {
         }
     }
 
-    /// Returns true if the time limit is reached.
+    /// Returns true if the time limit is reached (or was reached when the clock was stopped).
     pub fn is_time_up(&self) -> bool {
-        let elapsed = self.start.elapsed();
-        elapsed.as_secs() >= self.limit
+        self.elapsed.unwrap_or_else(|| self.start.elapsed()) >= self.limit
     }
 
-    /// Enters a subroutine of the algorithm and start the timer for this subroutine.
-    /// It creates a new start time for this subroutine but don't reset the duration.
+    /// Counts a node of the search of the algorithm. The observer gets a heartbeat if it is due.
+    pub fn count_node(&mut self) {
+        self.nodes += 1;
+        if let Some(observer) = &self.observer {
+            if self.last_heartbeat.elapsed() >= observer.heartbeat_interval() {
+                self.last_heartbeat = std::time::Instant::now();
+                observer.on_heartbeat(&self.progress());
+            }
+        }
+    }
+
+    /// Returns the progress of the run : time, nodes, incumbent and lower bound reported so far.
+    pub fn progress(&self) -> Progress {
+        Progress {
+            elapsed: self.get_time().duration,
+            nodes: self.nodes,
+            incumbent: self.incumbent,
+            lower_bound: self.lower_bound,
+        }
+    }
+
+    /// Notifies the observer that the algorithm starts on a graph.
+    pub fn notify_start(&mut self, stats: &GraphStats) {
+        if let Some(observer) = &self.observer {
+            observer.on_start(stats);
+        }
+    }
+
+    /// Reports the size of a vertex cover found by the algorithm. The observer is notified if it is the smallest so far.
+    pub fn improve_incumbent(&mut self, value: u64) {
+        if self.incumbent.is_none_or(|incumbent| value < incumbent) {
+            self.incumbent = Some(value);
+            if let Some(observer) = &self.observer {
+                observer.on_incumbent(&self.progress());
+            }
+        }
+    }
+
+    /// Reports a lower bound on the size of a minimum vertex cover. The observer is notified if it is the best so far.
+    pub fn improve_lower_bound(&mut self, value: u64) {
+        if self.lower_bound.is_none_or(|lower_bound| value > lower_bound) {
+            self.lower_bound = Some(value);
+            if let Some(observer) = &self.observer {
+                observer.on_lower_bound(&self.progress());
+            }
+        }
+    }
+
+    /// Notifies the observer that the algorithm ended. If it was not stopped, its incumbent is a minimum vertex cover
+    /// and becomes the lower bound.
+    pub fn notify_finish(&mut self) {
+        let stop_reason = self.stop_reason();
+        if stop_reason.is_none() {
+            self.lower_bound = self.incumbent.or(self.lower_bound);
+        }
+        if let Some(observer) = &self.observer {
+            observer.on_finish(&self.progress(), stop_reason);
+        }
+    }
+
+    /// Returns the number of nodes counted so far.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Counts subtrees of the search skipped by a pruning rule of the algorithm.
     ///
-    /// If the subroutine was already entered before, it will reset the start time and add the time taken since the last time it was entered.
+    /// # Example
+    /// ```rust
+    /// use vertex::Clock;
+    ///
+    /// let mut clock = Clock::new(60);
+    /// clock.count_pruned("dominance", 1);
+    /// clock.count_pruned("symmetry", 3);
+    /// clock.count_pruned("dominance", 1);
+    /// assert_eq!(clock.pruned()["dominance"], 2);
+    /// assert_eq!(clock.pruned()["symmetry"], 3);
+    /// ```
+    pub fn count_pruned(&mut self, rule: &str, subtrees: u64) {
+        *self.pruned.entry(rule.to_string()).or_insert(0) += subtrees;
+    }
+
+    /// Returns the number of subtrees pruned by each rule so far.
+    pub fn pruned(&self) -> &BTreeMap<String, u64> {
+        &self.pruned
+    }
+
+    /// Returns true if the algorithm must stop because it was cancelled, the node budget is exhausted or the time limit
+    /// is reached. The limit is then recorded as the reason of the stop.
+    pub fn should_stop(&mut self) -> bool {
+        if self.stopped.is_none() {
+            if self.cancellation.as_ref().is_some_and(|token| token.is_cancelled()) {
+                self.stopped = Some(StopReason::Cancelled);
+            } else if self.node_limit.is_some_and(|limit| self.nodes >= limit) {
+                self.stopped = Some(StopReason::NodeLimit);
+            } else if self.is_time_up() {
+                self.stopped = Some(StopReason::TimeLimit);
+            }
+        }
+        self.stopped.is_some()
+    }
+
+    /// Returns the limit that stopped the algorithm, or None if it was not stopped.
+    ///
+    /// The algorithms that only check [`Clock::is_time_up`] are considered stopped by the time limit if it is reached.
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stopped.or_else(|| self.is_time_up().then_some(StopReason::TimeLimit))
+    }
+
+    /// Enters a subroutine of the algorithm and starts its timer. A subroutine entered while another one is running is
+    /// profiled as its child (see [`profiler`]).
+    ///
+    /// [`Clock::scope`] exits the subroutine automatically.
     /// # Example
     /// ```rust
     /// use std::time::Duration;
@@ -356,48 +1138,51 @@ impl Clock {
     /// // Do something
     /// clock.enter_subroutine("subroutine2");
     /// // Do something
-    /// clock.exit_subroutine("subroutine2").expect("The subroutine was not entered before");
+    /// clock.exit_subroutine("subroutine2").expect("The subroutine was not the last one entered");
+    /// clock.exit_subroutine("subroutine1").expect("The subroutine was not the last one entered");
     ///
     /// clock.enter_subroutine("subroutine1");
     /// // Add the time taken since the last time we entered subroutine1
-    /// clock.exit_subroutine("subroutine1").expect("The subroutine was not entered before");
+    /// clock.exit_subroutine("subroutine1").expect("The subroutine was not the last one entered");
+    /// assert_eq!(clock.profile().entries[0].calls, 2);
     /// ```
     pub fn enter_subroutine(&mut self, name: &str) {
-        if self.details.contains_key(name) {
-            // Keep the duration but change the start time
-            let (time, duration) = self.details.get(name).unwrap();
-            if time.is_none() {
-                self.details.insert(name.to_string(), (Some(std::time::Instant::now()), *duration));
-            } else {
-                let new_duration = duration.add(time.unwrap().elapsed());
-                self.details.insert(name.to_string(), (Some(std::time::Instant::now()), new_duration));
-            }
-        } else {
-            self.details.insert(name.to_string(), (Some(std::time::Instant::now()), Duration::new(0, 0)));
-        }
+        self.profiler.enter(name);
     }
 
-    /// Exits a subroutine of the algorithm and add the time taken since the last time it was entered.
-    /// If the subroutine was already exit before, it does nothing.
+    /// Exits the last subroutine entered and adds the time taken since it was entered.
     ///
     /// # Throws
-    /// ClockError if the subroutine was not entered before.
-    pub fn exit_subroutine(&mut self, name: &str) -> Result<(), ClockError>{
-        let (start, duration) = match self.details.get(name) {
-            Some((start, duration)) => (start, duration),
-            None => return Err(ClockError::new("The subroutine was not entered before")),
-        };
-        if !start.is_none() {
-            // If the subroutine is not exit, we exit it
-            let elapsed = start.unwrap().elapsed();
-            self.details.insert(name.to_string(), (None, *duration + elapsed));
-        }
-        Ok(())
+    /// ClockError if `name` is not the last subroutine entered and not exited yet.
+    pub fn exit_subroutine(&mut self, name: &str) -> Result<(), ClockError> {
+        self.profiler.exit(name)
+    }
+
+    /// Enters a subroutine of the algorithm and returns a guard that exits it when dropped. The guard gives access to
+    /// the clock, so the subroutine can enter nested subroutines.
+    ///
+    /// # Example
+    /// ```rust
+    /// use vertex::Clock;
+    ///
+    /// let mut clock = Clock::new(3600);
+    /// {
+    ///     let mut bound = clock.scope("bound");
+    ///     let _matching = bound.scope("matching");
+    ///     // Do something
+    /// }
+    /// let paths: Vec<String> = clock.profile().entries.iter().map(|e| e.path.join(";")).collect();
+    /// assert_eq!(paths, vec!["bound", "bound;matching"]);
+    /// ```
+    pub fn scope(&mut self, name: &'static str) -> ScopeGuard<'_> {
+        self.enter_subroutine(name);
+        ScopeGuard { clock: self, name }
     }
 
     /// Returns the time taken by a subroutine of the algorithm.
     ///
-    /// The time taken is the sum of all the time taken by this subroutine since the first time it was entered.
+    /// The time taken is the sum of all the time taken by this subroutine since the first time it was entered, whatever
+    /// the subroutines it was called from.
     /// If the subroutine was not entered before, it a duration of 0.
     ///
     /// # Example
@@ -416,14 +1201,50 @@ impl Clock {
     /// println!("Percentage of time taken by subroutine1 : {}%", elapsed.as_secs_f64() * 100.0 / clock.get_time().duration.as_secs_f64());
     /// ```
     pub fn get_subroutine_duration(&self, name: &str) -> Duration {
-        if self.details.contains_key(name) {
-            let (_, duration) = self.details.get(name).unwrap();
-            *duration
-        } else {
-            Duration::new(0, 0)
-        }
+        self.profiler.totals().into_iter()
+            .find(|(subroutine, _)| subroutine == name)
+            .map_or(Duration::ZERO, |(_, duration)| duration)
+    }
+
+    /// Returns the names of the subroutines entered so far with the time taken by each of them, sorted by name.
+    pub fn subroutines(&self) -> Vec<(String, Duration)> {
+        self.profiler.totals()
+    }
+
+    /// Returns the profile of the subroutines : their calls and their total and self time in each scope they were
+    /// called from, relative to the time of the clock.
+    pub fn profile(&self) -> Profile {
+        self.profiler.profile(self.get_time().duration)
+    }
+}
+
+/// Guard of a subroutine entered with [`Clock::scope`] : the subroutine is exited when the guard is dropped.
+pub struct ScopeGuard<'a> {
+    clock: &'a mut Clock,
+    name: &'static str,
+}
+
+impl Deref for ScopeGuard<'_> {
+    type Target = Clock;
+
+    fn deref(&self) -> &Clock {
+        self.clock
     }
 }
+
+impl DerefMut for ScopeGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Clock {
+        self.clock
+    }
+}
+
+impl Drop for ScopeGuard<'_> {
+    fn drop(&mut self) {
+        // The nested guards are dropped first, so the subroutine is the last one entered unless it was exited by hand
+        let _ = self.clock.profiler.exit(self.name);
+    }
+}
+
 #[cfg(test)]
 mod algorithms_tests {
     use super::*;
@@ -440,6 +1261,113 @@ mod algorithms_tests {
         graph.add_edge(2, 3, ());
 
         let expected_vertex_cover = 2;
-        assert_eq!(naive_search(&graph, &mut Clock::new(3600)).0, expected_vertex_cover);
+        assert_eq!(naive_search(&graph, &mut Clock::new(3600)).unwrap().0, expected_vertex_cover);
+    }
+
+    #[test]
+    fn test_naive_algorithm_non_contiguous_vertices() {
+        let mut graph = Box::new(UnGraphMap::<u64, ()>::new());
+        graph.add_node(4);
+        graph.add_node(5);
+        graph.add_node(6);
+        graph.add_edge(4, 5, ());
+        graph.add_edge(5, 6, ());
+
+        assert_eq!(naive_search(&graph, &mut Clock::new(3600)).unwrap(), (1, vec![5]));
+    }
+
+    #[test]
+    fn test_empty_workspace() {
+        let dir = std::env::temp_dir().join(format!("mvc_empty_workspace_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ws = Workspace::new(&dir);
+        let graph = graph_utils::load_clq_file("src/resources/graphs/test.clq").unwrap();
+
+        // Without database, the optimal value is unknown
+        let res = solve_problem(&ws, "test.clq", &graph, &branch_and_bound, Problem::Mvc, Limits::seconds(60)).unwrap();
+        assert_eq!((res.value, res.optimal_value, res.is_optimal), (Some(3), None, None));
+
+        // The first update creates the database
+        let database = ws.database_path();
+        InstanceDatabase::update(&database, |db| {
+            db.add_graph("test.clq", "clq", &graph);
+            Ok(())
+        }).unwrap();
+        assert!(InstanceDatabase::load(&database).unwrap().get("test.clq").is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_bounds_at_time_limit() {
+        let ws = Workspace::default();
+        let graph = graph_utils::load_clq_file("src/resources/graphs/test.clq").unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+        let stops = [(Limits::seconds(0), RunStatus::TimeLimit),
+            (Limits::seconds(60).with_node_limit(0), RunStatus::NodeLimit),
+            (Limits::seconds(60).with_cancellation(token), RunStatus::Cancelled)];
+        for ((limits, status), problem) in stops.into_iter().cartesian_product([Problem::Mvc, Problem::Clique, Problem::Mis]) {
+            // The naive search returns no vertex cover when it is stopped before the end
+            let res = solve_problem(&ws, "test.clq", &graph, &naive_search, problem, limits).unwrap();
+            assert_eq!(res.status, status);
+            assert_eq!((res.value, res.set.clone()), (None, Vec::new()), "{} {}", status, problem);
+            let expected = match problem {
+                Problem::Mvc => (2, 5),
+                Problem::Clique => (0, 2),
+                Problem::Mis => (0, 3),
+            };
+            assert_eq!((res.lower_bound, res.upper_bound), expected, "{} {}", status, problem);
+
+            // The text tells why the run stopped and does not judge its value
+            let notice = match status {
+                RunStatus::TimeLimit => "reached the time limit",
+                RunStatus::NodeLimit => "exhausted its node budget",
+                _ => "was cancelled",
+            };
+            let text = res.to_string();
+            assert!(text.contains(notice) && !text.contains("not optimal"), "{}", text);
+        }
+
+        for problem in [Problem::Mvc, Problem::Clique, Problem::Mis] {
+            // The branch and bound may return its initial vertex cover or nothing
+            let res = solve_problem(&ws, "test.clq", &graph, &branch_and_bound, problem, Limits::seconds(0)).unwrap();
+            assert!(res.lower_bound <= res.upper_bound, "{}", problem);
+            assert!(res.value.is_none_or(|v| res.lower_bound <= v && v <= res.upper_bound), "{}", problem);
+            let is_valid = match problem {
+                Problem::Mvc => res.set.is_empty() || graph_utils::is_vertex_cover(&graph, &res.set),
+                Problem::Clique => graph_utils::is_clique(&graph, &res.set),
+                Problem::Mis => graph_utils::is_independent_set(&graph, &res.set),
+            };
+            assert!(is_valid, "{}", problem);
+        }
+
+        let res = solve_problem(&ws, "test.clq", &graph, &naive_search, Problem::Mis, Limits::seconds(0)).unwrap();
+        assert!(res.to_yaml().contains("status: time_limit"));
+        assert!(res.to_yaml().contains("value: null"));
+    }
+
+    #[test]
+    fn test_solver_errors() {
+        let ws = Workspace::default();
+        let graph = graph_utils::load_clq_file("src/resources/graphs/test.clq").unwrap();
+        let res = naive_search(&graph, &mut Clock::with_limits(Limits::seconds(60).with_node_limit(0)));
+        assert!(matches!(res, Err(MvcError::Timeout(StopReason::NodeLimit))));
+
+        // An algorithm that returns a set that is not a vertex cover
+        let wrong = |_: &UnGraphMap<u64, ()>, _: &mut Clock| Ok((1, vec![0]));
+        let res = solve_problem(&ws, "test.clq", &graph, &wrong, Problem::Mvc, Limits::seconds(60));
+        assert!(matches!(res, Err(MvcError::InvalidResult(_, _))));
+    }
+
+    #[test]
+    fn test_csv_row() {
+        let ws = Workspace::default();
+        let graph = graph_utils::load_clq_file("src/resources/graphs/test.clq").unwrap();
+        let mut res = solve_problem(&ws, "test.clq", &graph, &branch_and_bound, Problem::Mvc, Limits::seconds(60)).unwrap();
+        res.graph_id = "a,\"b\"".to_string();
+        let row = res.to_csv_row();
+        assert!(row.starts_with("\"a,\"\"b\"\"\",mvc,3,solved,3,3,true,3,"));
+        assert!(row.ends_with(",0 4 2"));
+        assert_eq!(MVCResult::CSV_HEADER.split(',').count(), 12);
     }
 }
\ No newline at end of file
@@ -0,0 +1,268 @@
+//! Progress of the algorithms.
+//!
+//! The clock of a run notifies an [`Observer`] (see [`crate::Limits::with_observer`]) when the run starts, when the
+//! algorithm finds a better vertex cover or proves a better lower bound, periodically while it searches and when it
+//! ends. The values are those of the vertex cover searched by the algorithm : for the maximum clique, the vertex cover
+//! of the complement of the graph. The `mvc` command also notifies it when the graph is loaded from its file.
+//!
+//! Without observer, the library prints nothing. [`ProgressLine`] draws a progress line on a terminal.
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::csr::LoadStats;
+use crate::graph_utils::GraphStats;
+use crate::StopReason;
+use crate::database::RunStatus;
+
+/// Default time between 2 heartbeats.
+pub const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// State of a run when an event occurs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub elapsed: Duration,
+    /// Number of search nodes explored so far
+    pub nodes: u64,
+    /// Size of the smallest vertex cover found so far
+    pub incumbent: Option<u64>,
+    /// Best lower bound on the size of a minimum vertex cover proved so far
+    pub lower_bound: Option<u64>,
+}
+
+impl Progress {
+    /// Returns the number of nodes explored per second.
+    pub fn nodes_per_sec(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.nodes as f64 / secs,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the relative gap between the incumbent and the lower bound (0 once the incumbent is proved optimal).
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use vertex::observer::Progress;
+    ///
+    /// let progress = Progress { elapsed: Duration::from_secs(2), nodes: 100, incumbent: Some(20), lower_bound: Some(15) };
+    /// assert_eq!(progress.gap(), Some(0.25));
+    /// assert_eq!(progress.nodes_per_sec(), 50.0);
+    /// ```
+    pub fn gap(&self) -> Option<f64> {
+        match (self.incumbent, self.lower_bound) {
+            (Some(0), Some(_)) => Some(0.0),
+            (Some(ub), Some(lb)) => Some(ub.saturating_sub(lb) as f64 / ub as f64),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = |v: Option<u64>| v.map_or("-".to_string(), |v| v.to_string());
+        write!(f, "{:.1}s, {} nodes ({:.0} nodes/s), best {}, lower bound {}", self.elapsed.as_secs_f64(),
+               self.nodes, self.nodes_per_sec(), value(self.incumbent), value(self.lower_bound))?;
+        if let Some(gap) = self.gap() {
+            write!(f, ", gap {:.1}%", 100.0 * gap)?;
+        }
+        Ok(())
+    }
+}
+
+/// Receives the events of a run. Every method does nothing by default.
+///
+/// An observer can be shared by the threads of a portfolio, hence the methods take `&self`.
+pub trait Observer: Send + Sync {
+    /// Time between 2 calls to [`Observer::on_heartbeat`].
+    fn heartbeat_interval(&self) -> Duration {
+        DEFAULT_HEARTBEAT
+    }
+
+    /// Called when the graph of the run is loaded from a file, with the statistics of the loading.
+    fn on_load(&self, _stats: &LoadStats) {}
+
+    /// Called before the algorithm runs, with the statistics of the graph it runs on.
+    fn on_start(&self, _stats: &GraphStats) {}
+
+    /// Called when the algorithm finds a smaller vertex cover.
+    fn on_incumbent(&self, _progress: &Progress) {}
+
+    /// Called when the algorithm proves a better lower bound.
+    fn on_lower_bound(&self, _progress: &Progress) {}
+
+    /// Called periodically while the algorithm searches.
+    fn on_heartbeat(&self, _progress: &Progress) {}
+
+    /// Called when the algorithm ends, with the limit that stopped it (None if it proved that its solution is optimal).
+    fn on_finish(&self, _progress: &Progress, _stop_reason: Option<StopReason>) {}
+}
+
+/// Observer that ignores every event, the default of the runs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuietObserver;
+
+impl Observer for QuietObserver {}
+
+/// Observer that draws the progress of the run on a single line of a terminal, rewritten at each event.
+///
+/// # Example
+/// ```rust
+/// use std::sync::Arc;
+/// use vertex::{branch_and_bound, Clock, Limits};
+/// use vertex::graph_utils::load_clq_file;
+/// use vertex::observer::ProgressLine;
+///
+/// let graph = load_clq_file("src/resources/graphs/test.clq").unwrap();
+/// let mut clock = Clock::with_limits(Limits::seconds(60).with_observer(Arc::new(ProgressLine::stderr())));
+/// branch_and_bound(&graph, &mut clock).unwrap();
+/// ```
+pub struct ProgressLine {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl ProgressLine {
+    /// Draws the progress line on the standard error.
+    pub fn stderr() -> ProgressLine {
+        ProgressLine::new(Box::new(io::stderr()))
+    }
+
+    pub fn new(writer: Box<dyn Write + Send>) -> ProgressLine {
+        ProgressLine { writer: Mutex::new(writer) }
+    }
+
+    fn write(&self, line: &str) {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        // The progress is only informative : a closed terminal must not stop the run
+        let _ = write!(writer, "{}", line).and_then(|_| writer.flush());
+    }
+}
+
+impl Observer for ProgressLine {
+    fn on_load(&self, stats: &LoadStats) {
+        self.write(&format!("Graph loaded : {}\n", stats));
+    }
+
+    fn on_start(&self, stats: &GraphStats) {
+        self.write(&format!("Solving a graph of order {}, size {} and density {:.4}\n", stats.order, stats.size,
+                            stats.density));
+    }
+
+    fn on_incumbent(&self, progress: &Progress) {
+        self.on_heartbeat(progress);
+    }
+
+    fn on_lower_bound(&self, progress: &Progress) {
+        self.on_heartbeat(progress);
+    }
+
+    fn on_heartbeat(&self, progress: &Progress) {
+        // Clears the end of the previous line
+        self.write(&format!("\r{}\x1b[K", progress));
+    }
+
+    fn on_finish(&self, progress: &Progress, stop_reason: Option<StopReason>) {
+        let status = stop_reason.map_or(RunStatus::Solved, RunStatus::from);
+        self.write(&format!("\r{} : {}\x1b[K\n", progress, status));
+    }
+}
+
+#[cfg(test)]
+mod observer_tests {
+    use std::sync::Arc;
+
+    use crate::{branch_and_bound, Clock, Limits, naive_search};
+    use crate::graph_utils::load_clq_file;
+
+    use super::*;
+
+    /// Records the events of a run.
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl Observer for Recorder {
+        fn heartbeat_interval(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        fn on_incumbent(&self, progress: &Progress) {
+            self.events.lock().unwrap().push(format!("incumbent {}", progress.incumbent.unwrap()));
+        }
+
+        fn on_lower_bound(&self, progress: &Progress) {
+            self.events.lock().unwrap().push(format!("lower bound {}", progress.lower_bound.unwrap()));
+        }
+
+        fn on_heartbeat(&self, _progress: &Progress) {
+            self.events.lock().unwrap().push("heartbeat".to_string());
+        }
+
+        fn on_finish(&self, progress: &Progress, stop_reason: Option<StopReason>) {
+            self.events.lock().unwrap().push(format!("finish {:?} {:?}", stop_reason, progress.gap()));
+        }
+    }
+
+    #[test]
+    fn test_events_of_branch_and_bound() {
+        let graph = load_clq_file("src/resources/graphs/queen5_5.clq").unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let mut clock = Clock::with_limits(Limits::seconds(60).with_observer(recorder.clone()));
+        branch_and_bound(&graph, &mut clock).unwrap();
+        clock.notify_finish();
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(events[0], "incumbent 25");
+        assert!(events.iter().any(|e| e.starts_with("lower bound")));
+        assert!(events.contains(&"incumbent 20".to_string()));
+        assert!(events.iter().any(|e| e == "heartbeat"));
+        assert_eq!(events.last().unwrap(), "finish None Some(0.0)");
+        // The incumbents decrease
+        let incumbents: Vec<u64> = events.iter().filter_map(|e| e.strip_prefix("incumbent ")?.parse().ok()).collect();
+        assert!(incumbents.windows(2).all(|w| w[0] > w[1]));
+    }
+
+    #[test]
+    fn test_lower_bounds_of_naive_search() {
+        let graph = load_clq_file("src/resources/graphs/test.clq").unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let mut clock = Clock::with_limits(Limits::seconds(60).with_observer(recorder.clone()));
+        naive_search(&graph, &mut clock).unwrap();
+
+        let events = recorder.events.lock().unwrap();
+        let lower_bounds: Vec<&String> = events.iter().filter(|e| e.starts_with("lower bound")).collect();
+        assert_eq!(lower_bounds, vec!["lower bound 1", "lower bound 2", "lower bound 3"]);
+        assert_eq!(events.iter().rfind(|e| e.starts_with("incumbent")).unwrap(), "incumbent 3");
+    }
+
+    /// Writer whose content can be read after it is given to a progress line.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_progress_line() {
+        let buffer = Buffer::default();
+        let line = ProgressLine::new(Box::new(buffer.clone()));
+        let progress = Progress { elapsed: Duration::from_secs(1), nodes: 10, incumbent: Some(4), lower_bound: Some(3) };
+        line.on_heartbeat(&progress);
+        line.on_finish(&progress, Some(StopReason::NodeLimit));
+
+        let content = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(content, "\r1.0s, 10 nodes (10 nodes/s), best 4, lower bound 3, gap 25.0%\x1b[K\
+                             \r1.0s, 10 nodes (10 nodes/s), best 4, lower bound 3, gap 25.0% : node limit\x1b[K\n");
+    }
+}
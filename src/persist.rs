@@ -0,0 +1,328 @@
+//! Crash-safe and concurrency-safe writing of the result files.
+//!
+//! A file is never truncated in place : the new content is written in a temporary file next to it,
+//! flushed to the disk and then renamed over the original file, which is atomic. Read-modify-write
+//! cycles are protected by an advisory lock file (`<file>.lock`) so that several processes (for example
+//! parallel benchmark runs) can update the same file without losing each other's changes.
+//!
+//! If a process crashes before renaming its temporary file, the file is left untouched.
+//! [`recover`] removes these leftovers, and restores a temporary file if the original is missing or corrupted.
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+use crate::errors::YamlError;
+
+/// Maximum time spent waiting for a lock before giving up.
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+/// Age after which a lock file is considered as left by a crashed process.
+/// The locks are only held during a read-modify-write cycle, which takes milliseconds.
+pub const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+
+const TMP_SUFFIX: &str = ".tmp-";
+/// Distinguishes the temporary files of the threads of a process.
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the path of the lock file of a file.
+pub fn lock_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.lock", path.display()))
+}
+
+/// Writes a file atomically : readers see either the old content or the new one, never a partial file.
+///
+/// # Throws
+/// io::Error if the temporary file cannot be written or renamed.
+///
+/// # Example
+/// ```rust
+/// use vertex::persist::write_atomic;
+///
+/// let path = std::env::temp_dir().join("mvc_write_atomic_example.txt");
+/// write_atomic(&path, b"first").unwrap();
+/// write_atomic(&path, b"second").unwrap();
+/// assert_eq!(std::fs::read(&path).unwrap(), b"second");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let tmp = PathBuf::from(format!("{}{}{}-{}", path.display(), TMP_SUFFIX, std::process::id(),
+                                    TMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let result = (|| {
+        let mut file = File::create(&tmp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Advisory lock on a file, released when dropped.
+///
+/// The lock is a `<file>.lock` file created exclusively. It only protects against the processes that also use it.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::persist::FileLock;
+///
+/// let path = std::env::temp_dir().join("mvc_lock_example.yml");
+/// let lock = FileLock::acquire(&path).unwrap();
+/// // A second lock cannot be taken while the first one is held
+/// assert!(FileLock::acquire_with_timeout(&path, Duration::from_millis(50)).is_err());
+/// drop(lock);
+/// assert!(FileLock::acquire_with_timeout(&path, Duration::from_millis(50)).is_ok());
+/// ```
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Acquires the lock of a file, waiting at most [`LOCK_TIMEOUT`].
+    ///
+    /// # Throws
+    /// io::Error with the kind TimedOut if the lock is still held after the timeout.
+    pub fn acquire(path: &Path) -> io::Result<FileLock> {
+        FileLock::acquire_with_timeout(path, LOCK_TIMEOUT)
+    }
+
+    /// Acquires the lock of a file, waiting at most the given duration.
+    ///
+    /// A lock file older than [`STALE_LOCK_AGE`] is considered as left by a crashed process and is removed. The lock
+    /// must therefore not be held longer : the costly computations have to be done before acquiring it.
+    ///
+    /// # Throws
+    /// io::Error with the kind TimedOut if the lock is still held after the timeout.
+    pub fn acquire_with_timeout(path: &Path, timeout: Duration) -> io::Result<FileLock> {
+        let lock = lock_path(path);
+        let start = SystemTime::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&lock) {
+                Ok(mut file) => {
+                    // The pid is only written to help debugging a lock that is never released
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(FileLock { path: lock });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if is_stale(&lock) && break_stale_lock(&lock) {
+                        continue;
+                    }
+                    if start.elapsed().unwrap_or_default() >= timeout {
+                        return Err(io::Error::new(io::ErrorKind::TimedOut,
+                                                  format!("Timeout while waiting for the lock {:?}", lock)));
+                    }
+                    sleep(Duration::from_millis(5));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Removes a stale lock file. Returns true if this call removed it.
+///
+/// Removing the file after checking its age would delete the lock of a process which broke it in between. The lock
+/// is moved to a unique name instead : the rename is atomic, so only one process can take the stale file, and its
+/// age is checked again once it cannot be replaced anymore. A live lock taken by mistake is put back with a hard
+/// link, which fails instead of overwriting a lock created in the meantime.
+fn break_stale_lock(lock: &Path) -> bool {
+    let taken = PathBuf::from(format!("{}.stale-{}-{}", lock.display(), std::process::id(),
+                                      TMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+    if std::fs::rename(lock, &taken).is_err() {
+        // Another process broke or released the lock first
+        return false;
+    }
+    let stale = is_stale(&taken);
+    if !stale {
+        let _ = std::fs::hard_link(&taken, lock);
+    }
+    let _ = std::fs::remove_file(&taken);
+    stale
+}
+
+fn is_stale(lock: &Path) -> bool {
+    std::fs::metadata(lock)
+        .and_then(|m| m.modified())
+        .map(|modified| modified.elapsed().unwrap_or_default() > STALE_LOCK_AGE)
+        .unwrap_or(false)
+}
+
+/// Checks a file for the leftovers of an interrupted write.
+///
+/// The temporary files left next to the file are removed. If the file is missing or its content is not
+/// valid according to `is_valid`, it is replaced by the most recent valid temporary file (if any).
+/// Returns true if the file was restored from a temporary file.
+///
+/// It must be called with the lock of the file held, otherwise the temporary file of a running write could be removed.
+///
+/// # Throws
+/// io::Error if the folder of the file cannot be read or a temporary file cannot be renamed.
+pub fn recover<F: Fn(&[u8]) -> bool>(path: &Path, is_valid: F) -> io::Result<bool> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let prefix = format!("{}{}", path.file_name().unwrap_or_default().to_string_lossy(), TMP_SUFFIX);
+
+    let mut leftovers: Vec<(SystemTime, PathBuf)> = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+            leftovers.push((modified, entry.path()));
+        }
+    }
+    if leftovers.is_empty() {
+        return Ok(false);
+    }
+    // Most recent first
+    leftovers.sort_by_key(|l| std::cmp::Reverse(l.0));
+
+    let current_is_valid = std::fs::read(path).map(|c| is_valid(&c)).unwrap_or(false);
+    let mut restored = false;
+    for (_, tmp) in leftovers {
+        if !current_is_valid && !restored && std::fs::read(&tmp).map(|c| is_valid(&c)).unwrap_or(false) {
+            std::fs::rename(&tmp, path)?;
+            restored = true;
+        } else {
+            std::fs::remove_file(&tmp)?;
+        }
+    }
+    Ok(restored)
+}
+
+/// Acquires the lock of a YAML file and recovers it from an interrupted write if needed.
+///
+/// # Throws
+/// YamlError::IoError if the lock cannot be acquired or the recovery fails.
+pub(crate) fn lock_yaml(path: &Path) -> Result<FileLock, YamlError> {
+    let lock = match FileLock::acquire(path) {
+        Ok(lock) => lock,
+        Err(e) => return Err(YamlError::IoError(format!("unable to lock file {:?}", path), e))
+    };
+    if let Err(e) = recover(path, |c| serde_yaml::from_slice::<serde_yaml::Value>(c).is_ok()) {
+        return Err(YamlError::IoError(format!("unable to recover file {:?}", path), e));
+    }
+    Ok(lock)
+}
+
+/// Writes a YAML file atomically (see [`write_atomic`]).
+///
+/// # Throws
+/// YamlError::IoError if the file cannot be written.
+pub(crate) fn write_yaml(path: &Path, content: &str) -> Result<(), YamlError> {
+    match write_atomic(path, content.as_bytes()) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(YamlError::IoError(format!("unable to write file {:?}", path), e))
+    }
+}
+
+#[cfg(test)]
+mod persist_tests {
+    use std::sync::{Arc, Barrier};
+
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mvc_persist_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_recover_restores_missing_file() {
+        let dir = test_dir("recover");
+        let path = dir.join("data.yml");
+        std::fs::write(dir.join("data.yml.tmp-1"), b"valid").unwrap();
+        std::fs::write(dir.join("data.yml.tmp-2"), b"partial").unwrap();
+
+        assert!(recover(&path, |c| c == b"valid").unwrap());
+        assert_eq!(std::fs::read(&path).unwrap(), b"valid");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        // A valid file is kept and the leftovers are removed
+        std::fs::write(dir.join("data.yml.tmp-3"), b"valid").unwrap();
+        assert!(!recover(&path, |c| c == b"valid").unwrap());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stale_lock_is_removed() {
+        let dir = test_dir("stale");
+        let path = dir.join("data.yml");
+        let lock = lock_path(&path);
+        std::fs::write(&lock, b"12345").unwrap();
+        let old = SystemTime::now() - STALE_LOCK_AGE - Duration::from_secs(1);
+        File::options().write(true).open(&lock).unwrap().set_modified(old).unwrap();
+
+        let acquired = FileLock::acquire_with_timeout(&path, Duration::from_millis(50)).unwrap();
+        drop(acquired);
+        assert!(!lock.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_live_lock_is_not_broken() {
+        let dir = test_dir("live");
+        let path = dir.join("data.yml");
+        let lock = lock_path(&path);
+        let held = FileLock::acquire(&path).unwrap();
+
+        // The lock was taken after another process found a stale one
+        assert!(!break_stale_lock(&lock));
+        assert!(lock.exists());
+        assert!(FileLock::acquire_with_timeout(&path, Duration::from_millis(20)).is_err());
+        drop(held);
+
+        // Only one of the processes which found a stale lock removes it
+        std::fs::write(&lock, b"12345").unwrap();
+        let old = SystemTime::now() - STALE_LOCK_AGE - Duration::from_secs(1);
+        File::options().write(true).open(&lock).unwrap().set_modified(old).unwrap();
+        assert!(break_stale_lock(&lock));
+        assert!(!break_stale_lock(&lock));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lock_serializes_updates() {
+        let dir = test_dir("concurrent");
+        let path = dir.join("counter.txt");
+        write_atomic(&path, b"0").unwrap();
+
+        let threads = 8;
+        let barrier = Arc::new(Barrier::new(threads));
+        let handles: Vec<_> = (0..threads).map(|_| {
+            let path = path.clone();
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..10 {
+                    let _lock = FileLock::acquire(&path).unwrap();
+                    let value: u64 = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+                    std::fs::write(&path, (value + 1).to_string()).unwrap();
+                }
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "80");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
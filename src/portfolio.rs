@@ -0,0 +1,153 @@
+//! Portfolio of solvers : several algorithms race on the same graph, each on its own thread.
+//!
+//! The first algorithm that proves the optimality of its vertex cover (it ends its search without being stopped by a
+//! limit) cancels the others with a shared [`crate::CancellationToken`]. The cancelled algorithms return the best
+//! vertex cover they found so far.
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use petgraph::prelude::UnGraphMap;
+
+use crate::{Clock, Limits, StopReason};
+use crate::errors::MvcError;
+use crate::graph_utils::is_vertex_cover;
+
+/// Algorithm that can be run on a thread of a portfolio (see [`crate::Algorithm`]).
+pub type SyncAlgorithm = dyn Fn(&UnGraphMap<u64, ()>, &mut Clock) -> Result<(u64, Vec<u64>), MvcError> + Sync;
+
+/// Run of an algorithm of the portfolio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioRun {
+    pub name: String,
+    /// Size of the vertex cover found
+    pub value: u64,
+    pub cover: Vec<u64>,
+    /// Limit that stopped the algorithm (None if it proved that its vertex cover is minimum)
+    pub stop_reason: Option<StopReason>,
+    pub time: Duration,
+    /// Number of search nodes explored by the algorithm
+    pub nodes: u64,
+}
+
+/// Runs of a portfolio, in the order of the algorithms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioResult {
+    pub runs: Vec<PortfolioRun>,
+    /// Index of the first algorithm that proved the optimality of its vertex cover
+    pub winner: Option<usize>,
+    /// Index of the best run : the winner, or else the run with the smallest vertex cover
+    pub best: Option<usize>,
+}
+
+impl PortfolioResult {
+    /// Returns the best run (see [`PortfolioResult::best`]).
+    pub fn best_run(&self) -> Option<&PortfolioRun> {
+        self.best.map(|i| &self.runs[i])
+    }
+}
+
+/// Races several algorithms on a graph, each on its own thread with its own clock.
+///
+/// Every algorithm gets the limits given. The algorithms share the cancellation token of the limits (or a new one) :
+/// cancelling it cancels the whole portfolio, and it is cancelled when an algorithm proves that its vertex cover is
+/// minimum. The solutions of the stopped algorithms are only kept as candidates for the best run if they are
+/// vertex covers. An algorithm stopped before finding a solution (MvcError::Timeout) has an empty cover.
+///
+/// # Throws
+/// - MvcError::ThreadFailure if one of the algorithms panics
+/// - the first other error returned by an algorithm, in the order of the algorithms
+///
+/// # Example
+/// ```rust
+/// use vertex::{branch_and_bound, Limits, naive_search};
+/// use vertex::graph_utils::load_clq_file;
+/// use vertex::portfolio::race;
+///
+/// let graph = load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+/// let result = race(&graph, &[("bnb", &branch_and_bound), ("naive_search", &naive_search)], Limits::seconds(60))
+///     .unwrap();
+///
+/// // The branch and bound proves the optimality long before the naive search
+/// assert_eq!(result.winner, Some(0));
+/// assert_eq!(result.best_run().unwrap().value, 12);
+/// assert!(result.runs[1].stop_reason.is_some());
+/// ```
+pub fn race(graph: &UnGraphMap<u64, ()>, algorithms: &[(&str, &SyncAlgorithm)], limits: Limits)
+            -> Result<PortfolioResult, MvcError> {
+    let token = limits.cancellation.clone().unwrap_or_default();
+    let winner: Mutex<Option<usize>> = Mutex::new(None);
+
+    let runs: Result<Vec<PortfolioRun>, MvcError> = thread::scope(|scope| {
+        let handles: Vec<_> = algorithms.iter().enumerate().map(|(i, &(name, f))| {
+            let limits = limits.clone().with_cancellation(token.clone());
+            let (token, winner) = (&token, &winner);
+            scope.spawn(move || {
+                let mut clock = Clock::with_limits(limits);
+                let (value, cover) = match f(graph, &mut clock) {
+                    Err(MvcError::Timeout(_)) => (0, Vec::new()),
+                    res => res?,
+                };
+                clock.stop_timer();
+                let stop_reason = clock.stop_reason();
+                if stop_reason.is_none() {
+                    let mut winner = winner.lock().unwrap_or_else(|e| e.into_inner());
+                    if winner.is_none() {
+                        *winner = Some(i);
+                        token.cancel();
+                    }
+                }
+                Ok(PortfolioRun {
+                    name: name.to_string(),
+                    value,
+                    cover,
+                    stop_reason,
+                    time: clock.get_time().duration,
+                    nodes: clock.nodes(),
+                })
+            })
+        }).collect();
+        handles.into_iter().zip(algorithms).map(|(h, (name, _))| h.join().unwrap_or_else(|_| Err(
+            MvcError::ThreadFailure(format!("The algorithm {} of the portfolio panicked", name))))).collect()
+    });
+    let runs = runs?;
+
+    let winner = winner.into_inner().unwrap_or_else(|e| e.into_inner());
+    let best = winner.or_else(|| runs.iter().enumerate()
+        .filter(|(_, run)| is_vertex_cover(graph, &run.cover))
+        .min_by_key(|(_, run)| run.value)
+        .map(|(i, _)| i));
+    Ok(PortfolioResult { runs, winner, best })
+}
+
+#[cfg(test)]
+mod portfolio_tests {
+    use crate::{branch_and_bound, CancellationToken, naive_search};
+    use crate::graph_utils::load_clq_file;
+
+    use super::*;
+
+    #[test]
+    fn test_cancelled_portfolio() {
+        let graph = load_clq_file("src/resources/graphs/keller4.clq").unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+        let limits = Limits::seconds(3600).with_cancellation(token);
+        let result = race(&graph, &[("bnb", &branch_and_bound), ("naive_search", &naive_search)], limits).unwrap();
+
+        assert_eq!(result.winner, None);
+        assert!(result.runs.iter().all(|run| run.stop_reason == Some(StopReason::Cancelled)));
+        // The naive search has no vertex cover when it is stopped, the branch and bound returns every vertex
+        assert_eq!(result.best, Some(0));
+        assert_eq!(result.best_run().unwrap().value, graph.node_count() as u64);
+    }
+
+    #[test]
+    fn test_node_limit_in_portfolio() {
+        let graph = load_clq_file("src/resources/graphs/test.clq").unwrap();
+        let result = race(&graph, &[("bnb", &branch_and_bound)], Limits::seconds(60).with_node_limit(1)).unwrap();
+        assert_eq!(result.winner, None);
+        assert_eq!(result.runs[0].stop_reason, Some(StopReason::NodeLimit));
+        assert_eq!(result.runs[0].nodes, 1);
+    }
+}
@@ -0,0 +1,277 @@
+//! Hierarchical profiler of the subroutines of an algorithm.
+//!
+//! The subroutines are nested scopes : a scope entered while another one is open is its child, and the same
+//! subroutine called from 2 different scopes is profiled twice. Each scope counts its calls, its total time and its self
+//! time (the total time minus the time spent in its children).
+//!
+//! The algorithms open scopes with [`crate::Clock::scope`], whose guard closes the scope when dropped, or with
+//! [`crate::Clock::enter_subroutine`] and [`crate::Clock::exit_subroutine`].
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Serializer};
+
+use crate::errors::ClockError;
+
+/// Calls and time of a scope.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ScopeStats {
+    calls: u64,
+    total: Duration,
+}
+
+/// Records the time spent in nested scopes.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::profiler::Profiler;
+///
+/// let mut profiler = Profiler::new();
+/// profiler.enter("search");
+/// for _ in 0..3 {
+///     profiler.enter("bound");
+///     profiler.exit("bound").unwrap();
+/// }
+/// profiler.exit("search").unwrap();
+///
+/// let profile = profiler.profile(Duration::from_secs(1));
+/// assert_eq!(profile.entries[1].path, vec!["search", "bound"]);
+/// assert_eq!(profile.entries[1].calls, 3);
+/// // Scopes must be exited in the reverse order they were entered
+/// assert!(profiler.exit("search").is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    /// Stats of each scope, by path from the outermost scope
+    scopes: BTreeMap<Vec<String>, ScopeStats>,
+    /// Open scopes, the innermost last
+    stack: Vec<(String, Instant)>,
+}
+
+impl Profiler {
+    pub fn new() -> Profiler {
+        Default::default()
+    }
+
+    /// Opens a scope, as a child of the innermost open scope.
+    pub fn enter(&mut self, name: &str) {
+        self.stack.push((name.to_string(), Instant::now()));
+    }
+
+    /// Closes the innermost open scope and adds its call and its time to the profile.
+    ///
+    /// # Throws
+    /// ClockError if the innermost open scope is not `name`. The open scopes are left unchanged.
+    pub fn exit(&mut self, name: &str) -> Result<(), ClockError> {
+        match self.stack.last() {
+            Some((last, _)) if last == name => {}
+            Some((last, _)) => return Err(ClockError::new(
+                &format!("The subroutine {:?} is exited while {:?} is still running", name, last))),
+            None => return Err(ClockError::new(&format!("The subroutine {:?} was not entered before", name))),
+        }
+        let path: Vec<String> = self.stack.iter().map(|(name, _)| name.clone()).collect();
+        let (_, start) = self.stack.pop().unwrap_or_else(|| unreachable!());
+        let stats = self.scopes.entry(path).or_default();
+        stats.calls += 1;
+        stats.total += start.elapsed();
+        Ok(())
+    }
+
+    /// Returns the names of the open scopes, the innermost last.
+    pub fn open_scopes(&self) -> Vec<&str> {
+        self.stack.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Returns the total time spent in each subroutine, whatever its parents, sorted by name.
+    ///
+    /// The time of a subroutine called from itself is only counted once.
+    pub fn totals(&self) -> Vec<(String, Duration)> {
+        let mut totals: BTreeMap<&str, Duration> = BTreeMap::new();
+        for (path, stats) in &self.scopes {
+            let (name, parents) = path.split_last().unwrap_or_else(|| unreachable!());
+            if !parents.contains(name) {
+                *totals.entry(name).or_default() += stats.total;
+            }
+        }
+        totals.into_iter().map(|(name, total)| (name.to_string(), total)).collect()
+    }
+
+    /// Returns the profile of the closed scopes, for a run that lasted `total`.
+    pub fn profile(&self, total: Duration) -> Profile {
+        let entries = self.scopes.iter().map(|(path, stats)| {
+            // The direct children of the scope follow it in the map
+            let children: Duration = self.scopes.range(path.clone()..)
+                .skip(1)
+                .take_while(|(child, _)| child.starts_with(path))
+                .filter(|(child, _)| child.len() == path.len() + 1)
+                .map(|(_, child)| child.total)
+                .sum();
+            ProfileEntry {
+                path: path.clone(),
+                calls: stats.calls,
+                total: stats.total,
+                self_time: stats.total.saturating_sub(children),
+            }
+        }).collect();
+        Profile { total, entries }
+    }
+}
+
+fn serialize_seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Profile of a scope.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileEntry {
+    /// Names of the scope and of its parents, from the outermost one
+    pub path: Vec<String>,
+    pub calls: u64,
+    /// Time spent in the scope (in seconds once serialized)
+    #[serde(serialize_with = "serialize_seconds")]
+    pub total: Duration,
+    /// Time spent in the scope but not in its children (in seconds once serialized)
+    #[serde(serialize_with = "serialize_seconds")]
+    pub self_time: Duration,
+}
+
+/// Profile of a run : its scopes in depth-first order, each parent before its children.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Profile {
+    /// Duration of the run (in seconds once serialized)
+    #[serde(serialize_with = "serialize_seconds")]
+    pub total: Duration,
+    pub entries: Vec<ProfileEntry>,
+}
+
+impl Profile {
+    /// Returns a table of the scopes, indented by depth, with their calls and their total and self time (also as
+    /// percentages of the run).
+    ///
+    /// # Example
+    /// ```rust
+    /// use vertex::{branch_and_bound, Clock};
+    /// use vertex::graph_utils::load_clq_file;
+    ///
+    /// let graph = load_clq_file("src/resources/graphs/myciel4.clq").unwrap();
+    /// let mut clock = Clock::new(60);
+    /// branch_and_bound(&graph, &mut clock).unwrap();
+    /// clock.stop_timer();
+    ///
+    /// let report = clock.profile().report();
+    /// assert!(report.lines().any(|line| line.starts_with("copy")));
+    /// assert!(report.lines().any(|line| line.starts_with("lower_bound")));
+    /// assert!(report.lines().any(|line| line.starts_with("  deg_lb")));
+    /// ```
+    pub fn report(&self) -> String {
+        let percent = |d: Duration| match self.total.as_secs_f64() {
+            total if total > 0.0 => 100.0 * d.as_secs_f64() / total,
+            _ => 0.0,
+        };
+        let width = self.entries.iter()
+            .map(|e| 2 * (e.path.len() - 1) + e.path.last().map_or(0, |name| name.len()))
+            .max()
+            .unwrap_or(0)
+            .max("subroutine".len());
+        let mut report = format!("{:<width$} {:>10} {:>12} {:>7} {:>12} {:>7}\n", "subroutine", "calls", "total",
+                                 "%", "self", "%", width = width);
+        for e in &self.entries {
+            let name = format!("{}{}", "  ".repeat(e.path.len() - 1), e.path.last().map_or("", |n| n.as_str()));
+            let _ = writeln!(report, "{:<width$} {:>10} {:>11.6}s {:>6.2}% {:>11.6}s {:>6.2}%", name, e.calls,
+                             e.total.as_secs_f64(), percent(e.total), e.self_time.as_secs_f64(),
+                             percent(e.self_time), width = width);
+        }
+        report
+    }
+
+    /// Returns the profile in the folded stacks format of the flame graph tools : one line per scope with its path
+    /// under a `root` frame and its self time in microseconds. The root frame holds the time of the run spent outside
+    /// of any scope.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use vertex::profiler::{Profile, ProfileEntry};
+    ///
+    /// let entry = |path: &[&str], total, self_time| ProfileEntry {
+    ///     path: path.iter().map(|s| s.to_string()).collect(),
+    ///     calls: 1,
+    ///     total: Duration::from_micros(total),
+    ///     self_time: Duration::from_micros(self_time),
+    /// };
+    /// let profile = Profile {
+    ///     total: Duration::from_micros(100),
+    ///     entries: vec![entry(&["bound"], 60, 20), entry(&["bound", "clq_lb"], 40, 40)],
+    /// };
+    /// assert_eq!(profile.to_folded("bnb"), "bnb 40\nbnb;bound 20\nbnb;bound;clq_lb 40\n");
+    /// ```
+    pub fn to_folded(&self, root: &str) -> String {
+        let outermost: Duration = self.entries.iter().filter(|e| e.path.len() == 1).map(|e| e.total).sum();
+        let mut folded = format!("{} {}\n", root, self.total.saturating_sub(outermost).as_micros());
+        for e in &self.entries {
+            let _ = writeln!(folded, "{};{} {}", root, e.path.join(";"), e.self_time.as_micros());
+        }
+        folded
+    }
+}
+
+#[cfg(test)]
+mod profiler_tests {
+    use std::thread::sleep;
+
+    use super::*;
+
+    #[test]
+    fn test_self_and_total_time() {
+        let mut profiler = Profiler::new();
+        profiler.enter("a");
+        sleep(Duration::from_millis(10));
+        profiler.enter("b");
+        sleep(Duration::from_millis(20));
+        profiler.exit("b").unwrap();
+        profiler.enter("b");
+        profiler.exit("b").unwrap();
+        profiler.exit("a").unwrap();
+        profiler.enter("b");
+        profiler.exit("b").unwrap();
+
+        let profile = profiler.profile(Duration::from_millis(40));
+        let paths: Vec<String> = profile.entries.iter().map(|e| e.path.join(";")).collect();
+        assert_eq!(paths, vec!["a", "a;b", "b"]);
+        let (a, ab) = (&profile.entries[0], &profile.entries[1]);
+        assert_eq!((a.calls, ab.calls), (1, 2));
+        assert_eq!(a.self_time, a.total - ab.total);
+        assert!(a.self_time >= Duration::from_millis(10) && ab.total >= Duration::from_millis(20));
+
+        // The totals of b add up its calls in a and outside of a
+        let totals = profiler.totals();
+        assert_eq!(totals[1], ("b".to_string(), ab.total + profile.entries[2].total));
+    }
+
+    #[test]
+    fn test_recursive_scopes() {
+        let mut profiler = Profiler::new();
+        profiler.enter("f");
+        profiler.enter("f");
+        profiler.exit("f").unwrap();
+        profiler.exit("f").unwrap();
+        let totals = profiler.totals();
+        assert_eq!(totals.len(), 1);
+        assert_eq!(totals[0].1, profiler.profile(Duration::ZERO).entries[0].total);
+    }
+
+    #[test]
+    fn test_exit_errors() {
+        let mut profiler = Profiler::new();
+        assert!(profiler.exit("a").is_err());
+        profiler.enter("a");
+        profiler.enter("b");
+        assert!(profiler.exit("a").is_err());
+        assert_eq!(profiler.open_scopes(), vec!["a", "b"]);
+        profiler.exit("b").unwrap();
+        profiler.exit("a").unwrap();
+        assert!(profiler.open_scopes().is_empty());
+    }
+}
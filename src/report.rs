@@ -0,0 +1,444 @@
+//! Reports of the runs stored in the instance database.
+//!
+//! A report compares several series of runs (for example one per algorithm, or one per version selected by comment)
+//! on the instances of the database. It can be rendered as a Markdown table, a standalone HTML page, and two SVG plots
+//! drawn without external tools :
+//! * a cactus plot : for each series, the time needed to solve the instances, sorted by time
+//! * a performance profile (Dolan and Moré) : for each series, the fraction of the instances solved within a factor τ
+//!   of the time of the best series
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::compare::RunSelector;
+use crate::database::{InstanceDatabase, RunRecord, RunStatus};
+
+/// Colors of the series in the plots.
+const COLORS: [&str; 8] = ["#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f"];
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 400.0;
+const MARGIN: f64 = 60.0;
+
+/// Set of runs compared in a report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    /// Name of the series in the tables and the plots
+    pub label: String,
+    pub selector: RunSelector,
+}
+
+impl Series {
+    pub fn new(label: &str, selector: RunSelector) -> Series {
+        Series {
+            label: label.to_string(),
+            selector,
+        }
+    }
+}
+
+/// Line of a report.
+#[derive(Debug, Clone)]
+pub struct ReportRow {
+    pub id: String,
+    pub order: usize,
+    pub density: f64,
+    pub known_mvc: Option<u64>,
+    pub known_clique: Option<u64>,
+    /// Last run of each series on the instance
+    pub runs: Vec<Option<RunRecord>>,
+}
+
+/// Report of the runs of several series on the instances of the database.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use vertex::database::{InstanceDatabase, InstanceRecord, Problem, RunRecord};
+/// use vertex::report::Report;
+///
+/// let mut db = InstanceDatabase::new();
+/// let mut record = InstanceRecord::new("a.clq", "clq", 10, 20);
+/// record.runs.push(RunRecord::new(Problem::Mvc, "bnb", 6, Duration::from_millis(1500), false, ""));
+/// db.insert(record);
+///
+/// let report = Report::new(&db, &Report::default_series(&db));
+/// assert!(report.to_markdown().contains("| a.clq | 10 | 0.444 | - | - | 6 / 1.50 s / solved |"));
+/// assert!(report.cactus_plot_svg().starts_with("<svg"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub series: Vec<String>,
+    /// Instances having at least one run in one of the series
+    pub rows: Vec<ReportRow>,
+}
+
+impl Report {
+    /// Builds a report from the runs of the database. For each instance and series, the last run is kept.
+    pub fn new(db: &InstanceDatabase, series: &[Series]) -> Report {
+        let mut rows = Vec::new();
+        for record in db.instances() {
+            let runs: Vec<Option<RunRecord>> = series.iter()
+                .map(|s| record.runs.iter()
+                    .filter(|r| s.selector.matches(r))
+                    .max_by(|a, b| a.date.cmp(&b.date))
+                    .cloned())
+                .collect();
+            if runs.iter().all(Option::is_none) {
+                continue;
+            }
+            rows.push(ReportRow {
+                id: record.id.clone(),
+                order: record.order,
+                density: record.density(),
+                known_mvc: record.mvc.as_ref().map(|k| k.value),
+                known_clique: record.clique.as_ref().map(|k| k.value),
+                runs,
+            });
+        }
+        Report {
+            series: series.iter().map(|s| s.label.clone()).collect(),
+            rows,
+        }
+    }
+
+    /// Returns one series per algorithm found in the runs of the database.
+    pub fn default_series(db: &InstanceDatabase) -> Vec<Series> {
+        let mut algorithms: Vec<&str> = db.instances().iter()
+            .flat_map(|r| r.runs.iter().map(|run| run.algorithm.as_str()))
+            .collect();
+        algorithms.sort();
+        algorithms.dedup();
+        algorithms.into_iter()
+            .map(|a| Series::new(a, RunSelector { algorithm: Some(a.to_string()), ..Default::default() }))
+            .collect()
+    }
+
+    /// Renders the report as a Markdown table.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("| instance | order | density | mvc | clique |");
+        for label in &self.series {
+            let _ = write!(md, " {} |", label.replace('|', "\\|"));
+        }
+        md.push_str("\n|---|---:|---:|---:|---:|");
+        md.push_str(&"---|".repeat(self.series.len()));
+        md.push('\n');
+        for row in &self.rows {
+            let _ = write!(md, "| {} | {} | {:.3} | {} | {} |", row.id, row.order, row.density,
+                           optional(row.known_mvc), optional(row.known_clique));
+            for run in &row.runs {
+                let _ = write!(md, " {} |", run_cell(run));
+            }
+            md.push('\n');
+        }
+        md
+    }
+
+    /// Renders the report as a standalone HTML page, with the table and the two plots.
+    pub fn to_html(&self, title: &str) -> String {
+        let mut html = String::new();
+        let _ = write!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n", escape(title));
+        html.push_str("<style>\nbody { font-family: sans-serif; margin: 2em; }\n\
+                       table { border-collapse: collapse; }\n\
+                       th, td { border: 1px solid #ccc; padding: 4px 8px; }\n\
+                       td.num { text-align: right; }\n\
+                       td.time_limit, td.node_limit, td.cancelled, td.killed { background: #fff3cd; }\n\
+                       td.crashed { background: #f8d7da; }\n</style>\n</head>\n<body>\n");
+        let _ = writeln!(html, "<h1>{}</h1>", escape(title));
+        html.push_str("<table>\n<tr><th>instance</th><th>order</th><th>density</th><th>mvc</th><th>clique</th>");
+        for label in &self.series {
+            let _ = write!(html, "<th>{}</th>", escape(label));
+        }
+        html.push_str("</tr>\n");
+        for row in &self.rows {
+            let _ = write!(html, "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{:.3}</td>\
+                                  <td class=\"num\">{}</td><td class=\"num\">{}</td>",
+                           escape(&row.id), row.order, row.density, optional(row.known_mvc), optional(row.known_clique));
+            for run in &row.runs {
+                let class = run.as_ref().map_or("", |r| r.status.name());
+                let _ = write!(html, "<td class=\"{}\">{}</td>", class, escape(&run_cell(run)));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n<h2>Cactus plot</h2>\n");
+        html.push_str(&self.cactus_plot_svg());
+        html.push_str("\n<h2>Performance profile</h2>\n");
+        html.push_str(&self.performance_profile_svg());
+        html.push_str("\n</body>\n</html>\n");
+        html
+    }
+
+    /// Times (in seconds) of the solved runs of a series.
+    fn solved_times(&self, series: usize) -> Vec<f64> {
+        self.rows.iter()
+            .filter_map(|row| row.runs[series].as_ref())
+            .filter(|run| run.status == RunStatus::Solved)
+            .map(|run| seconds(run.time))
+            .collect()
+    }
+
+    /// Renders the cactus plot of the report as SVG : the x axis is the number of solved instances and the
+    /// y axis (logarithmic) is the time needed to solve them.
+    pub fn cactus_plot_svg(&self) -> String {
+        let mut curves: Vec<Vec<f64>> = (0..self.series.len()).map(|s| self.solved_times(s)).collect();
+        curves.iter_mut().for_each(|c| c.sort_by(f64::total_cmp));
+        let max_solved = curves.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let all_times = curves.iter().flatten().copied();
+        let (y_min, y_max) = log_range(all_times);
+
+        let mut plot = Plot::new("instances solved", "time (s)");
+        plot.x_axis(0.0, max_solved as f64, &linear_ticks(max_solved));
+        plot.y_axis_log(y_min, y_max);
+        for (s, curve) in curves.iter().enumerate() {
+            let points: Vec<(f64, f64)> = curve.iter().enumerate()
+                .map(|(i, t)| ((i + 1) as f64, t.log10()))
+                .collect();
+            plot.polyline(s, &points, true);
+        }
+        plot.legend(&self.series, true);
+        plot.finish()
+    }
+
+    /// Renders the performance profile of the report as SVG : for each series, the fraction of the instances
+    /// (solved by at least one series) solved within a factor τ of the best time, with τ on a log2 scale.
+    pub fn performance_profile_svg(&self) -> String {
+        // Ratio of each series to the best time of each instance (None if not solved)
+        let mut ratios: Vec<Vec<f64>> = vec![Vec::new(); self.series.len()];
+        let mut instances = 0;
+        for row in &self.rows {
+            let times: Vec<Option<f64>> = row.runs.iter()
+                .map(|r| r.as_ref().filter(|r| r.status == RunStatus::Solved).map(|r| seconds(r.time)))
+                .collect();
+            let best = times.iter().flatten().copied().fold(f64::INFINITY, f64::min);
+            if best.is_infinite() {
+                continue;
+            }
+            instances += 1;
+            for (s, time) in times.iter().enumerate() {
+                if let Some(time) = time {
+                    ratios[s].push(time / best);
+                }
+            }
+        }
+        ratios.iter_mut().for_each(|r| r.sort_by(f64::total_cmp));
+        let max_log = ratios.iter().flatten().map(|r| r.log2()).fold(1.0_f64, f64::max).ceil();
+
+        let mut plot = Plot::new("log2(τ)", "fraction of instances");
+        plot.x_axis(0.0, max_log, &linear_ticks(max_log as usize));
+        plot.y_axis(0.0, 1.0, &[0.0, 0.25, 0.5, 0.75, 1.0]);
+        for (s, r) in ratios.iter().enumerate() {
+            // Step function starting at τ = 1
+            let mut points = vec![(0.0, 0.0)];
+            for (i, ratio) in r.iter().enumerate() {
+                let x = ratio.log2();
+                points.push((x, points.last().unwrap().1));
+                points.push((x, (i + 1) as f64 / instances.max(1) as f64));
+            }
+            points.push((max_log, points.last().unwrap().1));
+            plot.polyline(s, &points, false);
+        }
+        plot.legend(&self.series, false);
+        plot.finish()
+    }
+}
+
+/// SVG plot under construction.
+struct Plot {
+    svg: String,
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+}
+
+impl Plot {
+    fn new(x_label: &str, y_label: &str) -> Plot {
+        let mut svg = String::new();
+        let _ = write!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+                            font-family=\"sans-serif\" font-size=\"12\">\n\
+                            <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n", w = WIDTH, h = HEIGHT);
+        let _ = writeln!(svg, "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                         (MARGIN + WIDTH - MARGIN / 2.0) / 2.0, HEIGHT - 15.0, escape(x_label));
+        let _ = writeln!(svg, "<text x=\"15\" y=\"{y}\" text-anchor=\"middle\" transform=\"rotate(-90 15 {y})\">{}</text>",
+                         escape(y_label), y = HEIGHT / 2.0);
+        let _ = writeln!(svg, "<rect x=\"{m}\" y=\"{t}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>",
+                         WIDTH - 1.5 * MARGIN, HEIGHT - 1.5 * MARGIN, m = MARGIN, t = MARGIN / 2.0);
+        Plot { svg, x_range: (0.0, 1.0), y_range: (0.0, 1.0) }
+    }
+
+    fn x(&self, x: f64) -> f64 {
+        let (min, max) = self.x_range;
+        MARGIN + (x - min) / (max - min) * (WIDTH - 1.5 * MARGIN)
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        let (min, max) = self.y_range;
+        HEIGHT - MARGIN - (y - min) / (max - min) * (HEIGHT - 1.5 * MARGIN)
+    }
+
+    fn x_axis(&mut self, min: f64, max: f64, ticks: &[f64]) {
+        self.x_range = (min, if max > min { max } else { min + 1.0 });
+        for &tick in ticks {
+            let x = self.x(tick);
+            let _ = writeln!(self.svg, "<line x1=\"{x:.1}\" y1=\"{}\" x2=\"{x:.1}\" y2=\"{}\" stroke=\"#ddd\"/>\
+                                        <text x=\"{x:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                             MARGIN / 2.0, HEIGHT - MARGIN, HEIGHT - MARGIN + 15.0, tick);
+        }
+    }
+
+    fn y_axis(&mut self, min: f64, max: f64, ticks: &[f64]) {
+        self.y_range = (min, if max > min { max } else { min + 1.0 });
+        for &tick in ticks {
+            self.y_tick(tick, &tick.to_string());
+        }
+    }
+
+    /// Logarithmic y axis : the values given to the plot are log10 of the data, between two powers of 10.
+    fn y_axis_log(&mut self, min_exp: i32, max_exp: i32) {
+        self.y_range = (min_exp as f64, max_exp as f64);
+        for exp in min_exp..=max_exp {
+            self.y_tick(exp as f64, &format!("1e{}", exp));
+        }
+    }
+
+    fn y_tick(&mut self, value: f64, label: &str) {
+        let y = self.y(value);
+        let _ = writeln!(self.svg, "<line x1=\"{}\" y1=\"{y:.1}\" x2=\"{}\" y2=\"{y:.1}\" stroke=\"#ddd\"/>\
+                                    <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+                         MARGIN, WIDTH - MARGIN / 2.0, MARGIN - 5.0, y + 4.0, label);
+    }
+
+    fn polyline(&mut self, series: usize, points: &[(f64, f64)], markers: bool) {
+        let color = COLORS[series % COLORS.len()];
+        let coords: Vec<String> = points.iter().map(|&(x, y)| format!("{:.1},{:.1}", self.x(x), self.y(y))).collect();
+        let _ = writeln!(self.svg, "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"2\" points=\"{}\"/>",
+                         color, coords.join(" "));
+        if markers {
+            for &(x, y) in points {
+                let _ = writeln!(self.svg, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{}\"/>", self.x(x), self.y(y), color);
+            }
+        }
+    }
+
+    /// Draws the legend in the top left corner, or in the bottom right corner.
+    fn legend(&mut self, labels: &[String], top_left: bool) {
+        for (s, label) in labels.iter().enumerate() {
+            let (x, y) = if top_left {
+                (MARGIN + 10.0, MARGIN / 2.0 + 15.0 + 18.0 * s as f64)
+            } else {
+                (WIDTH - MARGIN / 2.0 - 150.0, HEIGHT - MARGIN - 10.0 - 18.0 * (labels.len() - 1 - s) as f64)
+            };
+            let _ = writeln!(self.svg, "<rect x=\"{x}\" y=\"{}\" width=\"12\" height=\"12\" fill=\"{}\"/>\
+                                        <text x=\"{}\" y=\"{y}\">{}</text>",
+                             y - 10.0, COLORS[s % COLORS.len()], x + 18.0, escape(label));
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.svg.push_str("</svg>\n");
+        self.svg
+    }
+}
+
+fn seconds(duration: Duration) -> f64 {
+    // Avoid null times, which have no logarithm
+    duration.as_secs_f64().max(1e-6)
+}
+
+/// Returns the powers of 10 surrounding the values.
+fn log_range<I: Iterator<Item=f64>>(values: I) -> (i32, i32) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if min.is_infinite() {
+        return (0, 1);
+    }
+    let (lo, hi) = (min.log10().floor() as i32, max.log10().ceil() as i32);
+    (lo, hi.max(lo + 1))
+}
+
+/// Returns at most 10 integer ticks between 0 and max.
+fn linear_ticks(max: usize) -> Vec<f64> {
+    let step = max.div_ceil(10).max(1);
+    (0..=max.max(1)).step_by(step).map(|t| t as f64).collect()
+}
+
+fn optional(value: Option<u64>) -> String {
+    value.map_or("-".to_string(), |v| v.to_string())
+}
+
+/// Formats a duration with a unit adapted to its magnitude.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs >= 1.0 {
+        format!("{:.2} s", secs)
+    } else if secs >= 1e-3 {
+        format!("{:.2} ms", secs * 1e3)
+    } else {
+        format!("{} µs", duration.as_micros())
+    }
+}
+
+fn run_cell(run: &Option<RunRecord>) -> String {
+    match run {
+        Some(run) => format!("{} / {} / {}", optional(run.value), format_duration(run.time), run.status),
+        None => "-".to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod report_tests {
+    use crate::database::{InstanceRecord, Problem};
+
+    use super::*;
+
+    fn database() -> InstanceDatabase {
+        let mut db = InstanceDatabase::new();
+        for (i, (fast, slow)) in [(1, 4), (2, 2), (8, 0)].iter().enumerate() {
+            let mut record = InstanceRecord::new(&format!("g{}.clq", i), "clq", 10, 20);
+            record.runs.push(RunRecord::new(Problem::Mvc, "fast", 6, Duration::from_secs(*fast), false, ""));
+            if *slow > 0 {
+                record.runs.push(RunRecord::new(Problem::Mvc, "slow", 6, Duration::from_secs(*slow), false, ""));
+            } else {
+                record.runs.push(RunRecord::new(Problem::Mvc, "slow", 7, Duration::from_secs(60), true, ""));
+            }
+            db.insert(record);
+        }
+        db
+    }
+
+    #[test]
+    fn test_tables() {
+        let db = database();
+        let report = Report::new(&db, &Report::default_series(&db));
+        assert_eq!(report.series, vec!["fast", "slow"]);
+        let md = report.to_markdown();
+        assert_eq!(md.lines().count(), 2 + 3);
+        assert!(md.contains("| g2.clq | 10 | 0.444 | - | - | 6 / 8.00 s / solved | 7 / 60.00 s / time limit |"));
+
+        let html = Report::new(&db, &[Series::new("<fast>", "algorithm=fast".parse().unwrap())]).to_html("Test & report");
+        assert!(html.contains("<title>Test &amp; report</title>"));
+        assert!(html.contains("<th>&lt;fast&gt;</th>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+    }
+
+    #[test]
+    fn test_performance_profile() {
+        let db = database();
+        let svg = Report::new(&db, &Report::default_series(&db)).performance_profile_svg();
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.ends_with("</svg>\n"));
+        // "fast" reaches 1 (every instance solved), "slow" stops at 2/3
+        let y_one = HEIGHT - MARGIN - (HEIGHT - 1.5 * MARGIN);
+        let y_two_thirds = HEIGHT - MARGIN - 2.0 / 3.0 * (HEIGHT - 1.5 * MARGIN);
+        let lines: Vec<&str> = svg.lines().filter(|l| l.starts_with("<polyline")).collect();
+        assert!(lines[0].trim_end_matches("\"/>").ends_with(&format!(",{:.1}", y_one)));
+        assert!(lines[1].trim_end_matches("\"/>").ends_with(&format!(",{:.1}", y_two_thirds)));
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = Report::new(&InstanceDatabase::new(), &[]);
+        assert!(report.rows.is_empty());
+        assert!(report.cactus_plot_svg().contains("</svg>"));
+        assert!(report.performance_profile_svg().contains("</svg>"));
+    }
+}
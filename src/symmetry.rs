@@ -0,0 +1,244 @@
+//! Symmetries of a graph, used by the branch and bound to skip the subtrees that are equivalent up to an automorphism.
+//!
+//! The vertices are first partitioned by colour refinement : 2 vertices get the same colour if they have the same
+//! number of neighbours of each colour, until the partition is stable. Vertices of different colours are never
+//! exchanged by an automorphism. The automorphisms are then searched by individualisation and refinement, with a budget
+//! per orbit : a vertex whose automorphism is not found within the budget is left out of the orbit, so the orbits
+//! returned are always exact or smaller than the true orbits.
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use petgraph::prelude::UnGraphMap;
+
+/// Maximum number of refinements spent on the orbit of a vertex, shared by all the vertices it may be mapped to.
+pub const SEARCH_BUDGET: usize = 256;
+/// Depth of the branch and bound from which the orbits are not searched anymore. It keeps the searches at the top of
+/// the tree, where they prune the largest subtrees, and bounds their number to 2^MAX_DEPTH.
+pub const MAX_DEPTH: usize = 16;
+
+/// Graph with its vertices numbered from 0, sorted by label.
+struct IndexedGraph {
+    vertices: Vec<u64>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl IndexedGraph {
+    fn new(graph: &UnGraphMap<u64, ()>) -> IndexedGraph {
+        let mut vertices: Vec<u64> = graph.nodes().collect();
+        vertices.sort_unstable();
+        let index: HashMap<u64, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let adjacency = vertices.iter()
+            .map(|v| graph.neighbors(*v).map(|u| index[&u]).collect())
+            .collect();
+        IndexedGraph { vertices, adjacency }
+    }
+
+    /// Refines the colourings together until they are stable, so that their colours can be compared : the new colours
+    /// are numbered in the order of the previous colour and of the colours of the neighbours.
+    fn refine(&self, colorings: &mut [Vec<usize>]) {
+        let mut classes = count_classes(colorings);
+        loop {
+            let signatures: Vec<Vec<(usize, Vec<usize>)>> = colorings.iter()
+                .map(|colors| (0..self.vertices.len())
+                    .map(|v| {
+                        let mut neighbors: Vec<usize> = self.adjacency[v].iter().map(|u| colors[*u]).collect();
+                        neighbors.sort_unstable();
+                        (colors[v], neighbors)
+                    })
+                    .collect())
+                .collect();
+            let ids: BTreeMap<&(usize, Vec<usize>), usize> = signatures.iter().flatten()
+                .map(|signature| (signature, 0))
+                .collect::<BTreeMap<_, _>>()
+                .into_keys()
+                .enumerate()
+                .map(|(id, signature)| (signature, id))
+                .collect();
+            for (colors, signatures) in colorings.iter_mut().zip(signatures.iter()) {
+                for (color, signature) in colors.iter_mut().zip(signatures) {
+                    *color = ids[signature];
+                }
+            }
+            let refined = count_classes(colorings);
+            if refined == classes {
+                return;
+            }
+            classes = refined;
+        }
+    }
+
+    /// Searches an automorphism mapping the colouring `a` to the colouring `b`. Returns the image of each vertex.
+    fn find_automorphism(&self, a: Vec<usize>, b: Vec<usize>, budget: &mut usize) -> Option<Vec<usize>> {
+        if *budget == 0 {
+            return None;
+        }
+        *budget -= 1;
+        let mut colorings = [a, b];
+        self.refine(&mut colorings);
+        let [a, b] = colorings;
+        let (sizes_a, sizes_b) = (class_sizes(&a), class_sizes(&b));
+        if sizes_a != sizes_b {
+            return None;
+        }
+
+        match sizes_a.iter().find(|(_, size)| **size > 1) {
+            None => {
+                // Every vertex has its own colour : the mapping is given by the colours
+                let position: HashMap<usize, usize> = b.iter().enumerate().map(|(v, color)| (*color, v)).collect();
+                let image: Vec<usize> = a.iter().map(|color| position[color]).collect();
+                let is_automorphism = (0..image.len()).all(|v| self.adjacency[v].iter()
+                    .all(|u| self.adjacency[image[v]].contains(&image[*u])));
+                is_automorphism.then_some(image)
+            }
+            Some((&cell, _)) => {
+                // Individualises the first vertex of the cell in a and tries each vertex of the cell in b
+                let new_color = self.vertices.len();
+                let x = a.iter().position(|color| *color == cell)?;
+                for y in (0..b.len()).filter(|y| b[*y] == cell) {
+                    let (mut a, mut b) = (a.clone(), b.clone());
+                    a[x] = new_color;
+                    b[y] = new_color;
+                    if let Some(image) = self.find_automorphism(a, b, budget) {
+                        return Some(image);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+fn count_classes(colorings: &[Vec<usize>]) -> usize {
+    colorings.iter().flatten().collect::<HashSet<_>>().len()
+}
+
+fn class_sizes(colors: &[usize]) -> BTreeMap<usize, usize> {
+    let mut sizes = BTreeMap::new();
+    for color in colors {
+        *sizes.entry(*color).or_insert(0) += 1;
+    }
+    sizes
+}
+
+/// Returns the colour of each vertex once the colouring by degree is refined until it is stable (the colours are
+/// numbered from 0).
+///
+/// # Example
+/// ```rust
+/// use petgraph::prelude::UnGraphMap;
+/// use vertex::symmetry::color_refinement;
+///
+/// // A path of 5 vertices : the ends, their neighbours and the middle vertex
+/// let graph = UnGraphMap::<u64, ()>::from_edges([(0, 1), (1, 2), (2, 3), (3, 4)]);
+/// let colors = color_refinement(&graph);
+/// assert_eq!(colors[&0], colors[&4]);
+/// assert_eq!(colors[&1], colors[&3]);
+/// assert_ne!(colors[&0], colors[&1]);
+/// assert_ne!(colors[&1], colors[&2]);
+/// ```
+pub fn color_refinement(graph: &UnGraphMap<u64, ()>) -> HashMap<u64, usize> {
+    let indexed = IndexedGraph::new(graph);
+    let mut colorings = [vec![0; indexed.vertices.len()]];
+    indexed.refine(&mut colorings);
+    indexed.vertices.iter().copied().zip(colorings[0].iter().copied()).collect()
+}
+
+/// Returns the orbit of a vertex : the vertices it is mapped to by the automorphisms of the graph (the vertex itself
+/// included, sorted). The orbit can miss vertices whose automorphism was not found before [`SEARCH_BUDGET`]
+/// refinements were spent.
+///
+/// # Example
+/// ```rust
+/// use petgraph::prelude::UnGraphMap;
+/// use vertex::symmetry::orbit;
+///
+/// // A triangle with a pendant vertex on 0 : 1 and 2 are symmetric
+/// let graph = UnGraphMap::<u64, ()>::from_edges([(0, 1), (1, 2), (2, 0), (0, 3)]);
+/// assert_eq!(orbit(&graph, 1), vec![1, 2]);
+/// assert_eq!(orbit(&graph, 0), vec![0]);
+/// ```
+pub fn orbit(graph: &UnGraphMap<u64, ()>, v: u64) -> Vec<u64> {
+    let indexed = IndexedGraph::new(graph);
+    let v = match indexed.vertices.binary_search(&v) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let mut colorings = [vec![0; indexed.vertices.len()]];
+    indexed.refine(&mut colorings);
+    let [base] = colorings;
+
+    let mut orbit = HashSet::from([v]);
+    let mut generators: Vec<Vec<usize>> = Vec::new();
+    let mut budget = SEARCH_BUDGET;
+    let new_color = indexed.vertices.len();
+    for u in (0..base.len()).filter(|u| base[*u] == base[v]) {
+        if budget == 0 {
+            break;
+        }
+        if orbit.contains(&u) {
+            continue;
+        }
+        let (mut a, mut b) = (base.clone(), base.clone());
+        a[v] = new_color;
+        b[u] = new_color;
+        if let Some(image) = indexed.find_automorphism(a, b, &mut budget) {
+            generators.push(image);
+            // The orbit is closed under the automorphisms found so far
+            let mut stack: Vec<usize> = orbit.iter().copied().collect();
+            while let Some(w) = stack.pop() {
+                for generator in &generators {
+                    if orbit.insert(generator[w]) {
+                        stack.push(generator[w]);
+                    }
+                }
+            }
+        }
+    }
+    let mut orbit: Vec<u64> = orbit.into_iter().map(|u| indexed.vertices[u]).collect();
+    orbit.sort_unstable();
+    orbit
+}
+
+#[cfg(test)]
+mod symmetry_tests {
+    use crate::graph_utils::load_clq_file;
+
+    use super::*;
+
+    #[test]
+    fn test_vertex_transitive_graph() {
+        let graph = load_clq_file("src/resources/graphs/test_cycle_5.clq").unwrap();
+        let v = graph.nodes().min().unwrap();
+        assert_eq!(orbit(&graph, v).len(), 5);
+    }
+
+    #[test]
+    fn test_orbit_of_a_corner() {
+        // The corners of the board are symmetric, but not the other squares
+        let graph = load_clq_file("src/resources/graphs/queen5_5.clq").unwrap();
+        let v = graph.nodes().min().unwrap();
+        assert_eq!(orbit(&graph, v).len(), 4);
+    }
+
+    #[test]
+    fn test_regular_graph_without_symmetry() {
+        // The Frucht graph is 3-regular but has no automorphism other than the identity : the colour refinement does
+        // not tell its vertices apart, the search does
+        let graph = UnGraphMap::<u64, ()>::from_edges([
+            (0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 7), (7, 8), (8, 9), (9, 10), (10, 11), (11, 0),
+            (0, 7), (1, 11), (2, 10), (3, 5), (4, 9), (6, 8)]);
+        assert_eq!(graph.nodes().map(|v| graph.neighbors(v).count()).max(), Some(3));
+        let colors = color_refinement(&graph);
+        assert_eq!(colors.values().collect::<HashSet<_>>().len(), 1);
+        for v in graph.nodes() {
+            assert_eq!(orbit(&graph, v), vec![v]);
+        }
+    }
+
+    #[test]
+    fn test_star() {
+        let graph = UnGraphMap::<u64, ()>::from_edges([(0, 1), (0, 2), (0, 3)]);
+        assert_eq!(orbit(&graph, 0), vec![0]);
+        assert_eq!(orbit(&graph, 2), vec![1, 2, 3]);
+        assert!(orbit(&graph, 7).is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+//! Locations of the files used by the project (instances, database and legacy YAML files).
+//!
+//! By default, everything is located in the src/resources folder of the repository. Another location
+//! can be given with the `MVC_WORKSPACE` environment variable or the `--workspace` flag of the `mvc` command.
+//! Both accept either a resources folder (with the same layout as src/resources) or a YAML configuration file :
+//! ```yaml
+//! instance_dir: graphs          # folder containing the graph files
+//! database: instances.yml       # instance database
+//! graph_data: graph_data.yml    # legacy files, only read by `mvc db migrate`
+//! clique_data: clique_data.yml
+//! time_result: time_result.yml
+//! ```
+//! Every key is optional and relative paths are resolved from the folder of the configuration file.
+//! If a resources folder contains a `mvc.yml` file, it is used as the configuration file.
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::errors::YamlError;
+
+/// Name of the environment variable used to locate the workspace.
+pub const WORKSPACE_ENV_VAR: &str = "MVC_WORKSPACE";
+/// Name of the configuration file looked for in a resources folder.
+pub const CONFIG_FILE_NAME: &str = "mvc.yml";
+
+/// Locations of the files used by the project. See the module documentation.
+///
+/// # Example
+/// ```rust
+/// use std::path::PathBuf;
+/// use vertex::workspace::Workspace;
+///
+/// let ws = Workspace::new("data");
+/// assert_eq!(ws.instance_path("test.clq"), PathBuf::from("data/graphs/test.clq"));
+/// assert_eq!(ws.database, PathBuf::from("data/instances.yml"));
+///
+/// let default = Workspace::default();
+/// assert_eq!(default.database, PathBuf::from("src/resources/instances.yml"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    /// Folder containing the graph files
+    pub instance_dir: PathBuf,
+    /// Instance database (see the database module)
+    pub database: PathBuf,
+    /// Legacy file containing the known values of the minimum vertex cover
+    pub graph_data: PathBuf,
+    /// Legacy file containing the known values of the maximum clique
+    pub clique_data: PathBuf,
+    /// Legacy file containing the times of the runs
+    pub time_result: PathBuf,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Workspace::new("src/resources")
+    }
+}
+
+/// Content of a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkspaceConfig {
+    instance_dir: Option<PathBuf>,
+    database: Option<PathBuf>,
+    graph_data: Option<PathBuf>,
+    clique_data: Option<PathBuf>,
+    time_result: Option<PathBuf>,
+}
+
+impl Workspace {
+    /// Creates a workspace from a resources folder with the same layout as src/resources.
+    pub fn new<P: AsRef<Path>>(resources_dir: P) -> Workspace {
+        let dir = resources_dir.as_ref();
+        Workspace {
+            instance_dir: dir.join("graphs"),
+            database: dir.join("instances.yml"),
+            graph_data: dir.join("graph_data.yml"),
+            clique_data: dir.join("clique_data.yml"),
+            time_result: dir.join("time_result.yml"),
+        }
+    }
+
+    /// Creates a workspace from a YAML configuration file.
+    ///
+    /// The missing keys take the location they have in a resources folder located next to the configuration file.
+    ///
+    /// # Throws
+    /// - YamlError::IoError if the file cannot be opened
+    /// - YamlError::YAMLParsingError if the file is not a valid configuration
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Workspace, YamlError> {
+        let path = path.as_ref();
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => return Err(YamlError::IoError(format!("unable to open file {:?}", path), e))
+        };
+        let config: WorkspaceConfig = serde_yaml::from_reader(file)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let default = Workspace::new(dir);
+        let resolve = |p: Option<PathBuf>, default: PathBuf| p.map_or(default, |p| dir.join(p));
+        Ok(Workspace {
+            instance_dir: resolve(config.instance_dir, default.instance_dir),
+            database: resolve(config.database, default.database),
+            graph_data: resolve(config.graph_data, default.graph_data),
+            clique_data: resolve(config.clique_data, default.clique_data),
+            time_result: resolve(config.time_result, default.time_result),
+        })
+    }
+
+    /// Creates a workspace from a path that is either a configuration file or a resources folder.
+    ///
+    /// # Throws
+    /// YamlError if the configuration file cannot be read.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Workspace, YamlError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            let config = path.join(CONFIG_FILE_NAME);
+            if config.is_file() {
+                Workspace::from_file(config)
+            } else {
+                Ok(Workspace::new(path))
+            }
+        } else {
+            Workspace::from_file(path)
+        }
+    }
+
+    /// Creates a workspace from the `MVC_WORKSPACE` environment variable, or the default workspace if it is not set.
+    ///
+    /// # Throws
+    /// YamlError if the configuration file cannot be read.
+    pub fn from_env() -> Result<Workspace, YamlError> {
+        match std::env::var_os(WORKSPACE_ENV_VAR) {
+            Some(path) if !path.is_empty() => Workspace::from_path(path),
+            _ => Ok(Workspace::default()),
+        }
+    }
+
+    /// Creates a workspace from the command line arguments of a bin.
+    ///
+    /// If the arguments contain `--workspace <path>`, both are removed from the arguments and the workspace
+    /// is created from the path. Otherwise, the workspace is created from the environment (see [`Workspace::from_env`]).
+    ///
+    /// # Throws
+    /// YamlError if the configuration file cannot be read or if the flag has no value.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::path::PathBuf;
+    /// use vertex::workspace::Workspace;
+    ///
+    /// let mut args = vec!["bnb".to_string(), "--workspace".to_string(), "src/resources".to_string(), "test.clq".to_string()];
+    /// let ws = Workspace::from_args(&mut args).unwrap();
+    /// assert_eq!(args, vec!["bnb", "test.clq"]);
+    /// assert_eq!(ws.instance_dir, PathBuf::from("src/resources/graphs"));
+    /// ```
+    pub fn from_args(args: &mut Vec<String>) -> Result<Workspace, YamlError> {
+        match args.iter().position(|a| a == "--workspace") {
+            Some(i) if i + 1 < args.len() => {
+                let path = args.remove(i + 1);
+                args.remove(i);
+                Workspace::from_path(path)
+            }
+            Some(_) => Err(YamlError::NotFound("Missing path after --workspace".to_string(),
+                                               "Usage: --workspace <config_file | resources_folder>".to_string())),
+            None => Workspace::from_env(),
+        }
+    }
+
+    /// Returns the path of an instance file from its id. Example : "test.clq"
+    pub fn instance_path(&self, id: &str) -> PathBuf {
+        self.instance_dir.join(id)
+    }
+
+    /// Returns the path of the instance database as a string.
+    pub fn database_path(&self) -> String {
+        self.database.to_string_lossy().to_string()
+    }
+}
+
+#[cfg(test)]
+mod workspace_tests {
+    use super::*;
+
+    #[test]
+    fn test_from_file() {
+        let dir = std::env::temp_dir().join(format!("mvc_workspace_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(CONFIG_FILE_NAME), "instance_dir: /data/graphs\ndatabase: db/instances.yml\n").unwrap();
+
+        let ws = Workspace::from_path(&dir).unwrap();
+        assert_eq!(ws.instance_dir, PathBuf::from("/data/graphs"));
+        assert_eq!(ws.database, dir.join("db/instances.yml"));
+        assert_eq!(ws.graph_data, dir.join("graph_data.yml"));
+
+        std::fs::write(dir.join(CONFIG_FILE_NAME), "unknown_key: 1\n").unwrap();
+        assert!(Workspace::from_path(&dir).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_from_path_without_config() {
+        let ws = Workspace::from_path("src/resources").unwrap();
+        assert_eq!(ws, Workspace::default());
+        assert!(ws.instance_path("test.clq").is_file());
+        assert!(matches!(Workspace::from_path("unknown_file.yml"), Err(YamlError::IoError(_, _))));
+    }
+
+    #[test]
+    fn test_from_args_missing_value() {
+        let mut args = vec!["bnb".to_string(), "--workspace".to_string()];
+        assert!(Workspace::from_args(&mut args).is_err());
+    }
+}